@@ -0,0 +1,50 @@
+//! Throughput of payment-only transactions, as built by payout workloads.
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+use stellar_baselib::account::{Account, AccountBehavior};
+use stellar_baselib::asset::{Asset, AssetBehavior};
+use stellar_baselib::keypair::{Keypair, KeypairBehavior};
+use stellar_baselib::network::{NetworkPassphrase, Networks};
+use stellar_baselib::operation::{self, Operation};
+use stellar_baselib::transaction_builder::{
+    build_payments_tx, TransactionBuilder, TransactionBuilderBehavior, MAX_OPERATIONS,
+};
+
+const SOURCE: &str = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+
+fn payments_100_ops(c: &mut Criterion) {
+    let destinations: Vec<String> = (0..MAX_OPERATIONS)
+        .map(|_| Keypair::random().unwrap().public_key())
+        .collect();
+    let asset = Asset::native();
+    let payments: Vec<(&str, &Asset, i64)> = destinations
+        .iter()
+        .map(|d| (d.as_str(), &asset, operation::ONE))
+        .collect();
+
+    c.bench_function("build_payments_tx/100", |b| {
+        let mut source = Account::new(SOURCE, "0").unwrap();
+        b.iter(|| {
+            build_payments_tx(&mut source, Networks::testnet(), black_box(&payments), 100).unwrap()
+        })
+    });
+
+    c.bench_function("transaction_builder/100", |b| {
+        let mut source = Account::new(SOURCE, "0").unwrap();
+        b.iter(|| {
+            let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+            builder.fee(100_u32);
+            for (destination, asset, amount) in black_box(&payments) {
+                builder.add_operation(
+                    Operation::new()
+                        .payment(destination, asset, *amount)
+                        .unwrap(),
+                );
+            }
+            builder.try_build().unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, payments_100_ops);
+criterion_main!(benches);
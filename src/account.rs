@@ -4,7 +4,10 @@
 //! number. `Account` tracks the sequence number as it is used by `TransactionBuilder`.
 //!
 use crate::asset::AssetBehavior;
+use crate::transaction::Transaction;
+use crate::transaction_builder::{BuildError, TransactionBuilder, TransactionBuilderBehavior};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::{error::Error, ops::AddAssign};
 use stellar_strkey::ed25519::{MuxedAccount, PublicKey};
 
@@ -61,6 +64,75 @@ impl AccountBehavior for Account {
     }
 }
 
+/// Locks a shared `account`.
+///
+/// The account is valid even if another thread panicked while holding the lock, so a
+/// poisoned lock is recovered instead of panicking.
+pub(crate) fn lock_account(account: &Mutex<Account>) -> MutexGuard<'_, Account> {
+    account.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An [Account] shared between threads, e.g. by the workers of a service submitting
+/// transactions from the same source.
+///
+/// Clones refer to the same account, and every transaction built with
+/// [build_transaction](Self::build_transaction) gets its own sequence number.
+#[derive(Debug, Clone)]
+pub struct SharedAccount(Arc<Mutex<Account>>);
+
+impl SharedAccount {
+    pub fn new(account: Account) -> Self {
+        Self(Arc::new(Mutex::new(account)))
+    }
+
+    pub fn account_id(&self) -> String {
+        self.lock().account_id()
+    }
+
+    pub fn sequence_number(&self) -> String {
+        self.lock().sequence_number()
+    }
+
+    /// Locks the account, e.g. to reset its sequence number after a failed submission.
+    pub fn lock(&self) -> MutexGuard<'_, Account> {
+        lock_account(&self.0)
+    }
+
+    /// Builds a transaction on `network` with this account as source, after `configure` has
+    /// set up the builder.
+    ///
+    /// The account stays locked until the transaction is built, so concurrent calls get
+    /// consecutive sequence numbers. If `configure` fails, the sequence number is unchanged.
+    pub fn build_transaction<F, E>(&self, network: &str, configure: F) -> Result<Transaction, E>
+    where
+        F: FnOnce(&mut TransactionBuilder<'_>) -> Result<(), E>,
+        E: From<BuildError>,
+    {
+        let mut account = self.lock();
+        let mut builder = TransactionBuilder::new(&mut account, network, None);
+        configure(&mut builder)?;
+        Ok(builder.try_build()?)
+    }
+}
+
+impl From<Account> for SharedAccount {
+    fn from(account: Account) -> Self {
+        Self::new(account)
+    }
+}
+
+impl From<Arc<Mutex<Account>>> for SharedAccount {
+    fn from(account: Arc<Mutex<Account>>) -> Self {
+        Self(account)
+    }
+}
+
+impl From<SharedAccount> for Arc<Mutex<Account>> {
+    fn from(account: SharedAccount) -> Self {
+        account.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,4 +180,48 @@ mod tests {
         account.increment_sequence_number();
         assert_eq!(account.sequence_number(), "103");
     }
+
+    #[test]
+    fn test_shared_account_across_threads() {
+        use crate::network::{NetworkPassphrase, Networks};
+        use crate::operation::Operation;
+
+        let shared = SharedAccount::new(Account::new(ACCOUNT, "100").unwrap());
+        let workers = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    (0..5)
+                        .map(|_| {
+                            shared
+                                .build_transaction(Networks::testnet(), |builder| {
+                                    builder
+                                        .fee(100_u32)
+                                        .add_operation(Operation::new().bump_sequence(0).unwrap())
+                                        .set_timeout(0)?;
+                                    Ok::<_, Box<dyn Error + Send + Sync>>(())
+                                })
+                                .unwrap()
+                                .sequence
+                                .unwrap()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut sequences = workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .map(|seq| seq.parse::<i64>().unwrap())
+            .collect::<Vec<_>>();
+        sequences.sort();
+        assert_eq!(sequences, (101..=120).collect::<Vec<_>>());
+        assert_eq!(shared.sequence_number(), "120");
+
+        let failed: Result<_, Box<dyn Error>> =
+            shared.build_transaction(Networks::testnet(), |_| Err("no operations".into()));
+        assert!(failed.is_err());
+        assert_eq!(shared.sequence_number(), "120");
+    }
 }
@@ -3,19 +3,21 @@ use std::str::FromStr;
 use crate::xdr;
 use stellar_strkey::{
     ed25519::{self, MuxedAccount, PublicKey},
-    Contract, Strkey,
+    ClaimableBalance, Contract, LiquidityPool, Strkey,
 };
 
 use crate::hashing::{self, HashingBehavior};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AddressType {
     Account,
     Contract,
     MuxedAccount,
+    ClaimableBalance,
+    LiquidityPool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Address {
     address_type: AddressType,
     key: Vec<u8>,
@@ -46,6 +48,16 @@ pub trait AddressTrait {
     where
         Self: Sized;
 
+    /// Creates a new claimable balance Address object from the 32 byte balance id hash.
+    fn claimable_balance(buffer: &[u8]) -> Result<Self, &'static str>
+    where
+        Self: Sized;
+
+    /// Creates a new liquidity pool Address object from the 32 byte pool id.
+    fn liquidity_pool(buffer: &[u8]) -> Result<Self, &'static str>
+    where
+        Self: Sized;
+
     /// Convert from an xdr.ScVal type.
     fn from_sc_val(sc_val: &xdr::ScVal) -> Result<Self, &'static str>
     where
@@ -86,6 +98,10 @@ impl AddressTrait for Address {
                 id.copy_from_slice(&x.id.to_be_bytes());
                 (AddressType::MuxedAccount, payload.to_vec())
             }
+            Ok(Strkey::ClaimableBalance(ClaimableBalance::V0(id))) => {
+                (AddressType::ClaimableBalance, id.to_vec())
+            }
+            Ok(Strkey::LiquidityPool(pool)) => (AddressType::LiquidityPool, pool.0.to_vec()),
 
             _ => return Err("Unsupported address type"),
         };
@@ -131,6 +147,32 @@ impl AddressTrait for Address {
         )
     }
 
+    fn claimable_balance(buffer: &[u8]) -> Result<Self, &'static str>
+    where
+        Self: Sized,
+    {
+        let id: [u8; 32] = buffer
+            .try_into()
+            .map_err(|_| "Claimable balance id is not 32 bytes long")?;
+        Ok(Self {
+            address_type: AddressType::ClaimableBalance,
+            key: id.to_vec(),
+        })
+    }
+
+    fn liquidity_pool(buffer: &[u8]) -> Result<Self, &'static str>
+    where
+        Self: Sized,
+    {
+        let id: [u8; 32] = buffer
+            .try_into()
+            .map_err(|_| "Liquidity pool id is not 32 bytes long")?;
+        Ok(Self {
+            address_type: AddressType::LiquidityPool,
+            key: id.to_vec(),
+        })
+    }
+
     fn from_sc_val(sc_val: &xdr::ScVal) -> Result<Self, &'static str>
     where
         Self: Sized,
@@ -163,7 +205,10 @@ impl AddressTrait for Address {
                 keyid.copy_from_slice(&id.to_be_bytes());
                 Self::muxed_account(&payload)
             }
-            _ => Err("Address type not supported"),
+            xdr::ScAddress::ClaimableBalance(
+                xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash(id)),
+            ) => Self::claimable_balance(id),
+            xdr::ScAddress::LiquidityPool(xdr::PoolId(xdr::Hash(id))) => Self::liquidity_pool(id),
         }
     }
 
@@ -197,6 +242,20 @@ impl AddressTrait for Address {
 
                 Strkey::MuxedAccountEd25519(MuxedAccount { id, ed25519 }).to_string()
             }
+            AddressType::ClaimableBalance => {
+                let id = self
+                    .key
+                    .last_chunk::<32>()
+                    .expect("Claimable balance id is less than 32 bytes");
+                Strkey::ClaimableBalance(ClaimableBalance::V0(*id)).to_string()
+            }
+            AddressType::LiquidityPool => {
+                let id = self
+                    .key
+                    .last_chunk::<32>()
+                    .expect("Liquidity pool id is less than 32 bytes");
+                Strkey::LiquidityPool(LiquidityPool(*id)).to_string()
+            }
         }
     }
 
@@ -234,6 +293,16 @@ impl AddressTrait for Address {
                     ed25519: xdr::Uint256(ed25519),
                 }))
             }
+            AddressType::ClaimableBalance => {
+                let id = *self.key.last_chunk::<32>().unwrap();
+                Ok(xdr::ScAddress::ClaimableBalance(
+                    xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash(id)),
+                ))
+            }
+            AddressType::LiquidityPool => {
+                let id = *self.key.last_chunk::<32>().unwrap();
+                Ok(xdr::ScAddress::LiquidityPool(xdr::PoolId(xdr::Hash(id))))
+            }
         }
     }
 
@@ -346,6 +415,33 @@ mod tests {
         assert_eq!(account.to_string(), MUXED_ADDRESS);
     }
 
+    #[test]
+    fn test_claimable_balance_and_liquidity_pool_round_trip() {
+        let balance_id = ClaimableBalance::V0([3; 32]).to_string();
+        let pool_id = LiquidityPool([5; 32]).to_string();
+
+        let balance = Address::new(&balance_id).expect("Should create claimable balance address");
+        assert_eq!(balance.to_string(), balance_id);
+        assert_eq!(balance, Address::claimable_balance(&[3; 32]).unwrap());
+        let sc_address = balance.to_sc_address().unwrap();
+        assert_eq!(sc_address, xdr::ScAddress::from_str(&balance_id).unwrap());
+        assert_eq!(Address::from_sc_address(&sc_address).unwrap(), balance);
+
+        let pool = Address::new(&pool_id).expect("Should create liquidity pool address");
+        assert_eq!(pool.to_string(), pool_id);
+        assert_eq!(pool.to_buffer(), vec![5; 32]);
+        let sc_val = pool.to_sc_val().unwrap();
+        assert_eq!(
+            sc_val,
+            xdr::ScVal::Address(xdr::ScAddress::LiquidityPool(xdr::PoolId(xdr::Hash(
+                [5; 32]
+            ))))
+        );
+        assert_eq!(Address::from_sc_val(&sc_val).unwrap(), pool);
+
+        assert!(Address::liquidity_pool(&[0; 31]).is_err());
+    }
+
     #[test]
     fn creates_address_object_for_accounts_sc_address() {
         // Decode the account public key
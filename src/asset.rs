@@ -5,17 +5,37 @@ use std::{
 
 use crate::claimant::ClaimantBehavior;
 use crate::keypair::Keypair;
+use crate::operation;
 use crate::xdr;
+use serde::{Deserialize, Serialize};
 use stellar_strkey::{
     ed25519,
     Strkey::{self, PublicKeyEd25519},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(try_from = "AssetFields")]
 pub struct Asset {
     pub code: String,
     pub issuer: Option<String>,
 }
+
+/// Serialized form of [Asset], validated by [Asset::new] when deserializing.
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+struct AssetFields {
+    code: String,
+    issuer: Option<String>,
+}
+
+impl TryFrom<AssetFields> for Asset {
+    type Error = String;
+
+    fn try_from(fields: AssetFields) -> Result<Self, Self::Error> {
+        Asset::new(&fields.code, fields.issuer.as_deref())
+    }
+}
 impl From<&Asset> for xdr::TrustLineAsset {
     fn from(value: &Asset) -> Self {
         value.to_trust_line_xdr_object()
@@ -48,6 +68,11 @@ pub trait AssetBehavior {
     fn to_xdr_object(&self) -> xdr::Asset;
     fn to_change_trust_xdr_object(&self) -> xdr::ChangeTrustAsset;
     fn to_trust_line_xdr_object(&self) -> xdr::TrustLineAsset;
+    /// Like [to_xdr_object](Self::to_xdr_object), but fails instead of panicking when the
+    /// code or issuer is invalid, e.g. after setting the fields directly.
+    fn try_to_xdr_object(&self) -> Result<xdr::Asset, operation::Error>;
+    fn try_to_change_trust_xdr_object(&self) -> Result<xdr::ChangeTrustAsset, operation::Error>;
+    fn try_to_trust_line_xdr_object(&self) -> Result<xdr::TrustLineAsset, operation::Error>;
     fn ascii_compare(a: &str, b: &str) -> i32;
     fn native() -> Self
     where
@@ -62,6 +87,9 @@ pub trait AssetBehavior {
     fn get_code(&self) -> Option<String>;
     fn get_issuer(&self) -> Option<String>;
     fn to_string_asset(&self) -> String;
+    /// Returns the `C...` address of the Stellar Asset Contract of this asset on the network
+    /// with passphrase `network_passphrase`. The contract need not be deployed yet.
+    fn contract_id(&self, network_passphrase: &str) -> String;
 }
 
 impl AssetBehavior for Asset {
@@ -77,7 +105,7 @@ impl AssetBehavior for Asset {
         }
 
         if let Some(issuer) = issuer {
-            if Strkey::from_str(issuer).is_err() {
+            if crate::operation::parse_account_id(issuer, "issuer").is_err() {
                 return Err("Not a valid ed25519 public key".to_string());
             }
         }
@@ -112,85 +140,59 @@ impl AssetBehavior for Asset {
     }
 
     fn to_trust_line_xdr_object(&self) -> xdr::TrustLineAsset {
-        if self.is_native() {
-            xdr::TrustLineAsset::Native
-        } else if self.code.len() <= 4 {
-            let asset_code = xdr::AssetCode4::from_str(&self.code).expect("Asset code is invalid");
-            let issuer = xdr::AccountId::from_str(
-                &self
-                    .issuer
-                    .clone()
-                    .expect("Issuer is None while not native"),
-            )
-            .expect("Issuer is invalid");
-
-            xdr::TrustLineAsset::CreditAlphanum4(xdr::AlphaNum4 { asset_code, issuer })
-        } else {
-            let asset_code = xdr::AssetCode12::from_str(&self.code).expect("Asset code is invalid");
-            let issuer = xdr::AccountId::from_str(
-                &self
-                    .issuer
-                    .clone()
-                    .expect("Issuer is None while not native"),
-            )
-            .expect("Issuer is invalid");
-
-            xdr::TrustLineAsset::CreditAlphanum12(xdr::AlphaNum12 { asset_code, issuer })
-        }
+        self.try_to_trust_line_xdr_object()
+            .expect("Asset is invalid")
     }
 
     fn to_change_trust_xdr_object(&self) -> xdr::ChangeTrustAsset {
-        if self.is_native() {
-            xdr::ChangeTrustAsset::Native
-        } else if self.code.len() <= 4 {
-            let asset_code = xdr::AssetCode4::from_str(&self.code).expect("Asset code is invalid");
-            let issuer = xdr::AccountId::from_str(
-                &self
-                    .issuer
-                    .clone()
-                    .expect("Issuer is None while not native"),
-            )
-            .expect("Issuer is invalid");
-            xdr::ChangeTrustAsset::CreditAlphanum4(xdr::AlphaNum4 { asset_code, issuer })
-        } else {
-            let asset_code = xdr::AssetCode12::from_str(&self.code).expect("Asset code is invalid");
-            let issuer = xdr::AccountId::from_str(
-                &self
-                    .issuer
-                    .clone()
-                    .expect("Issuer is None while not native"),
-            )
-            .expect("Issuer is invalid");
-            xdr::ChangeTrustAsset::CreditAlphanum12(xdr::AlphaNum12 { asset_code, issuer })
-        }
+        self.try_to_change_trust_xdr_object()
+            .expect("Asset is invalid")
     }
 
     fn to_xdr_object(&self) -> xdr::Asset {
+        self.try_to_xdr_object().expect("Asset is invalid")
+    }
+
+    fn try_to_xdr_object(&self) -> Result<xdr::Asset, operation::Error> {
         if self.is_native() {
-            xdr::Asset::Native
-        } else if self.code.len() <= 4 {
-            let asset_code = xdr::AssetCode4::from_str(&self.code).expect("Asset code is invalid");
-            let issuer = xdr::AccountId::from_str(
-                &self
-                    .issuer
-                    .clone()
-                    .expect("Issuer is None while not native"),
-            )
-            .expect("Issuer is invalid");
-            xdr::Asset::CreditAlphanum4(xdr::AlphaNum4 { asset_code, issuer })
+            return Ok(xdr::Asset::Native);
+        }
+        let issuer = match &self.issuer {
+            Some(issuer) => operation::parse_account_id(issuer, "issuer")?,
+            None => return Err(operation::Error::InvalidField("issuer".into())),
+        };
+        let invalid_code = |_| operation::Error::InvalidField("code".into());
+        if self.code.len() <= 4 {
+            let asset_code = xdr::AssetCode4::from_str(&self.code).map_err(invalid_code)?;
+            Ok(xdr::Asset::CreditAlphanum4(xdr::AlphaNum4 {
+                asset_code,
+                issuer,
+            }))
         } else {
-            let asset_code = xdr::AssetCode12::from_str(&self.code).expect("Asset code is invalid");
-            let issuer = xdr::AccountId::from_str(
-                &self
-                    .issuer
-                    .clone()
-                    .expect("Issuer is None while not native"),
-            )
-            .expect("Issuer is invalid");
-            xdr::Asset::CreditAlphanum12(xdr::AlphaNum12 { asset_code, issuer })
+            let asset_code = xdr::AssetCode12::from_str(&self.code).map_err(invalid_code)?;
+            Ok(xdr::Asset::CreditAlphanum12(xdr::AlphaNum12 {
+                asset_code,
+                issuer,
+            }))
         }
     }
 
+    fn try_to_change_trust_xdr_object(&self) -> Result<xdr::ChangeTrustAsset, operation::Error> {
+        Ok(match self.try_to_xdr_object()? {
+            xdr::Asset::Native => xdr::ChangeTrustAsset::Native,
+            xdr::Asset::CreditAlphanum4(asset) => xdr::ChangeTrustAsset::CreditAlphanum4(asset),
+            xdr::Asset::CreditAlphanum12(asset) => xdr::ChangeTrustAsset::CreditAlphanum12(asset),
+        })
+    }
+
+    fn try_to_trust_line_xdr_object(&self) -> Result<xdr::TrustLineAsset, operation::Error> {
+        Ok(match self.try_to_xdr_object()? {
+            xdr::Asset::Native => xdr::TrustLineAsset::Native,
+            xdr::Asset::CreditAlphanum4(asset) => xdr::TrustLineAsset::CreditAlphanum4(asset),
+            xdr::Asset::CreditAlphanum12(asset) => xdr::TrustLineAsset::CreditAlphanum12(asset),
+        })
+    }
+
     fn ascii_compare(a: &str, b: &str) -> i32 {
         let result = a.as_bytes().cmp(b.as_bytes());
         match result {
@@ -284,8 +286,13 @@ impl AssetBehavior for Asset {
             _ => "".to_string(),
         }
     }
+
+    fn contract_id(&self, network_passphrase: &str) -> String {
+        crate::contract::contract_id_from_asset(self, network_passphrase)
+    }
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for Asset {
     fn to_string(&self) -> String {
         if self.is_native() {
@@ -300,19 +307,80 @@ impl ToString for Asset {
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_to_owned)]
 mod tests {
     use crate::xdr::WriteXdr as _;
 
     use super::Asset;
     use crate::asset::AssetBehavior;
+    use crate::network::{NetworkPassphrase, Networks};
+    use crate::operation;
     use crate::xdr;
 
+    #[test]
+    fn test_contract_id() {
+        assert_eq!(
+            Asset::native().contract_id(Networks::testnet()),
+            "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"
+        );
+        let issuer = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+        let usd = Asset::new("USD", Some(issuer)).unwrap();
+        assert_eq!(
+            usd.contract_id(Networks::testnet()),
+            "CBIMQ4GRFDK27OR6MUIK2VO2ZXRFPWATPNCAHPTUS5MSJCUB3G2MBHGV"
+        );
+        assert_ne!(
+            usd.contract_id(Networks::public()),
+            usd.contract_id(Networks::testnet())
+        );
+    }
+
+    #[test]
+    fn test_serde_json_shape() {
+        let issuer = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+        let asset = Asset::new("USD", Some(issuer)).unwrap();
+        let json = serde_json::to_value(&asset).unwrap();
+
+        assert_eq!(json, serde_json::json!({ "code": "USD", "issuer": issuer }));
+        assert_eq!(serde_json::from_value::<Asset>(json).unwrap(), asset);
+
+        let bad_issuer = serde_json::json!({ "code": "USD", "issuer": "GBAD" });
+        assert!(serde_json::from_value::<Asset>(bad_issuer).is_err());
+        let bad_code = serde_json::json!({ "code": "NOT-VALID", "issuer": issuer });
+        assert!(serde_json::from_value::<Asset>(bad_code).is_err());
+        let missing_issuer = serde_json::json!({ "code": "USD", "issuer": null });
+        assert!(serde_json::from_value::<Asset>(missing_issuer).is_err());
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(Asset)).unwrap();
+
+        assert_eq!(schema["title"], "Asset");
+        assert!(schema["properties"]["code"].is_object());
+        assert!(schema["properties"]["issuer"].is_object());
+    }
+
     #[test]
     fn test_no_issuer_for_non_xlm_asset() {
         let err_val = Asset::new("USD", None).unwrap_err();
         assert_eq!(err_val, "Issuer cannot be null");
     }
 
+    #[test]
+    fn test_invalid_issuer() {
+        let issuer = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+        let invalid = [
+            issuer.to_lowercase(),
+            "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".to_string(),
+        ];
+        for issuer in invalid {
+            let err_val = Asset::new("USD", Some(&issuer)).unwrap_err();
+            assert_eq!(err_val, "Not a valid ed25519 public key");
+        }
+    }
+
     #[test]
     fn test_invalid_asset_code() {
         let err_val = Asset::new(
@@ -344,6 +412,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_try_to_xdr_object() {
+        let issuer = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+        let asset = Asset::new("USD", Some(issuer)).unwrap();
+        assert_eq!(asset.try_to_xdr_object().unwrap(), asset.to_xdr_object());
+        assert_eq!(
+            asset.try_to_change_trust_xdr_object().unwrap(),
+            asset.to_change_trust_xdr_object()
+        );
+        assert_eq!(
+            asset.try_to_trust_line_xdr_object().unwrap(),
+            asset.to_trust_line_xdr_object()
+        );
+
+        let bad_issuer = Asset {
+            code: "USD".into(),
+            issuer: Some("GBAD".into()),
+        };
+        assert_eq!(
+            bad_issuer.try_to_xdr_object(),
+            Err(operation::Error::InvalidStrkey {
+                kind: "issuer".into(),
+                value: "GBAD".into()
+            })
+        );
+        assert!(bad_issuer.try_to_trust_line_xdr_object().is_err());
+        let bad_code = Asset {
+            code: "TOOLONGASSETCODE".into(),
+            issuer: Some(issuer.into()),
+        };
+        assert_eq!(
+            bad_code.try_to_change_trust_xdr_object(),
+            Err(operation::Error::InvalidField("code".into()))
+        );
+        let destination = "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB";
+        assert!(matches!(
+            operation::Operation::new().payment(destination, &bad_issuer, 1),
+            Err(operation::Error::InvalidStrkey { kind, .. }) if kind == "issuer"
+        ));
+    }
+
     #[test]
     fn test_native_asset_code() {
         let asset = Asset::native();
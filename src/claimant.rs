@@ -4,6 +4,9 @@ use crate::keypair::Keypair;
 use crate::keypair::KeypairBehavior;
 use crate::xdr;
 
+/// Maximum number of claimants a claimable balance can have.
+pub const MAX_CLAIMANTS: usize = 10;
+
 pub struct Claimant {
     destination: Option<String>,
     predicate: xdr::ClaimPredicate,
@@ -0,0 +1,133 @@
+//! Shared defaults for building transactions.
+use std::error::Error;
+
+use crate::decoded::Decoded;
+use crate::fee_bump_transaction::{FeeBumpTransaction, FeeBumpTransactionBehavior};
+use crate::transaction::{decode_envelope_with_limits, Transaction, TransactionBehavior};
+use crate::xdr;
+
+/// Minimum base fee per operation, in stroops.
+pub const BASE_FEE: u32 = 100;
+
+/// Policy applied by [TransactionBuilder::with_config](crate::transaction_builder::TransactionBuilderBehavior::with_config)
+/// so that the fee, network and timeout are set once instead of at every call site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Base fee per operation, in stroops.
+    pub base_fee: u32,
+    /// Passphrase of the network transactions are built for.
+    pub network_passphrase: String,
+    /// Timeout in seconds from the time of building, or `None` to leave time bounds unset.
+    pub timeout: Option<u32>,
+    /// Limits used when decoding XDR inputs.
+    pub xdr_limits: xdr::Limits,
+}
+
+impl Config {
+    /// Creates a config for `network_passphrase` with the minimum base fee, no timeout and no
+    /// XDR limits.
+    pub fn new(network_passphrase: &str) -> Self {
+        Self {
+            base_fee: BASE_FEE,
+            network_passphrase: network_passphrase.to_string(),
+            timeout: None,
+            xdr_limits: xdr::Limits::none(),
+        }
+    }
+
+    pub fn base_fee(mut self, base_fee: u32) -> Self {
+        self.base_fee = base_fee;
+        self
+    }
+
+    pub fn timeout(mut self, timeout_seconds: u32) -> Self {
+        self.timeout = Some(timeout_seconds);
+        self
+    }
+
+    pub fn xdr_limits(mut self, limits: xdr::Limits) -> Self {
+        self.xdr_limits = limits;
+        self
+    }
+
+    /// Decodes the base64 `envelope` for the configured network and XDR limits, see
+    /// [decode_envelope](crate::transaction::decode_envelope).
+    pub fn decode_envelope(&self, envelope: &str) -> Result<Decoded<Transaction>, Box<dyn Error>> {
+        decode_envelope_with_limits(envelope, &self.network_passphrase, self.xdr_limits.clone())
+    }
+
+    /// Decodes the base64 envelope `xdr` for the configured network and XDR limits, see
+    /// [try_from_xdr_envelope](TransactionBehavior::try_from_xdr_envelope).
+    pub fn transaction_from_xdr_envelope(&self, xdr: &str) -> Result<Transaction, Box<dyn Error>> {
+        Transaction::try_from_xdr_envelope_with_limits(
+            xdr,
+            &self.network_passphrase,
+            self.xdr_limits.clone(),
+        )
+    }
+
+    /// Decodes the base64 fee-bump envelope `xdr` for the configured network and XDR limits,
+    /// see [from_xdr_envelope](FeeBumpTransactionBehavior::from_xdr_envelope).
+    pub fn fee_bump_from_xdr_envelope(
+        &self,
+        xdr: &str,
+    ) -> Result<FeeBumpTransaction, Box<dyn Error>> {
+        FeeBumpTransaction::from_xdr_envelope_with_limits(
+            xdr,
+            &self.network_passphrase,
+            self.xdr_limits.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::{Account, AccountBehavior};
+    use crate::network::{NetworkPassphrase, Networks};
+    use crate::operation::Operation;
+    use crate::transaction_builder::{TransactionBuilder, TransactionBuilderBehavior};
+
+    #[test]
+    fn test_config_defaults() {
+        let config = Config::new(Networks::testnet());
+        assert_eq!(config.base_fee, BASE_FEE);
+        assert_eq!(config.network_passphrase, Networks::testnet());
+        assert_eq!(config.timeout, None);
+        assert_eq!(config.xdr_limits, xdr::Limits::none());
+
+        let config = config
+            .base_fee(500)
+            .timeout(30)
+            .xdr_limits(xdr::Limits::depth(10));
+        assert_eq!(config.base_fee, 500);
+        assert_eq!(config.timeout, Some(30));
+        assert_eq!(config.xdr_limits, xdr::Limits::depth(10));
+    }
+
+    #[test]
+    fn test_config_decodes_with_xdr_limits() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let config = Config::new(Networks::testnet());
+        let envelope = TransactionBuilder::with_config(&mut source, &config)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .try_build()
+            .unwrap()
+            .to_envelope_base64()
+            .unwrap();
+
+        let transaction = config.transaction_from_xdr_envelope(&envelope).unwrap();
+        assert_eq!(transaction.to_envelope_base64().unwrap(), envelope);
+        assert!(config.decode_envelope(&envelope).unwrap().known().is_some());
+        assert!(config.fee_bump_from_xdr_envelope(&envelope).is_err());
+
+        let config = config.xdr_limits(xdr::Limits::len(8));
+        assert!(config.transaction_from_xdr_envelope(&envelope).is_err());
+        assert!(config.decode_envelope(&envelope).is_err());
+        assert!(config.fee_bump_from_xdr_envelope(&envelope).is_err());
+    }
+}
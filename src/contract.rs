@@ -2,7 +2,14 @@ use core::str;
 use std::str::FromStr;
 
 use crate::address::{Address, AddressTrait};
+use crate::asset::{Asset, AssetBehavior};
+use crate::hashing::{HashingBehavior, Sha256Hasher};
+use crate::ledger_key;
+use crate::operation;
+use crate::scval::{self, ToScVal};
+use crate::soroban::restore_footprint_data;
 use crate::xdr;
+use crate::xdr::{Limits, WriteXdr};
 use stellar_strkey::{Contract, Strkey};
 
 #[derive(Clone, Debug)]
@@ -28,8 +35,49 @@ pub trait ContractBehavior {
     /// Invokes a contract call with the specified method and parameters.
     fn call(&self, method: &str, params: Option<Vec<xdr::ScVal>>) -> xdr::Operation; // Operation and ScVal types need to be defined.
 
+    /// Invokes a contract call carrying the authorization entries `auth`, on behalf of the
+    /// `source` account (`G...` or `M...`) if given instead of the transaction source.
+    fn call_with_auth(
+        &self,
+        method: &str,
+        params: Option<Vec<xdr::ScVal>>,
+        auth: Vec<xdr::SorobanAuthorizationEntry>,
+        source: Option<&str>,
+    ) -> Result<xdr::Operation, operation::Error>;
+
+    /// Invokes a contract call with arguments converted by [ToScVal].
+    ///
+    /// Arguments of different types are passed as trait objects, e.g.
+    /// `&[&from as &dyn ToScVal, &to, &amount]`.
+    fn call_typed<T: ToScVal>(
+        &self,
+        method: &str,
+        args: &[T],
+    ) -> Result<xdr::Operation, scval::Error>;
+
     /// Returns the read-only footprint entries necessary for invocations to this contract.
     fn get_footprint(&self) -> xdr::LedgerKey; // LedgerKey type needs to be defined.
+
+    /// Returns the ledger key of the contract instance, holding its code reference and
+    /// instance storage.
+    fn instance_key(&self) -> xdr::LedgerKey;
+
+    /// Returns the ledger key of the entry stored under `key` in persistent storage.
+    fn persistent_key(&self, key: xdr::ScVal) -> xdr::LedgerKey;
+
+    /// Returns the ledger key of the entry stored under `key` in temporary storage.
+    fn temporary_key(&self, key: xdr::ScVal) -> xdr::LedgerKey;
+
+    /// Returns a RestoreFootprint operation with the soroban data restoring the contract
+    /// instance and, given the `wasm_hash` of its executable, its code. Stellar Asset
+    /// Contracts have no code to restore.
+    ///
+    /// Resources and resource fee are left at 0, to be filled in from simulating the
+    /// transaction.
+    fn restore_footprint_op(
+        &self,
+        wasm_hash: Option<[u8; 32]>,
+    ) -> (xdr::Operation, xdr::SorobanTransactionData);
 }
 
 // Implement the trait for the Contracts struct
@@ -63,6 +111,37 @@ impl ContractBehavior for Contracts {
         }
     }
 
+    fn call_with_auth(
+        &self,
+        method: &str,
+        params: Option<Vec<xdr::ScVal>>,
+        auth: Vec<xdr::SorobanAuthorizationEntry>,
+        source: Option<&str>,
+    ) -> Result<xdr::Operation, operation::Error> {
+        let mut op = self.call(method, params);
+        if let xdr::OperationBody::InvokeHostFunction(invoke) = &mut op.body {
+            invoke.auth = auth
+                .try_into()
+                .map_err(|_| operation::Error::InvalidField("auth".into()))?;
+        }
+        op.source_account = source
+            .map(|source| operation::parse_muxed_account(source, "source"))
+            .transpose()?;
+        Ok(op)
+    }
+
+    fn call_typed<T: ToScVal>(
+        &self,
+        method: &str,
+        args: &[T],
+    ) -> Result<xdr::Operation, scval::Error> {
+        let params = args
+            .iter()
+            .map(ToScVal::to_scval)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.call(method, Some(params)))
+    }
+
     fn contract_id(&self) -> String {
         stellar_strkey::Contract(self.get_id()).to_string()
     }
@@ -76,15 +155,46 @@ impl ContractBehavior for Contracts {
     }
 
     fn get_footprint(&self) -> xdr::LedgerKey {
+        self.instance_key()
+    }
+
+    fn instance_key(&self) -> xdr::LedgerKey {
+        self.data_key(
+            xdr::ScVal::LedgerKeyContractInstance,
+            xdr::ContractDataDurability::Persistent,
+        )
+    }
+
+    fn persistent_key(&self, key: xdr::ScVal) -> xdr::LedgerKey {
+        self.data_key(key, xdr::ContractDataDurability::Persistent)
+    }
+
+    fn temporary_key(&self, key: xdr::ScVal) -> xdr::LedgerKey {
+        self.data_key(key, xdr::ContractDataDurability::Temporary)
+    }
+
+    fn restore_footprint_op(
+        &self,
+        wasm_hash: Option<[u8; 32]>,
+    ) -> (xdr::Operation, xdr::SorobanTransactionData) {
+        let mut keys = vec![self.instance_key()];
+        keys.extend(wasm_hash.map(ledger_key::contract_code));
+        let operation = operation::Operation::new()
+            .restore_footprint()
+            .expect("restore footprint takes no arguments to validate");
+        (operation, restore_footprint_data(keys))
+    }
+}
+
+impl Contracts {
+    fn data_key(&self, key: xdr::ScVal, durability: xdr::ContractDataDurability) -> xdr::LedgerKey {
         xdr::LedgerKey::ContractData(xdr::LedgerKeyContractData {
             contract: xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash(self.get_id()))),
-            key: xdr::ScVal::LedgerKeyContractInstance,
-            durability: xdr::ContractDataDurability::Persistent,
+            key,
+            durability,
         })
     }
-}
 
-impl Contracts {
     fn get_id(&self) -> [u8; 32] {
         *self
             .id
@@ -93,9 +203,47 @@ impl Contracts {
     }
 }
 
+/// Returns the `C...` id of the contract that `preimage` creates on the network with
+/// passphrase `network`.
+pub fn contract_id_from_preimage(preimage: &xdr::ContractIdPreimage, network: &str) -> String {
+    let preimage = xdr::HashIdPreimage::ContractId(xdr::HashIdPreimageContractId {
+        network_id: xdr::Hash(Sha256Hasher::hash(network)),
+        contract_id_preimage: preimage.clone(),
+    });
+    let bytes = preimage
+        .to_xdr(Limits::none())
+        .expect("preimage has no variable-length fields to overflow");
+    Contract(Sha256Hasher::hash(bytes)).to_string()
+}
+
+/// Returns the id of the contract `deployer` creates with `salt`, e.g. through
+/// [create_contract](crate::operation::Operation::create_contract).
+pub fn contract_id_from_address(
+    deployer: &str,
+    salt: [u8; 32],
+    network: &str,
+) -> Result<String, operation::Error> {
+    let address = operation::parse_sc_address(deployer, "deployer")?;
+    let preimage = xdr::ContractIdPreimage::Address(xdr::ContractIdPreimageFromAddress {
+        address,
+        salt: xdr::Uint256(salt),
+    });
+    Ok(contract_id_from_preimage(&preimage, network))
+}
+
+/// Returns the id of the Stellar Asset Contract of `asset`.
+pub fn contract_id_from_asset(asset: &Asset, network: &str) -> String {
+    contract_id_from_preimage(
+        &xdr::ContractIdPreimage::Asset(asset.to_xdr_object()),
+        network,
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use xdr::{Limits, OperationBody, WriteXdr};
+    use xdr::OperationBody;
+
+    use crate::network::{NetworkPassphrase, Networks};
 
     use super::*;
 
@@ -179,6 +327,31 @@ mod tests {
         assert_eq!(actual_footprint, expected_footprint);
     }
 
+    #[test]
+    fn test_storage_keys() {
+        let contract = Contracts::new(NULL_ADDRESS).unwrap();
+        let address = xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash(contract.get_id())));
+        let key = xdr::ScVal::Symbol("balance".try_into().unwrap());
+
+        assert_eq!(contract.instance_key(), contract.get_footprint());
+        assert_eq!(
+            contract.persistent_key(key.clone()),
+            xdr::LedgerKey::ContractData(xdr::LedgerKeyContractData {
+                contract: address.clone(),
+                key: key.clone(),
+                durability: xdr::ContractDataDurability::Persistent,
+            })
+        );
+        assert_eq!(
+            contract.temporary_key(key.clone()),
+            xdr::LedgerKey::ContractData(xdr::LedgerKeyContractData {
+                contract: address,
+                key,
+                durability: xdr::ContractDataDurability::Temporary,
+            })
+        );
+    }
+
     #[test]
     fn test_call_method_with_arguments() {
         // Define a NULL_ADDRESS equivalent
@@ -347,4 +520,110 @@ mod tests {
             panic!("Expected InvokeHostFunction operation body");
         }
     }
+
+    #[test]
+    fn test_contract_id_from_preimage() {
+        assert_eq!(
+            contract_id_from_asset(&Asset::native(), Networks::testnet()),
+            "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"
+        );
+        assert_eq!(
+            contract_id_from_asset(&Asset::native(), Networks::public()),
+            "CAS3J7GYLGXMF6TDJBBYYSE3HQ6BBSMLNUQ34T6TZMYMW2EVH34XOWMA"
+        );
+
+        let salt: [u8; 32] = core::array::from_fn(|i| i as u8);
+        assert_eq!(
+            contract_id_from_address(
+                "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB",
+                salt,
+                Networks::testnet()
+            )
+            .unwrap(),
+            "CBRUU23JLDU2UCP4FMP7Y3YNJAKLMDRS4VUFILTPCEAZBYGMPOXTB4RQ"
+        );
+        assert!(contract_id_from_address("GBAD", salt, Networks::testnet()).is_err());
+    }
+
+    #[test]
+    fn test_restore_footprint_op() {
+        let contract = Contracts::new(NULL_ADDRESS).unwrap();
+        let (operation, data) = contract.restore_footprint_op(Some([9; 32]));
+        assert!(matches!(operation.body, OperationBody::RestoreFootprint(_)));
+        assert!(data.resources.footprint.read_only.is_empty());
+        assert_eq!(
+            data.resources.footprint.read_write.to_vec(),
+            vec![contract.instance_key(), ledger_key::contract_code([9; 32])]
+        );
+
+        let (_, data) = contract.restore_footprint_op(None);
+        assert_eq!(
+            data.resources.footprint.read_write.to_vec(),
+            vec![contract.instance_key()]
+        );
+    }
+
+    #[test]
+    fn test_call_typed() {
+        let contract = Contracts::new(NULL_ADDRESS).unwrap();
+        let to = Address::new("GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB").unwrap();
+        let operation = contract
+            .call_typed("transfer", &[&to as &dyn ToScVal, &100_i128, &"memo"])
+            .unwrap();
+        assert_eq!(
+            operation,
+            contract.call(
+                "transfer",
+                Some(vec![
+                    to.to_sc_val().unwrap(),
+                    xdr::ScVal::I128(xdr::Int128Parts { hi: 0, lo: 100 }),
+                    xdr::ScVal::String(xdr::ScString("memo".try_into().unwrap())),
+                ])
+            )
+        );
+
+        let operation = contract.call_typed("sum", &[1_u32, 2]).unwrap();
+        assert_eq!(
+            operation,
+            contract.call("sum", Some(vec![xdr::ScVal::U32(1), xdr::ScVal::U32(2)]))
+        );
+        assert!(contract.call_typed::<u32>("empty", &[]).is_ok());
+    }
+
+    #[test]
+    fn test_call_with_auth() {
+        let contract = Contracts::new(NULL_ADDRESS).unwrap();
+        let source = "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB";
+        let entry = xdr::SorobanAuthorizationEntry {
+            credentials: xdr::SorobanCredentials::SourceAccount,
+            root_invocation: xdr::SorobanAuthorizedInvocation {
+                function: xdr::SorobanAuthorizedFunction::ContractFn(xdr::InvokeContractArgs {
+                    contract_address: contract.address().to_sc_address().unwrap(),
+                    function_name: "method".try_into().unwrap(),
+                    args: Default::default(),
+                }),
+                sub_invocations: Default::default(),
+            },
+        };
+
+        let op = contract
+            .call_with_auth("method", None, vec![entry.clone()], Some(source))
+            .unwrap();
+        assert_eq!(
+            op.source_account,
+            Some(xdr::MuxedAccount::from_str(source).unwrap())
+        );
+        let OperationBody::InvokeHostFunction(invoke) = op.body else {
+            panic!("Expected InvokeHostFunction operation body");
+        };
+        assert_eq!(invoke.auth.to_vec(), vec![entry]);
+
+        let op = contract
+            .call_with_auth("method", None, vec![], None)
+            .unwrap();
+        assert_eq!(op, contract.call("method", None));
+        assert!(contract
+            .call_with_auth("method", None, vec![], Some("GBAD"))
+            .is_err());
+    }
 }
@@ -0,0 +1,893 @@
+//! The interface a contract declares in its spec, and conversion of JSON values to and from the
+//! contract values its functions take and return.
+//!
+//! JSON values map to spec types as follows:
+//! - integers are numbers, or decimal strings for values that do not fit a JSON number,
+//! - bytes are hex strings (arrays of numbers are accepted as input),
+//! - addresses are strkeys and `Option` is `null` when absent,
+//! - vectors and tuples are arrays, maps are objects (or arrays of `[key, value]` pairs),
+//! - structs are objects by field name, or arrays for tuple structs,
+//! - union cases are `{"tag": "Case", "values": [...]}` (or just `"Case"` as input), and enum
+//!   cases are their numbers.
+use std::io::Cursor;
+
+use serde_json::{json, Map, Value};
+
+use crate::address::{Address, AddressTrait};
+use crate::contract::{ContractBehavior, Contracts};
+use crate::sc_int::{ScInt, ScIntType};
+use crate::scval::{self, from_scval, ToScVal};
+use crate::xdr;
+use crate::xdr::ReadXdr;
+
+/// Name of the wasm custom section holding the spec entries.
+const SPEC_SECTION: &str = "contractspecv0";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The wasm blob is malformed or has no spec section.
+    InvalidWasm(&'static str),
+    /// The spec entries do not decode.
+    Xdr(xdr::Error),
+    FunctionNotFound(String),
+    /// A user-defined type is referenced but not declared.
+    TypeNotFound(String),
+    MissingArgument(String),
+    /// A value does not have the shape of the spec type `expected`.
+    InvalidValue {
+        expected: String,
+        value: Value,
+    },
+    /// A contract value does not have the shape of the spec type `expected`.
+    InvalidScVal {
+        expected: String,
+        found: xdr::ScValType,
+    },
+    ScVal(scval::Error),
+    /// The spec type cannot be converted without more information, e.g. `Val`.
+    Unsupported(String),
+}
+
+impl From<scval::Error> for Error {
+    fn from(error: scval::Error) -> Self {
+        Error::ScVal(error)
+    }
+}
+
+impl From<xdr::Error> for Error {
+    fn from(error: xdr::Error) -> Self {
+        Error::Xdr(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ContractSpec {
+    entries: Vec<xdr::ScSpecEntry>,
+}
+
+impl ContractSpec {
+    pub fn new(entries: Vec<xdr::ScSpecEntry>) -> Self {
+        ContractSpec { entries }
+    }
+
+    /// Decodes spec entries written one after the other, as in the spec section of a contract.
+    pub fn from_xdr(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = xdr::Limited::new(Cursor::new(bytes), xdr::Limits::none());
+        let entries = xdr::ScSpecEntry::read_xdr_iter(&mut reader).collect::<Result<_, _>>()?;
+        Ok(ContractSpec { entries })
+    }
+
+    /// Reads the spec from the `contractspecv0` custom section of a contract's wasm.
+    pub fn from_wasm(wasm: &[u8]) -> Result<Self, Error> {
+        let section = custom_section(wasm, SPEC_SECTION)?
+            .ok_or(Error::InvalidWasm("no contractspecv0 section"))?;
+        Self::from_xdr(section)
+    }
+
+    pub fn entries(&self) -> &[xdr::ScSpecEntry] {
+        &self.entries
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = &xdr::ScSpecFunctionV0> {
+        self.entries.iter().filter_map(|entry| match entry {
+            xdr::ScSpecEntry::FunctionV0(function) => Some(function),
+            _ => None,
+        })
+    }
+
+    pub fn find_function(&self, name: &str) -> Result<&xdr::ScSpecFunctionV0, Error> {
+        self.functions()
+            .find(|function| function.name.0.as_slice() == name.as_bytes())
+            .ok_or_else(|| Error::FunctionNotFound(name.to_string()))
+    }
+
+    /// Returns the declaration of the user-defined type `name`.
+    pub fn find_type(&self, name: &str) -> Result<&xdr::ScSpecEntry, Error> {
+        self.entries
+            .iter()
+            .find(|entry| {
+                let entry_name = match entry {
+                    xdr::ScSpecEntry::UdtStructV0(udt) => &udt.name,
+                    xdr::ScSpecEntry::UdtUnionV0(udt) => &udt.name,
+                    xdr::ScSpecEntry::UdtEnumV0(udt) => &udt.name,
+                    xdr::ScSpecEntry::UdtErrorEnumV0(udt) => &udt.name,
+                    _ => return false,
+                };
+                entry_name.as_slice() == name.as_bytes()
+            })
+            .ok_or_else(|| Error::TypeNotFound(name.to_string()))
+    }
+
+    /// Converts the arguments of `function`, given as an object by input name or as an array
+    /// in input order.
+    pub fn func_args_to_scvals(
+        &self,
+        function: &str,
+        args: &Value,
+    ) -> Result<Vec<xdr::ScVal>, Error> {
+        let function = self.find_function(function)?;
+        function
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                let name = input.name.to_utf8_string_lossy();
+                let value = match args {
+                    Value::Object(args) => args.get(&name),
+                    Value::Array(args) => args.get(i),
+                    _ => None,
+                };
+                match (value, &input.type_) {
+                    (Some(value), ty) => self.native_to_scval(value, ty),
+                    (None, xdr::ScSpecTypeDef::Option(_)) => Ok(xdr::ScVal::Void),
+                    (None, _) => Err(Error::MissingArgument(name)),
+                }
+            })
+            .collect()
+    }
+
+    /// Converts the return value of `function`.
+    pub fn func_res_to_native(&self, function: &str, value: &xdr::ScVal) -> Result<Value, Error> {
+        let function = self.find_function(function)?;
+        match function.outputs.first() {
+            Some(ty) => self.scval_to_native(value, ty),
+            None => Ok(Value::Null),
+        }
+    }
+
+    /// Builds an operation invoking `function` of `contract`, with arguments converted by
+    /// [ContractSpec::func_args_to_scvals].
+    pub fn call(
+        &self,
+        contract: &Contracts,
+        function: &str,
+        args: &Value,
+    ) -> Result<xdr::Operation, Error> {
+        let args = self.func_args_to_scvals(function, args)?;
+        Ok(contract.call(function, Some(args)))
+    }
+
+    pub fn native_to_scval(
+        &self,
+        value: &Value,
+        ty: &xdr::ScSpecTypeDef,
+    ) -> Result<xdr::ScVal, Error> {
+        use xdr::ScSpecTypeDef as T;
+
+        let invalid = || Error::InvalidValue {
+            expected: type_name(ty),
+            value: value.clone(),
+        };
+        let small = |value: &Value| -> Result<i128, Error> {
+            json_int(value)
+                .ok_or_else(invalid)?
+                .to_i128()
+                .map_err(Error::from)
+        };
+        let large = |kind| -> Result<xdr::ScVal, Error> {
+            Ok(json_int(value)
+                .ok_or_else(invalid)?
+                .with_kind(kind)?
+                .to_scval())
+        };
+        let out_of_range = |_| Error::ScVal(scval::Error::OutOfRange);
+
+        Ok(match ty {
+            T::Val => return Err(Error::Unsupported(type_name(ty))),
+            T::Bool => xdr::ScVal::Bool(value.as_bool().ok_or_else(invalid)?),
+            T::Void => match value {
+                Value::Null => xdr::ScVal::Void,
+                _ => return Err(invalid()),
+            },
+            T::Error => xdr::ScVal::Error(xdr::ScError::Contract(
+                small(value)?.try_into().map_err(out_of_range)?,
+            )),
+            T::U32 => xdr::ScVal::U32(small(value)?.try_into().map_err(out_of_range)?),
+            T::I32 => xdr::ScVal::I32(small(value)?.try_into().map_err(out_of_range)?),
+            T::U64 => xdr::ScVal::U64(small(value)?.try_into().map_err(out_of_range)?),
+            T::I64 => xdr::ScVal::I64(small(value)?.try_into().map_err(out_of_range)?),
+            T::Timepoint => xdr::ScVal::Timepoint(xdr::TimePoint(
+                small(value)?.try_into().map_err(out_of_range)?,
+            )),
+            T::Duration => xdr::ScVal::Duration(xdr::Duration(
+                small(value)?.try_into().map_err(out_of_range)?,
+            )),
+            T::U128 => large(ScIntType::U128)?,
+            T::I128 => large(ScIntType::I128)?,
+            T::U256 => large(ScIntType::U256)?,
+            T::I256 => large(ScIntType::I256)?,
+            T::Bytes => json_bytes(value).ok_or_else(invalid)?.to_scval()?,
+            T::BytesN(bytes_n) => {
+                let bytes = json_bytes(value).ok_or_else(invalid)?;
+                if bytes.len() != bytes_n.n as usize {
+                    return Err(invalid());
+                }
+                bytes.to_scval()?
+            }
+            T::String => value.as_str().ok_or_else(invalid)?.to_scval()?,
+            T::Symbol => {
+                let symbol = value.as_str().ok_or_else(invalid)?;
+                xdr::ScVal::Symbol(symbol.try_into().map_err(|_| invalid())?)
+            }
+            T::Address | T::MuxedAddress => {
+                Address::from_string(value.as_str().ok_or_else(invalid)?)
+                    .map_err(|_| invalid())?
+                    .to_scval()?
+            }
+            T::Option(option) => match value {
+                Value::Null => xdr::ScVal::Void,
+                value => self.native_to_scval(value, &option.value_type)?,
+            },
+            T::Result(_) => return Err(Error::Unsupported(type_name(ty))),
+            T::Vec(vec) => {
+                let values = value.as_array().ok_or_else(invalid)?;
+                let values = values
+                    .iter()
+                    .map(|value| self.native_to_scval(value, &vec.element_type))
+                    .collect::<Result<Vec<_>, _>>()?;
+                sc_vec(values)?
+            }
+            T::Map(map) => {
+                let entries = match value {
+                    Value::Object(object) => object
+                        .iter()
+                        .map(|(key, value)| (Value::String(key.clone()), value))
+                        .collect::<Vec<_>>(),
+                    Value::Array(pairs) => pairs
+                        .iter()
+                        .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                            Some([key, value]) => Ok((key.clone(), value)),
+                            _ => Err(invalid()),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                    _ => return Err(invalid()),
+                };
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(xdr::ScMapEntry {
+                            key: self.native_to_scval(key, &map.key_type)?,
+                            val: self.native_to_scval(value, &map.value_type)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                sc_map(entries)?
+            }
+            T::Tuple(tuple) => {
+                let values = value.as_array().ok_or_else(invalid)?;
+                if values.len() != tuple.value_types.len() {
+                    return Err(invalid());
+                }
+                let values = values
+                    .iter()
+                    .zip(tuple.value_types.iter())
+                    .map(|(value, ty)| self.native_to_scval(value, ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                sc_vec(values)?
+            }
+            T::Udt(udt) => self.udt_to_scval(value, &udt.name.to_utf8_string_lossy())?,
+        })
+    }
+
+    fn udt_to_scval(&self, value: &Value, name: &str) -> Result<xdr::ScVal, Error> {
+        let invalid = || Error::InvalidValue {
+            expected: name.to_string(),
+            value: value.clone(),
+        };
+        match self.find_type(name)? {
+            xdr::ScSpecEntry::UdtStructV0(udt) if is_tuple_struct(udt) => {
+                let values = value.as_array().ok_or_else(invalid)?;
+                if values.len() != udt.fields.len() {
+                    return Err(invalid());
+                }
+                let values = values
+                    .iter()
+                    .zip(udt.fields.iter())
+                    .map(|(value, field)| self.native_to_scval(value, &field.type_))
+                    .collect::<Result<Vec<_>, _>>()?;
+                sc_vec(values)
+            }
+            xdr::ScSpecEntry::UdtStructV0(udt) => {
+                let object = value.as_object().ok_or_else(invalid)?;
+                let entries = udt
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let name = field.name.to_utf8_string_lossy();
+                        let value = object
+                            .get(&name)
+                            .ok_or_else(|| Error::MissingArgument(name.clone()))?;
+                        Ok(xdr::ScMapEntry {
+                            key: xdr::ScVal::Symbol(
+                                name.as_str().try_into().map_err(|_| invalid())?,
+                            ),
+                            val: self.native_to_scval(value, &field.type_)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                sc_map(entries)
+            }
+            xdr::ScSpecEntry::UdtUnionV0(udt) => {
+                let (tag, values) = match value {
+                    Value::String(tag) => (tag.as_str(), &[][..]),
+                    Value::Object(object) => (
+                        object
+                            .get("tag")
+                            .and_then(Value::as_str)
+                            .ok_or_else(invalid)?,
+                        match object.get("values") {
+                            Some(values) => values.as_array().ok_or_else(invalid)?.as_slice(),
+                            None => &[][..],
+                        },
+                    ),
+                    _ => return Err(invalid()),
+                };
+                let case = udt
+                    .cases
+                    .iter()
+                    .find(|case| union_case_name(case).as_slice() == tag.as_bytes())
+                    .ok_or_else(invalid)?;
+                let types = match case {
+                    xdr::ScSpecUdtUnionCaseV0::VoidV0(_) => &[][..],
+                    xdr::ScSpecUdtUnionCaseV0::TupleV0(case) => case.type_.as_slice(),
+                };
+                if values.len() != types.len() {
+                    return Err(invalid());
+                }
+                let mut vec = vec![xdr::ScVal::Symbol(tag.try_into().map_err(|_| invalid())?)];
+                for (value, ty) in values.iter().zip(types) {
+                    vec.push(self.native_to_scval(value, ty)?);
+                }
+                sc_vec(vec)
+            }
+            xdr::ScSpecEntry::UdtEnumV0(udt) => {
+                let number = value.as_u64().ok_or_else(invalid)?;
+                udt.cases
+                    .iter()
+                    .find(|case| case.value as u64 == number)
+                    .map(|case| xdr::ScVal::U32(case.value))
+                    .ok_or_else(invalid)
+            }
+            xdr::ScSpecEntry::UdtErrorEnumV0(udt) => {
+                let number = value.as_u64().ok_or_else(invalid)?;
+                udt.cases
+                    .iter()
+                    .find(|case| case.value as u64 == number)
+                    .map(|case| xdr::ScVal::Error(xdr::ScError::Contract(case.value)))
+                    .ok_or_else(invalid)
+            }
+            _ => Err(Error::TypeNotFound(name.to_string())),
+        }
+    }
+
+    pub fn scval_to_native(
+        &self,
+        value: &xdr::ScVal,
+        ty: &xdr::ScSpecTypeDef,
+    ) -> Result<Value, Error> {
+        use xdr::ScSpecTypeDef as T;
+
+        let invalid = || Error::InvalidScVal {
+            expected: type_name(ty),
+            found: value.discriminant(),
+        };
+
+        Ok(match (ty, value) {
+            (T::Option(_), xdr::ScVal::Void) => Value::Null,
+            (T::Option(option), value) => self.scval_to_native(value, &option.value_type)?,
+            (T::Result(_), xdr::ScVal::Error(xdr::ScError::Contract(code))) => {
+                json!({ "error": code })
+            }
+            (T::Result(result), value) => self.scval_to_native(value, &result.ok_type)?,
+            (T::Val, _) => return Err(Error::Unsupported(type_name(ty))),
+            (T::Bool, xdr::ScVal::Bool(b)) => json!(b),
+            (T::Void, xdr::ScVal::Void) => Value::Null,
+            (T::Error, xdr::ScVal::Error(xdr::ScError::Contract(code))) => json!(code),
+            (T::U32, xdr::ScVal::U32(n)) => json!(n),
+            (T::I32, xdr::ScVal::I32(n)) => json!(n),
+            (T::U64, xdr::ScVal::U64(n)) => json!(n),
+            (T::I64, xdr::ScVal::I64(n)) => json!(n),
+            (T::Timepoint, xdr::ScVal::Timepoint(n)) => json!(n.0),
+            (T::Duration, xdr::ScVal::Duration(n)) => json!(n.0),
+            (
+                T::U128 | T::I128 | T::U256 | T::I256,
+                xdr::ScVal::U128(_)
+                | xdr::ScVal::I128(_)
+                | xdr::ScVal::U256(_)
+                | xdr::ScVal::I256(_),
+            ) => Value::String(from_scval::<ScInt>(value)?.to_string()),
+            (T::Bytes | T::BytesN(_), xdr::ScVal::Bytes(bytes)) => {
+                Value::String(hex::encode(bytes.as_slice()))
+            }
+            (T::String | T::Symbol, _) => Value::String(from_scval(value)?),
+            (T::Address | T::MuxedAddress, _) => {
+                Value::String(from_scval::<Address>(value)?.to_string())
+            }
+            (T::Vec(vec), xdr::ScVal::Vec(Some(values))) => Value::Array(
+                values
+                    .iter()
+                    .map(|value| self.scval_to_native(value, &vec.element_type))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (T::Map(map), xdr::ScVal::Map(Some(entries))) => {
+                if matches!(*map.key_type, T::String | T::Symbol) {
+                    let mut object = Map::new();
+                    for entry in entries.iter() {
+                        object.insert(
+                            from_scval(&entry.key)?,
+                            self.scval_to_native(&entry.val, &map.value_type)?,
+                        );
+                    }
+                    Value::Object(object)
+                } else {
+                    Value::Array(
+                        entries
+                            .iter()
+                            .map(|entry| {
+                                Ok(json!([
+                                    self.scval_to_native(&entry.key, &map.key_type)?,
+                                    self.scval_to_native(&entry.val, &map.value_type)?,
+                                ]))
+                            })
+                            .collect::<Result<_, Error>>()?,
+                    )
+                }
+            }
+            (T::Tuple(tuple), xdr::ScVal::Vec(Some(values)))
+                if values.len() == tuple.value_types.len() =>
+            {
+                Value::Array(
+                    values
+                        .iter()
+                        .zip(tuple.value_types.iter())
+                        .map(|(value, ty)| self.scval_to_native(value, ty))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            (T::Udt(udt), _) => self.udt_to_native(value, &udt.name.to_utf8_string_lossy())?,
+            _ => return Err(invalid()),
+        })
+    }
+
+    fn udt_to_native(&self, value: &xdr::ScVal, name: &str) -> Result<Value, Error> {
+        let invalid = || Error::InvalidScVal {
+            expected: name.to_string(),
+            found: value.discriminant(),
+        };
+        match (self.find_type(name)?, value) {
+            (xdr::ScSpecEntry::UdtStructV0(udt), xdr::ScVal::Vec(Some(values)))
+                if is_tuple_struct(udt) && values.len() == udt.fields.len() =>
+            {
+                Ok(Value::Array(
+                    values
+                        .iter()
+                        .zip(udt.fields.iter())
+                        .map(|(value, field)| self.scval_to_native(value, &field.type_))
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            (xdr::ScSpecEntry::UdtStructV0(udt), xdr::ScVal::Map(Some(entries))) => {
+                let mut object = Map::new();
+                for field in udt.fields.iter() {
+                    let entry = entries
+                        .iter()
+                        .find(|entry| {
+                            matches!(&entry.key, xdr::ScVal::Symbol(key) if key.as_slice() == field.name.as_slice())
+                        })
+                        .ok_or_else(invalid)?;
+                    object.insert(
+                        field.name.to_utf8_string_lossy(),
+                        self.scval_to_native(&entry.val, &field.type_)?,
+                    );
+                }
+                Ok(Value::Object(object))
+            }
+            (xdr::ScSpecEntry::UdtUnionV0(udt), xdr::ScVal::Vec(Some(values))) => {
+                let (tag, values) = match values.split_first() {
+                    Some((xdr::ScVal::Symbol(tag), values)) => (tag, values),
+                    _ => return Err(invalid()),
+                };
+                let case = udt
+                    .cases
+                    .iter()
+                    .find(|case| union_case_name(case).as_slice() == tag.as_slice())
+                    .ok_or_else(invalid)?;
+                let tag = tag.to_utf8_string_lossy();
+                match case {
+                    xdr::ScSpecUdtUnionCaseV0::VoidV0(_) if values.is_empty() => {
+                        Ok(json!({ "tag": tag }))
+                    }
+                    xdr::ScSpecUdtUnionCaseV0::TupleV0(case)
+                        if values.len() == case.type_.len() =>
+                    {
+                        let values = values
+                            .iter()
+                            .zip(case.type_.iter())
+                            .map(|(value, ty)| self.scval_to_native(value, ty))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        Ok(json!({ "tag": tag, "values": values }))
+                    }
+                    _ => Err(invalid()),
+                }
+            }
+            (xdr::ScSpecEntry::UdtEnumV0(_), xdr::ScVal::U32(n)) => Ok(json!(n)),
+            (xdr::ScSpecEntry::UdtErrorEnumV0(_), xdr::ScVal::Error(xdr::ScError::Contract(n))) => {
+                Ok(json!(n))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+fn type_name(ty: &xdr::ScSpecTypeDef) -> String {
+    match ty {
+        xdr::ScSpecTypeDef::Udt(udt) => udt.name.to_utf8_string_lossy(),
+        _ => ty.name().to_string(),
+    }
+}
+
+fn union_case_name(case: &xdr::ScSpecUdtUnionCaseV0) -> &xdr::StringM<60> {
+    match case {
+        xdr::ScSpecUdtUnionCaseV0::VoidV0(case) => &case.name,
+        xdr::ScSpecUdtUnionCaseV0::TupleV0(case) => &case.name,
+    }
+}
+
+/// Tuple structs have fields named by their position.
+fn is_tuple_struct(udt: &xdr::ScSpecUdtStructV0) -> bool {
+    !udt.fields.is_empty()
+        && udt
+            .fields
+            .iter()
+            .all(|field| field.name.iter().all(u8::is_ascii_digit))
+}
+
+fn sc_vec(values: Vec<xdr::ScVal>) -> Result<xdr::ScVal, Error> {
+    let len = values.len();
+    Ok(xdr::ScVal::Vec(Some(xdr::ScVec(
+        values.try_into().map_err(|_| scval::Error::TooLong(len))?,
+    ))))
+}
+
+fn sc_map(mut entries: Vec<xdr::ScMapEntry>) -> Result<xdr::ScVal, Error> {
+    let len = entries.len();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(xdr::ScVal::Map(Some(xdr::ScMap(
+        entries.try_into().map_err(|_| scval::Error::TooLong(len))?,
+    ))))
+}
+
+/// Reads a JSON number or decimal string as an integer of up to 256 bits.
+fn json_int(value: &Value) -> Option<ScInt> {
+    match value {
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(n), _) => ScInt::from_i128(ScIntType::I256, n as i128).ok(),
+            (_, Some(n)) => ScInt::from_u128(ScIntType::I256, n as u128).ok(),
+            _ => None,
+        },
+        Value::String(s) => match ScInt::parse(ScIntType::I256, s) {
+            Err(scval::Error::OutOfRange) => ScInt::parse(ScIntType::U256, s).ok(),
+            result => result.ok(),
+        },
+        _ => None,
+    }
+}
+
+/// Reads a hex string or an array of byte values.
+fn json_bytes(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::String(s) => hex::decode(s).ok(),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| value.as_u64().and_then(|n| u8::try_from(n).ok()))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn read_leb128(bytes: &[u8], pos: &mut usize) -> Result<usize, Error> {
+    let mut result = 0_usize;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or(Error::InvalidWasm("unexpected end of wasm"))?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(Error::InvalidWasm("integer too long"))
+}
+
+/// Returns the contents of the first custom section called `name`.
+fn custom_section<'a>(wasm: &'a [u8], name: &str) -> Result<Option<&'a [u8]>, Error> {
+    if wasm.get(..8) != Some(b"\0asm\x01\0\0\0".as_slice()) {
+        return Err(Error::InvalidWasm("not a wasm module"));
+    }
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_leb128(wasm, &mut pos)?;
+        let section = wasm
+            .get(pos..pos + size)
+            .ok_or(Error::InvalidWasm("section extends past the end of wasm"))?;
+        pos += size;
+        if id != 0 {
+            continue;
+        }
+        let mut name_pos = 0;
+        let name_len = read_leb128(section, &mut name_pos)?;
+        let section_name = section
+            .get(name_pos..name_pos + name_len)
+            .ok_or(Error::InvalidWasm("section name extends past the section"))?;
+        if section_name == name.as_bytes() {
+            return Ok(Some(&section[name_pos + name_len..]));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xdr::{Limits, WriteXdr};
+
+    const ACCOUNT: &str = "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB";
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    fn udt(name: &str) -> xdr::ScSpecTypeDef {
+        xdr::ScSpecTypeDef::Udt(xdr::ScSpecTypeUdt {
+            name: name.try_into().unwrap(),
+        })
+    }
+
+    fn field(name: &str, type_: xdr::ScSpecTypeDef) -> xdr::ScSpecUdtStructFieldV0 {
+        xdr::ScSpecUdtStructFieldV0 {
+            doc: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            type_,
+        }
+    }
+
+    fn spec() -> ContractSpec {
+        let transfer = xdr::ScSpecFunctionV0 {
+            doc: "".try_into().unwrap(),
+            name: "transfer".try_into().unwrap(),
+            inputs: [
+                ("from", xdr::ScSpecTypeDef::Address),
+                ("to", xdr::ScSpecTypeDef::Address),
+                ("amount", xdr::ScSpecTypeDef::I128),
+                (
+                    "memo",
+                    xdr::ScSpecTypeDef::Option(Box::new(xdr::ScSpecTypeOption {
+                        value_type: Box::new(xdr::ScSpecTypeDef::String),
+                    })),
+                ),
+            ]
+            .into_iter()
+            .map(|(name, type_)| xdr::ScSpecFunctionInputV0 {
+                doc: "".try_into().unwrap(),
+                name: name.try_into().unwrap(),
+                type_,
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap(),
+            outputs: vec![udt("Balance")].try_into().unwrap(),
+        };
+        let balance = xdr::ScSpecUdtStructV0 {
+            doc: "".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: "Balance".try_into().unwrap(),
+            fields: vec![
+                field("amount", xdr::ScSpecTypeDef::I128),
+                field("state", udt("State")),
+            ]
+            .try_into()
+            .unwrap(),
+        };
+        let state = xdr::ScSpecUdtUnionV0 {
+            doc: "".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: "State".try_into().unwrap(),
+            cases: vec![
+                xdr::ScSpecUdtUnionCaseV0::VoidV0(xdr::ScSpecUdtUnionCaseVoidV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "Open".try_into().unwrap(),
+                }),
+                xdr::ScSpecUdtUnionCaseV0::TupleV0(xdr::ScSpecUdtUnionCaseTupleV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "Frozen".try_into().unwrap(),
+                    type_: vec![xdr::ScSpecTypeDef::U64].try_into().unwrap(),
+                }),
+            ]
+            .try_into()
+            .unwrap(),
+        };
+        ContractSpec::new(vec![
+            xdr::ScSpecEntry::FunctionV0(transfer),
+            xdr::ScSpecEntry::UdtStructV0(balance),
+            xdr::ScSpecEntry::UdtUnionV0(state),
+        ])
+    }
+
+    #[test]
+    fn test_from_wasm() {
+        let spec = spec();
+        let mut section = vec![SPEC_SECTION.len() as u8];
+        section.extend_from_slice(SPEC_SECTION.as_bytes());
+        for entry in spec.entries() {
+            section.extend(entry.to_xdr(Limits::none()).unwrap());
+        }
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // An empty type section before the spec.
+        wasm.extend([1, 1, 0]);
+        wasm.push(0);
+        let mut size = section.len();
+        while size >= 0x80 {
+            wasm.push((size as u8 & 0x7f) | 0x80);
+            size >>= 7;
+        }
+        wasm.push(size as u8);
+        wasm.extend(section);
+
+        assert_eq!(ContractSpec::from_wasm(&wasm).unwrap(), spec);
+        assert!(matches!(
+            ContractSpec::from_wasm(&wasm[..8]),
+            Err(Error::InvalidWasm(_))
+        ));
+        assert!(matches!(
+            ContractSpec::from_wasm(b"not wasm"),
+            Err(Error::InvalidWasm(_))
+        ));
+    }
+
+    #[test]
+    fn test_func_args_to_scvals() {
+        let spec = spec();
+        let args = spec
+            .func_args_to_scvals(
+                "transfer",
+                &json!({ "from": ACCOUNT, "to": CONTRACT, "amount": "-170141183460469231731687303715884105728" }),
+            )
+            .unwrap();
+        assert_eq!(
+            args,
+            vec![
+                Address::from_string(ACCOUNT).unwrap().to_scval().unwrap(),
+                Address::from_string(CONTRACT).unwrap().to_scval().unwrap(),
+                i128::MIN.to_scval().unwrap(),
+                xdr::ScVal::Void,
+            ]
+        );
+        let positional = spec
+            .func_args_to_scvals("transfer", &json!([ACCOUNT, CONTRACT, 5, "hi"]))
+            .unwrap();
+        assert_eq!(positional[2], 5_i128.to_scval().unwrap());
+        assert_eq!(positional[3], "hi".to_scval().unwrap());
+
+        assert!(matches!(
+            spec.func_args_to_scvals("transfer", &json!({ "from": ACCOUNT })),
+            Err(Error::MissingArgument(name)) if name == "to"
+        ));
+        assert!(matches!(
+            spec.func_args_to_scvals("transfer", &json!([ACCOUNT, CONTRACT, "1.5"])),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            spec.func_args_to_scvals("mint", &json!([])),
+            Err(Error::FunctionNotFound(_))
+        ));
+
+        let contract = Contracts::new(CONTRACT).unwrap();
+        let operation = spec
+            .call(&contract, "transfer", &json!([ACCOUNT, ACCOUNT, 1]))
+            .unwrap();
+        assert_eq!(
+            operation,
+            contract.call(
+                "transfer",
+                Some(
+                    spec.func_args_to_scvals("transfer", &json!([ACCOUNT, ACCOUNT, 1]))
+                        .unwrap()
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_udt_round_trip() {
+        let spec = spec();
+        let balance = json!({ "amount": "12", "state": { "tag": "Frozen", "values": [7] } });
+        let scval = spec.native_to_scval(&balance, &udt("Balance")).unwrap();
+        let xdr::ScVal::Map(Some(entries)) = &scval else {
+            panic!("structs are maps");
+        };
+        assert_eq!(
+            entries[1].val,
+            xdr::ScVal::Vec(Some(
+                vec![
+                    xdr::ScVal::Symbol("Frozen".try_into().unwrap()),
+                    xdr::ScVal::U64(7)
+                ]
+                .try_into()
+                .unwrap()
+            ))
+        );
+        assert_eq!(
+            spec.func_res_to_native("transfer", &scval).unwrap(),
+            balance
+        );
+
+        let open = spec.native_to_scval(&json!("Open"), &udt("State")).unwrap();
+        assert_eq!(
+            spec.scval_to_native(&open, &udt("State")).unwrap(),
+            json!({ "tag": "Open" })
+        );
+        assert!(matches!(
+            spec.native_to_scval(&json!("Closed"), &udt("State")),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            spec.native_to_scval(&json!(1), &udt("Missing")),
+            Err(Error::TypeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_containers() {
+        let spec = ContractSpec::default();
+        let map = xdr::ScSpecTypeDef::Map(Box::new(xdr::ScSpecTypeMap {
+            key_type: Box::new(xdr::ScSpecTypeDef::Symbol),
+            value_type: Box::new(xdr::ScSpecTypeDef::BytesN(xdr::ScSpecTypeBytesN { n: 2 })),
+        }));
+        let value = json!({ "b": "0102", "a": [3, 4] });
+        let scval = spec.native_to_scval(&value, &map).unwrap();
+        assert_eq!(
+            spec.scval_to_native(&scval, &map).unwrap(),
+            json!({ "a": "0304", "b": "0102" })
+        );
+        assert!(spec.native_to_scval(&json!({ "a": "01" }), &map).is_err());
+
+        let tuple = xdr::ScSpecTypeDef::Tuple(Box::new(xdr::ScSpecTypeTuple {
+            value_types: vec![xdr::ScSpecTypeDef::U32, xdr::ScSpecTypeDef::Bool]
+                .try_into()
+                .unwrap(),
+        }));
+        let scval = spec.native_to_scval(&json!([1, true]), &tuple).unwrap();
+        assert_eq!(
+            spec.scval_to_native(&scval, &tuple).unwrap(),
+            json!([1, true])
+        );
+        assert!(matches!(
+            spec.native_to_scval(&json!([-1, true]), &tuple),
+            Err(Error::ScVal(scval::Error::OutOfRange))
+        ));
+        assert!(matches!(
+            spec.scval_to_native(&xdr::ScVal::U32(1), &tuple),
+            Err(Error::InvalidScVal { .. })
+        ));
+    }
+}
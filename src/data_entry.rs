@@ -0,0 +1,113 @@
+//! Encoding of typed values into account data entries.
+//!
+//! Data entry values are raw byte strings of at most [MAX_DATA_VALUE_LEN] bytes. The
+//! [DataEntryValue] implementations give a common layout for the usual types:
+//! - `u64` as 8 big-endian bytes,
+//! - `String` as its UTF-8 bytes,
+//! - `[u8; 32]` hashes as their 32 raw bytes.
+use crate::xdr;
+
+/// Maximum length in bytes of a data entry value.
+pub const MAX_DATA_VALUE_LEN: usize = 64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The encoded value is longer than [MAX_DATA_VALUE_LEN] bytes.
+    TooLong(usize),
+    /// The stored value does not have the length of the requested type.
+    InvalidLength { expected: usize, actual: usize },
+    /// The stored value is not valid UTF-8.
+    InvalidUtf8,
+}
+
+/// A value that can be stored in, and read back from, an account data entry.
+pub trait DataEntryValue: Sized {
+    fn to_data_value(&self) -> Result<xdr::DataValue, Error>;
+    fn from_data_value(value: &[u8]) -> Result<Self, Error>;
+}
+
+fn data_value(bytes: Vec<u8>) -> Result<xdr::DataValue, Error> {
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map(xdr::DataValue)
+        .map_err(|_| Error::TooLong(len))
+}
+
+fn fixed<const N: usize>(value: &[u8]) -> Result<[u8; N], Error> {
+    value.try_into().map_err(|_| Error::InvalidLength {
+        expected: N,
+        actual: value.len(),
+    })
+}
+
+impl DataEntryValue for u64 {
+    fn to_data_value(&self) -> Result<xdr::DataValue, Error> {
+        data_value(self.to_be_bytes().to_vec())
+    }
+
+    fn from_data_value(value: &[u8]) -> Result<Self, Error> {
+        Ok(u64::from_be_bytes(fixed(value)?))
+    }
+}
+
+impl DataEntryValue for String {
+    fn to_data_value(&self) -> Result<xdr::DataValue, Error> {
+        data_value(self.as_bytes().to_vec())
+    }
+
+    fn from_data_value(value: &[u8]) -> Result<Self, Error> {
+        String::from_utf8(value.to_vec()).map_err(|_| Error::InvalidUtf8)
+    }
+}
+
+impl DataEntryValue for [u8; 32] {
+    fn to_data_value(&self) -> Result<xdr::DataValue, Error> {
+        data_value(self.to_vec())
+    }
+
+    fn from_data_value(value: &[u8]) -> Result<Self, Error> {
+        fixed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u64_roundtrip() {
+        let value = 1_234_567_890_123_u64.to_data_value().unwrap();
+        assert_eq!(value.len(), 8);
+        assert_eq!(u64::from_data_value(&value).unwrap(), 1_234_567_890_123);
+        assert_eq!(
+            u64::from_data_value(&[0; 4]),
+            Err(Error::InvalidLength {
+                expected: 8,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let value = "héllo".to_string().to_data_value().unwrap();
+        assert_eq!(String::from_data_value(&value).unwrap(), "héllo");
+        assert_eq!("a".repeat(65).to_data_value(), Err(Error::TooLong(65)));
+        assert!("a".repeat(64).to_data_value().is_ok());
+        assert_eq!(String::from_data_value(&[0xff]), Err(Error::InvalidUtf8));
+    }
+
+    #[test]
+    fn test_hash_roundtrip() {
+        let value = [7; 32].to_data_value().unwrap();
+        assert_eq!(<[u8; 32]>::from_data_value(&value).unwrap(), [7; 32]);
+        assert_eq!(
+            <[u8; 32]>::from_data_value(&[7; 33]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                actual: 33
+            })
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::error::Error;
+use std::io::Cursor;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::xdr;
+use crate::xdr::{ReadXdr, WriteXdr};
+
+/// An XDR value decoded with the types this crate is built with, or its raw bytes when they
+/// do not decode, e.g. because they use union arms of a newer protocol.
+///
+/// Unknown values keep their original bytes, so they encode back exactly as they were read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded<T> {
+    Known(T),
+    Unknown(Vec<u8>),
+}
+
+impl<T> Decoded<T> {
+    /// Returns the decoded value, or `None` if it is unknown.
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            Decoded::Known(value) => Some(value),
+            Decoded::Unknown(_) => None,
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Decoded::Unknown(_))
+    }
+
+    /// Converts the decoded value with `f`, keeping unknown bytes as they are.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Decoded<U> {
+        match self {
+            Decoded::Known(value) => Decoded::Known(f(value)),
+            Decoded::Unknown(bytes) => Decoded::Unknown(bytes),
+        }
+    }
+}
+
+impl<T: ReadXdr> Decoded<T> {
+    /// Decodes `bytes`, which must hold a single value of `T` and nothing else.
+    ///
+    /// Only a union arm or enum value this crate does not know makes the value unknown.
+    /// Truncated input, trailing bytes and other malformed XDR are errors.
+    pub fn from_xdr(bytes: impl Into<Vec<u8>>) -> Result<Self, xdr::Error> {
+        Self::from_xdr_with_limits(bytes, xdr::Limits::none())
+    }
+
+    /// Decodes `bytes` like [from_xdr](Self::from_xdr), failing if they exceed `limits`.
+    pub fn from_xdr_with_limits(
+        bytes: impl Into<Vec<u8>>,
+        limits: xdr::Limits,
+    ) -> Result<Self, xdr::Error> {
+        let bytes = bytes.into();
+        let mut reader = xdr::Limited::new(Cursor::new(bytes.as_slice()), limits);
+        match T::read_xdr(&mut reader) {
+            Ok(_) if reader.inner.position() != bytes.len() as u64 => Err(xdr::Error::Invalid),
+            Ok(value) => Ok(Decoded::Known(value)),
+            // Unknown discriminants are reported as `Invalid`, truncated input as `Io`.
+            Err(xdr::Error::Invalid) => Ok(Decoded::Unknown(bytes)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes base64 encoded XDR like [from_xdr](Self::from_xdr).
+    pub fn from_xdr_base64(value: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_xdr_base64_with_limits(value, xdr::Limits::none())
+    }
+
+    /// Decodes base64 encoded XDR like [from_xdr_with_limits](Self::from_xdr_with_limits).
+    pub fn from_xdr_base64_with_limits(
+        value: &str,
+        limits: xdr::Limits,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_xdr_with_limits(STANDARD.decode(value)?, limits)?)
+    }
+}
+
+impl<T: WriteXdr> Decoded<T> {
+    /// Encodes the value, or returns the unknown bytes unchanged.
+    pub fn to_xdr(&self) -> Result<Vec<u8>, xdr::Error> {
+        match self {
+            Decoded::Known(value) => value.to_xdr(xdr::Limits::none()),
+            Decoded::Unknown(bytes) => Ok(bytes.clone()),
+        }
+    }
+
+    pub fn to_xdr_base64(&self) -> Result<String, xdr::Error> {
+        Ok(STANDARD.encode(self.to_xdr()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operation::Operation;
+
+    #[test]
+    fn test_known_operation_round_trip() {
+        let operation = Operation::new().bump_sequence(5).unwrap();
+        let encoded = operation.to_xdr_base64(xdr::Limits::none()).unwrap();
+
+        let decoded = Decoded::<xdr::Operation>::from_xdr_base64(&encoded).unwrap();
+        assert_eq!(decoded.known(), Some(&operation));
+        assert_eq!(decoded.to_xdr_base64().unwrap(), encoded);
+    }
+
+    #[test]
+    fn test_unknown_operation_round_trip() {
+        // No source account, then an operation type no protocol defines yet.
+        let bytes = vec![0, 0, 0, 0, 0, 0, 0, 99, 0, 0, 0, 1];
+        let decoded = Decoded::<xdr::Operation>::from_xdr(bytes.clone()).unwrap();
+        assert!(decoded.is_unknown());
+        assert_eq!(decoded.to_xdr().unwrap(), bytes);
+
+        assert!(Decoded::<xdr::Operation>::from_xdr_base64("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_malformed_operation() {
+        let operation = Operation::new().bump_sequence(5).unwrap();
+        let bytes = operation.to_xdr(xdr::Limits::none()).unwrap();
+
+        let truncated = &bytes[..bytes.len() - 4];
+        assert!(Decoded::<xdr::Operation>::from_xdr(truncated).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.extend_from_slice(&[0; 4]);
+        assert!(Decoded::<xdr::Operation>::from_xdr(trailing).is_err());
+    }
+}
@@ -0,0 +1,608 @@
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::asset::{Asset, AssetBehavior};
+use crate::horizon::parse_amount;
+use crate::operation::{self, format_amount, Operation};
+use crate::utils::decode_encode_muxed_account::encode_muxed_account_to_address;
+use crate::xdr;
+
+/// An operation of a transaction with its amounts as decimals, see
+/// [format_amount](crate::operation::format_amount), and its accounts and contracts as
+/// strkeys.
+///
+/// It is decoded from XDR with [from_xdr](Self::from_xdr) or parsed from Horizon with
+/// [from_horizon_json](Self::from_horizon_json).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DecodedOperation {
+    /// The source of the operation itself, `None` if it uses the transaction source.
+    pub source_account: Option<String>,
+    pub body: DecodedOperationBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DecodedOperationBody {
+    CreateAccount {
+        destination: String,
+        starting_balance: String,
+    },
+    Payment {
+        destination: String,
+        asset: Asset,
+        amount: String,
+    },
+    PathPaymentStrictReceive {
+        send_asset: Asset,
+        send_max: String,
+        destination: String,
+        dest_asset: Asset,
+        dest_amount: String,
+        path: Vec<Asset>,
+    },
+    PathPaymentStrictSend {
+        send_asset: Asset,
+        send_amount: String,
+        destination: String,
+        dest_asset: Asset,
+        dest_min: String,
+        path: Vec<Asset>,
+    },
+    ManageSellOffer {
+        selling: Asset,
+        buying: Asset,
+        amount: String,
+        price: (i32, i32),
+        offer_id: i64,
+    },
+    ManageBuyOffer {
+        selling: Asset,
+        buying: Asset,
+        buy_amount: String,
+        price: (i32, i32),
+        offer_id: i64,
+    },
+    CreatePassiveSellOffer {
+        selling: Asset,
+        buying: Asset,
+        amount: String,
+        price: (i32, i32),
+    },
+    ChangeTrust {
+        asset: Asset,
+        limit: String,
+    },
+    AccountMerge {
+        destination: String,
+    },
+    ManageData {
+        name: String,
+        value: Option<Vec<u8>>,
+    },
+    BumpSequence {
+        bump_to: i64,
+    },
+    InvokeContract {
+        contract: String,
+        function: String,
+        args: Vec<xdr::ScVal>,
+    },
+    UploadWasm {
+        size: usize,
+    },
+    /// Any other operation, kept as its XDR body.
+    Other(xdr::OperationBody),
+}
+
+impl DecodedOperation {
+    /// Decodes `op`. Fails if one of its assets is invalid.
+    pub fn from_xdr(op: &xdr::Operation) -> Result<Self, Box<dyn Error>> {
+        let body = match &op.body {
+            xdr::OperationBody::CreateAccount(op) => DecodedOperationBody::CreateAccount {
+                destination: op.destination.to_string(),
+                starting_balance: format_amount(op.starting_balance),
+            },
+            xdr::OperationBody::Payment(op) => DecodedOperationBody::Payment {
+                destination: encode_muxed_account_to_address(&op.destination),
+                asset: asset(&op.asset)?,
+                amount: format_amount(op.amount),
+            },
+            xdr::OperationBody::PathPaymentStrictReceive(op) => {
+                DecodedOperationBody::PathPaymentStrictReceive {
+                    send_asset: asset(&op.send_asset)?,
+                    send_max: format_amount(op.send_max),
+                    destination: encode_muxed_account_to_address(&op.destination),
+                    dest_asset: asset(&op.dest_asset)?,
+                    dest_amount: format_amount(op.dest_amount),
+                    path: op.path.iter().map(asset).collect::<Result<_, _>>()?,
+                }
+            }
+            xdr::OperationBody::PathPaymentStrictSend(op) => {
+                DecodedOperationBody::PathPaymentStrictSend {
+                    send_asset: asset(&op.send_asset)?,
+                    send_amount: format_amount(op.send_amount),
+                    destination: encode_muxed_account_to_address(&op.destination),
+                    dest_asset: asset(&op.dest_asset)?,
+                    dest_min: format_amount(op.dest_min),
+                    path: op.path.iter().map(asset).collect::<Result<_, _>>()?,
+                }
+            }
+            xdr::OperationBody::ManageSellOffer(op) => DecodedOperationBody::ManageSellOffer {
+                selling: asset(&op.selling)?,
+                buying: asset(&op.buying)?,
+                amount: format_amount(op.amount),
+                price: (op.price.n, op.price.d),
+                offer_id: op.offer_id,
+            },
+            xdr::OperationBody::ManageBuyOffer(op) => DecodedOperationBody::ManageBuyOffer {
+                selling: asset(&op.selling)?,
+                buying: asset(&op.buying)?,
+                buy_amount: format_amount(op.buy_amount),
+                price: (op.price.n, op.price.d),
+                offer_id: op.offer_id,
+            },
+            xdr::OperationBody::CreatePassiveSellOffer(op) => {
+                DecodedOperationBody::CreatePassiveSellOffer {
+                    selling: asset(&op.selling)?,
+                    buying: asset(&op.buying)?,
+                    amount: format_amount(op.amount),
+                    price: (op.price.n, op.price.d),
+                }
+            }
+            xdr::OperationBody::ChangeTrust(xdr::ChangeTrustOp {
+                line: xdr::ChangeTrustAsset::Native,
+                limit,
+            }) => DecodedOperationBody::ChangeTrust {
+                asset: Asset::native(),
+                limit: format_amount(*limit),
+            },
+            xdr::OperationBody::ChangeTrust(xdr::ChangeTrustOp {
+                line: xdr::ChangeTrustAsset::CreditAlphanum4(line),
+                limit,
+            }) => DecodedOperationBody::ChangeTrust {
+                asset: asset(&xdr::Asset::CreditAlphanum4(line.clone()))?,
+                limit: format_amount(*limit),
+            },
+            xdr::OperationBody::ChangeTrust(xdr::ChangeTrustOp {
+                line: xdr::ChangeTrustAsset::CreditAlphanum12(line),
+                limit,
+            }) => DecodedOperationBody::ChangeTrust {
+                asset: asset(&xdr::Asset::CreditAlphanum12(line.clone()))?,
+                limit: format_amount(*limit),
+            },
+            xdr::OperationBody::AccountMerge(destination) => DecodedOperationBody::AccountMerge {
+                destination: encode_muxed_account_to_address(destination),
+            },
+            xdr::OperationBody::ManageData(op) => DecodedOperationBody::ManageData {
+                name: String::from_utf8_lossy(op.data_name.as_slice()).into_owned(),
+                value: op.data_value.as_ref().map(|value| value.to_vec()),
+            },
+            xdr::OperationBody::BumpSequence(op) => DecodedOperationBody::BumpSequence {
+                bump_to: op.bump_to.0,
+            },
+            xdr::OperationBody::InvokeHostFunction(xdr::InvokeHostFunctionOp {
+                host_function: xdr::HostFunction::InvokeContract(invocation),
+                ..
+            }) => DecodedOperationBody::InvokeContract {
+                contract: invocation.contract_address.to_string(),
+                function: String::from_utf8_lossy(invocation.function_name.as_slice()).into_owned(),
+                args: invocation.args.to_vec(),
+            },
+            xdr::OperationBody::InvokeHostFunction(xdr::InvokeHostFunctionOp {
+                host_function: xdr::HostFunction::UploadContractWasm(wasm),
+                ..
+            }) => DecodedOperationBody::UploadWasm { size: wasm.len() },
+            body => DecodedOperationBody::Other(body.clone()),
+        };
+
+        Ok(Self {
+            source_account: op
+                .source_account
+                .as_ref()
+                .map(encode_muxed_account_to_address),
+            body,
+        })
+    }
+}
+
+impl DecodedOperation {
+    /// Builds the equivalent XDR operation.
+    ///
+    /// Fails with [operation::Error::InvalidField] naming the amount that is not a valid
+    /// decimal, or `type` for contract invocations and uploads, which are not decoded in full.
+    pub fn to_operation(&self) -> Result<xdr::Operation, operation::Error> {
+        let builder = match &self.source_account {
+            Some(source) => Operation::with_source(source)?,
+            None => Operation::new(),
+        };
+
+        match &self.body {
+            DecodedOperationBody::CreateAccount {
+                destination,
+                starting_balance,
+            } => builder.create_account(
+                destination,
+                parse_amount(starting_balance, "starting_balance")?,
+            ),
+            DecodedOperationBody::Payment {
+                destination,
+                asset,
+                amount,
+            } => builder.payment(destination, asset, parse_amount(amount, "amount")?),
+            DecodedOperationBody::PathPaymentStrictReceive {
+                send_asset,
+                send_max,
+                destination,
+                dest_asset,
+                dest_amount,
+                path,
+            } => builder.path_payment_strict_receive(
+                send_asset,
+                parse_amount(send_max, "send_max")?,
+                destination,
+                dest_asset,
+                parse_amount(dest_amount, "dest_amount")?,
+                &path.iter().collect::<Vec<_>>(),
+            ),
+            DecodedOperationBody::PathPaymentStrictSend {
+                send_asset,
+                send_amount,
+                destination,
+                dest_asset,
+                dest_min,
+                path,
+            } => builder.path_payment_strict_send(
+                send_asset,
+                parse_amount(send_amount, "send_amount")?,
+                destination,
+                dest_asset,
+                parse_amount(dest_min, "dest_min")?,
+                &path.iter().collect::<Vec<_>>(),
+            ),
+            DecodedOperationBody::ManageSellOffer {
+                selling,
+                buying,
+                amount,
+                price,
+                offer_id,
+            } => builder.manage_sell_offer(
+                selling,
+                buying,
+                parse_amount(amount, "amount")?,
+                *price,
+                *offer_id,
+            ),
+            DecodedOperationBody::ManageBuyOffer {
+                selling,
+                buying,
+                buy_amount,
+                price,
+                offer_id,
+            } => builder.manage_buy_offer(
+                selling,
+                buying,
+                parse_amount(buy_amount, "buy_amount")?,
+                *price,
+                *offer_id,
+            ),
+            DecodedOperationBody::CreatePassiveSellOffer {
+                selling,
+                buying,
+                amount,
+                price,
+            } => builder.create_passive_sell_offer(
+                selling,
+                buying,
+                parse_amount(amount, "amount")?,
+                *price,
+            ),
+            DecodedOperationBody::ChangeTrust { asset, limit } => {
+                builder.change_trust(asset, parse_amount(limit, "limit")?)
+            }
+            DecodedOperationBody::AccountMerge { destination } => {
+                builder.account_merge(destination)
+            }
+            DecodedOperationBody::ManageData { name, value } => {
+                builder.manage_data(name, value.as_ref())
+            }
+            DecodedOperationBody::BumpSequence { bump_to } => builder.bump_sequence(*bump_to),
+            DecodedOperationBody::InvokeContract { .. }
+            | DecodedOperationBody::UploadWasm { .. } => {
+                Err(operation::Error::InvalidField("type".into()))
+            }
+            DecodedOperationBody::Other(body) => Ok(xdr::Operation {
+                source_account: builder.source.clone(),
+                body: body.clone(),
+            }),
+        }
+    }
+}
+
+/// One English sentence per operation, e.g. `Pay 100 USDC:GA… to GB…`, for signing prompts
+/// and audit logs. Credit assets are shown with their issuer, so that look-alike codes stand
+/// out.
+impl fmt::Display for DecodedOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.body {
+            DecodedOperationBody::CreateAccount {
+                destination,
+                starting_balance,
+            } => write!(
+                f,
+                "Create account {destination} with {} XLM",
+                amount(starting_balance)
+            )?,
+            DecodedOperationBody::Payment {
+                destination,
+                asset,
+                amount: paid,
+            } => write!(f, "Pay {} {} to {destination}", amount(paid), name(asset))?,
+            DecodedOperationBody::PathPaymentStrictReceive {
+                send_asset,
+                send_max,
+                destination,
+                dest_asset,
+                dest_amount,
+                ..
+            } => write!(
+                f,
+                "Pay {} {} to {destination}, sending at most {} {}",
+                amount(dest_amount),
+                name(dest_asset),
+                amount(send_max),
+                name(send_asset)
+            )?,
+            DecodedOperationBody::PathPaymentStrictSend {
+                send_asset,
+                send_amount,
+                destination,
+                dest_asset,
+                dest_min,
+                ..
+            } => write!(
+                f,
+                "Send {} {} to {destination}, delivering at least {} {}",
+                amount(send_amount),
+                name(send_asset),
+                amount(dest_min),
+                name(dest_asset)
+            )?,
+            DecodedOperationBody::ManageSellOffer { offer_id, .. }
+            | DecodedOperationBody::ManageBuyOffer { offer_id, .. }
+                if self.is_offer_deletion() =>
+            {
+                write!(f, "Delete offer {offer_id}")?
+            }
+            DecodedOperationBody::ManageSellOffer {
+                selling,
+                buying,
+                amount: sold,
+                price: (n, d),
+                offer_id,
+            } => {
+                write!(
+                    f,
+                    "Sell {} {} for {} at {n}/{d}",
+                    amount(sold),
+                    name(selling),
+                    name(buying)
+                )?;
+                offer(f, *offer_id)?
+            }
+            DecodedOperationBody::ManageBuyOffer {
+                selling,
+                buying,
+                buy_amount,
+                price: (n, d),
+                offer_id,
+            } => {
+                write!(
+                    f,
+                    "Buy {} {} for {} at {n}/{d}",
+                    amount(buy_amount),
+                    name(buying),
+                    name(selling)
+                )?;
+                offer(f, *offer_id)?
+            }
+            DecodedOperationBody::CreatePassiveSellOffer {
+                selling,
+                buying,
+                amount: sold,
+                price: (n, d),
+            } => write!(
+                f,
+                "Passively sell {} {} for {} at {n}/{d}",
+                amount(sold),
+                name(selling),
+                name(buying)
+            )?,
+            DecodedOperationBody::ChangeTrust { asset, limit } if amount(limit) == "0" => {
+                write!(f, "Remove trustline to {}", name(asset))?
+            }
+            DecodedOperationBody::ChangeTrust { asset, limit } => {
+                write!(f, "Trust {} up to {}", name(asset), amount(limit))?
+            }
+            DecodedOperationBody::AccountMerge { destination } => {
+                write!(f, "Merge account into {destination}")?
+            }
+            DecodedOperationBody::ManageData {
+                name,
+                value: Some(_),
+            } => write!(f, "Set data entry {name:?}")?,
+            DecodedOperationBody::ManageData { name, value: None } => {
+                write!(f, "Delete data entry {name:?}")?
+            }
+            DecodedOperationBody::BumpSequence { bump_to } => {
+                write!(f, "Bump sequence number to {bump_to}")?
+            }
+            DecodedOperationBody::InvokeContract {
+                contract, function, ..
+            } => write!(f, "Invoke contract {contract} method {function}")?,
+            DecodedOperationBody::UploadWasm { size } => {
+                write!(f, "Upload contract wasm of {size} bytes")?
+            }
+            DecodedOperationBody::Other(body) => write!(f, "{} operation", body.name())?,
+        }
+        if let Some(source) = &self.source_account {
+            write!(f, " on behalf of {source}")?;
+        }
+        Ok(())
+    }
+}
+
+impl DecodedOperation {
+    fn is_offer_deletion(&self) -> bool {
+        match &self.body {
+            DecodedOperationBody::ManageSellOffer {
+                amount: zero,
+                offer_id,
+                ..
+            }
+            | DecodedOperationBody::ManageBuyOffer {
+                buy_amount: zero,
+                offer_id,
+                ..
+            } => *offer_id != 0 && amount(zero) == "0",
+            _ => false,
+        }
+    }
+}
+
+/// Drops the trailing zeros of a decimal amount, e.g. `"100.5000000"` becomes `"100.5"`.
+fn amount(decimal: &str) -> &str {
+    match decimal.split_once('.') {
+        Some(_) => decimal.trim_end_matches('0').trim_end_matches('.'),
+        None => decimal,
+    }
+}
+
+fn name(asset: &Asset) -> String {
+    if asset.is_native() {
+        "XLM".to_string()
+    } else {
+        asset.to_string_asset()
+    }
+}
+
+fn offer(f: &mut fmt::Formatter<'_>, offer_id: i64) -> fmt::Result {
+    if offer_id != 0 {
+        write!(f, " (offer {offer_id})")?;
+    }
+    Ok(())
+}
+
+fn asset(asset: &xdr::Asset) -> Result<Asset, String> {
+    Asset::from_operation(asset.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operation::{Operation, ONE};
+
+    const SOURCE: &str = "GB7TAYRUZGE6TVT7NHP5SMIZRNQA6PLM423EYISAOAP3MKYIQMVYP2JO";
+    const DESTINATION: &str = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+
+    #[test]
+    fn test_decode_payment() {
+        let usd = Asset::new("USD", Some(SOURCE)).unwrap();
+        let op = Operation::with_source(SOURCE)
+            .unwrap()
+            .payment(DESTINATION, &usd, 105 * ONE / 10)
+            .unwrap();
+
+        assert_eq!(
+            DecodedOperation::from_xdr(&op).unwrap(),
+            DecodedOperation {
+                source_account: Some(SOURCE.to_string()),
+                body: DecodedOperationBody::Payment {
+                    destination: DESTINATION.to_string(),
+                    asset: usd,
+                    amount: "10.5000000".to_string(),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let usd = Asset::new("USD", Some(SOURCE)).unwrap();
+        let describe = |op: xdr::Operation| DecodedOperation::from_xdr(&op).unwrap().to_string();
+
+        assert_eq!(
+            describe(
+                Operation::new()
+                    .payment(DESTINATION, &usd, 100 * ONE)
+                    .unwrap()
+            ),
+            format!("Pay 100 USD:{SOURCE} to {DESTINATION}")
+        );
+        assert_eq!(
+            describe(
+                Operation::with_source(DESTINATION)
+                    .unwrap()
+                    .create_account(SOURCE, 15 * ONE / 10)
+                    .unwrap()
+            ),
+            format!("Create account {SOURCE} with 1.5 XLM on behalf of {DESTINATION}")
+        );
+        assert_eq!(
+            describe(
+                Operation::new()
+                    .manage_sell_offer(&usd, &Asset::native(), 0, (1, 2), 12)
+                    .unwrap()
+            ),
+            "Delete offer 12"
+        );
+        assert_eq!(
+            describe(Operation::new().bump_sequence(7).unwrap()),
+            "Bump sequence number to 7"
+        );
+        assert_eq!(
+            describe(Operation::new().restore_footprint().unwrap()),
+            "RestoreFootprint operation"
+        );
+    }
+
+    #[test]
+    fn test_decode_other_operations() {
+        let op = Operation::new().bump_sequence(7).unwrap();
+        assert_eq!(
+            DecodedOperation::from_xdr(&op).unwrap().body,
+            DecodedOperationBody::BumpSequence { bump_to: 7 }
+        );
+
+        let op = Operation::new().restore_footprint().unwrap();
+        let decoded = DecodedOperation::from_xdr(&op).unwrap();
+        assert_eq!(decoded.source_account, None);
+        assert_eq!(decoded.body, DecodedOperationBody::Other(op.body));
+    }
+
+    #[test]
+    fn test_to_operation() {
+        let usd = Asset::new("USD", Some(SOURCE)).unwrap();
+        let op = Operation::with_source(SOURCE)
+            .unwrap()
+            .payment(DESTINATION, &usd, 105 * ONE / 10)
+            .unwrap();
+        let decoded = DecodedOperation::from_xdr(&op).unwrap();
+        assert_eq!(decoded.to_operation().unwrap(), op);
+
+        let op = Operation::new().restore_footprint().unwrap();
+        assert_eq!(
+            DecodedOperation::from_xdr(&op).unwrap().to_operation(),
+            Ok(op)
+        );
+
+        let op = Operation::new().upload_wasm(&[0; 8], None).unwrap();
+        assert_eq!(
+            DecodedOperation::from_xdr(&op).unwrap().to_operation(),
+            Err(operation::Error::InvalidField("type".into()))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use std::error::Error;
+use std::future::Future;
+use std::str::FromStr;
+
+use crate::config::BASE_FEE;
+use crate::hashing::{HashingBehavior, Sha256Hasher};
+use crate::keypair::{Keypair, KeypairBehavior};
+use crate::transaction::{FeeBump, Transaction, TransactionBehavior, TxHash};
+use crate::transaction_signer;
+use crate::xdr;
+use crate::xdr::{ReadXdr, WriteXdr};
+
+/// A transaction whose fee is paid by `fee_source` instead of the source of the inner
+/// transaction, e.g. to resubmit a transaction stuck with a too low fee.
+///
+/// The inner transaction keeps its own signatures; `signatures` only authorize the fee source.
+#[derive(Debug, Clone)]
+pub struct FeeBumpTransaction {
+    pub network_passphrase: String,
+    pub inner_transaction: Transaction,
+    pub fee_source: String,
+    pub fee: i64,
+    pub signatures: Vec<xdr::DecoratedSignature>,
+}
+
+// Define a trait for FeeBumpTransaction behavior
+pub trait FeeBumpTransactionBehavior {
+    fn new(inner_transaction: Transaction, fee_source: &str, fee: i64) -> Result<Self, String>
+    where
+        Self: Sized;
+    fn from_xdr_envelope(xdr: &str, network: &str) -> Result<Self, Box<dyn Error>>
+    where
+        Self: Sized;
+    fn from_xdr_envelope_with_limits(
+        xdr: &str,
+        network: &str,
+        limits: xdr::Limits,
+    ) -> Result<Self, Box<dyn Error>>
+    where
+        Self: Sized;
+    fn signature_base(&self) -> Result<Vec<u8>, xdr::Error>;
+    fn hash(&self) -> Result<TxHash, xdr::Error>;
+    fn sign(&mut self, keypairs: &[Keypair]) -> Result<(), Box<dyn Error>>;
+    fn sign_with(
+        &mut self,
+        signers: &[&dyn transaction_signer::TransactionSigner],
+    ) -> Result<(), Box<dyn Error>>;
+    fn sign_with_async(
+        &mut self,
+        signers: &[&dyn transaction_signer::AsyncTransactionSigner],
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send;
+    fn to_envelope(&self) -> Result<xdr::TransactionEnvelope, Box<dyn Error>>;
+}
+
+impl FeeBumpTransaction {
+    fn to_fee_bump_tx(&self) -> Result<xdr::FeeBumpTransaction, xdr::Error> {
+        Ok(xdr::FeeBumpTransaction {
+            fee_source: xdr::MuxedAccount::from_str(&self.fee_source)?,
+            fee: self.fee,
+            inner_tx: xdr::FeeBumpTransactionInnerTx::Tx(xdr::TransactionV1Envelope {
+                tx: self.inner_transaction.to_tx(),
+                signatures: self.inner_transaction.signatures.clone().try_into()?,
+            }),
+            ext: xdr::FeeBumpTransactionExt::V0,
+        })
+    }
+}
+
+impl FeeBumpTransactionBehavior for FeeBumpTransaction {
+    /// Wraps `inner_transaction`, paying `fee` stroops from `fee_source`, a `G...` or `M...`
+    /// address. A V0 inner transaction is wrapped as its V1 equivalent.
+    ///
+    /// The fee bump counts as one more operation, and the Soroban resource fee of the inner
+    /// transaction is paid on top of its inclusion fee. Fails if the inclusion fee per
+    /// operation is lower than [BASE_FEE] or than the one of the inner transaction.
+    fn new(inner_transaction: Transaction, fee_source: &str, fee: i64) -> Result<Self, String> {
+        xdr::MuxedAccount::from_str(fee_source).map_err(|_| "fee_source is invalid")?;
+
+        let inner_ops = inner_transaction.operations.as_ref().map_or(0, Vec::len) as i64;
+        let resource_fee = inner_transaction
+            .soroban_data
+            .as_ref()
+            .map_or(0, |data| data.resource_fee);
+        let inclusion_fee = fee - resource_fee;
+        let inner_inclusion_fee = i64::from(inner_transaction.fee) - resource_fee;
+        let min_fee = i64::from(BASE_FEE) * (inner_ops + 1);
+        if inclusion_fee < min_fee {
+            return Err(format!(
+                "fee {} is lower than the minimum fee {}",
+                fee,
+                min_fee + resource_fee
+            ));
+        }
+        // Compares the fee rates, inclusion fee / operation count, without rounding.
+        if inclusion_fee * inner_ops < inner_inclusion_fee * (inner_ops + 1) {
+            return Err(format!(
+                "fee {} pays less per operation than the inner transaction fee {}",
+                fee, inner_transaction.fee
+            ));
+        }
+
+        let mut inner_transaction = inner_transaction;
+        inner_transaction.upgrade_to_v1();
+        inner_transaction.fee_bump = None;
+        Ok(Self {
+            network_passphrase: inner_transaction.network_passphrase.clone(),
+            inner_transaction,
+            fee_source: fee_source.to_string(),
+            fee,
+            signatures: Vec::new(),
+        })
+    }
+
+    /// Decodes the base64 fee-bump envelope `xdr`. Fails for any other envelope type.
+    fn from_xdr_envelope(xdr: &str, network: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_xdr_envelope_with_limits(xdr, network, xdr::Limits::none())
+    }
+
+    /// Decodes the base64 fee-bump envelope `xdr` like
+    /// [from_xdr_envelope](Self::from_xdr_envelope), failing if it exceeds `limits`.
+    fn from_xdr_envelope_with_limits(
+        xdr: &str,
+        network: &str,
+        limits: xdr::Limits,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut inner_transaction =
+            Transaction::try_from_xdr_envelope_with_limits(xdr, network, limits)?;
+        let FeeBump {
+            fee_source,
+            fee,
+            signatures,
+        } = inner_transaction
+            .fee_bump
+            .take()
+            .ok_or("envelope is not a fee-bump envelope")?;
+        Ok(Self {
+            network_passphrase: network.to_string(),
+            inner_transaction,
+            fee_source,
+            fee,
+            signatures,
+        })
+    }
+
+    /// Fails if `fee_source` is not a valid address or there are too many inner signatures.
+    fn signature_base(&self) -> Result<Vec<u8>, xdr::Error> {
+        let tx_sig = xdr::TransactionSignaturePayload {
+            network_id: xdr::Hash(Sha256Hasher::hash(self.network_passphrase.as_bytes())),
+            tagged_transaction: xdr::TransactionSignaturePayloadTaggedTransaction::TxFeeBump(
+                self.to_fee_bump_tx()?,
+            ),
+        };
+
+        tx_sig.to_xdr(xdr::Limits::none())
+    }
+
+    fn hash(&self) -> Result<TxHash, xdr::Error> {
+        Ok(TxHash(Sha256Hasher::hash(self.signature_base()?)))
+    }
+
+    /// Signs the fee bump with `keypairs`, which should include the fee source.
+    fn sign(&mut self, keypairs: &[Keypair]) -> Result<(), Box<dyn Error>> {
+        let tx_hash = self.hash()?;
+        let signatures = keypairs
+            .iter()
+            .map(|kp| kp.sign_decorated(&tx_hash.0))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.signatures.extend(signatures);
+        Ok(())
+    }
+
+    /// Signs the fee bump with external `signers`. Nothing is added if one of them fails.
+    fn sign_with(
+        &mut self,
+        signers: &[&dyn transaction_signer::TransactionSigner],
+    ) -> Result<(), Box<dyn Error>> {
+        let tx_hash = self.hash()?;
+        let signatures = signers
+            .iter()
+            .map(|signer| signer.decorated_signature(&tx_hash.0))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e as Box<dyn Error>)?;
+        self.signatures.extend(signatures);
+        Ok(())
+    }
+
+    /// Signs the fee bump with `signers` that sign asynchronously. Nothing is added if one of
+    /// them fails.
+    async fn sign_with_async(
+        &mut self,
+        signers: &[&dyn transaction_signer::AsyncTransactionSigner],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let tx_hash = self.hash()?;
+        let signatures =
+            transaction_signer::decorated_signatures_async(&tx_hash.0, signers).await?;
+        self.signatures.extend(signatures);
+        Ok(())
+    }
+
+    fn to_envelope(&self) -> Result<xdr::TransactionEnvelope, Box<dyn Error>> {
+        Ok(xdr::TransactionEnvelope::TxFeeBump(
+            xdr::FeeBumpTransactionEnvelope {
+                tx: self.to_fee_bump_tx()?,
+                signatures: self.signatures.clone().try_into()?,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::{Account, AccountBehavior};
+    use crate::network::{NetworkPassphrase, Networks};
+    use crate::operation::Operation;
+    use crate::transaction::{signature_base_from_envelope, verify_envelope_signature};
+    use crate::transaction_builder::{
+        TransactionBuilder, TransactionBuilderBehavior, TIMEOUT_INFINITE,
+    };
+
+    fn inner_transaction(signer: &Keypair) -> Transaction {
+        let mut source = Account::new(&signer.public_key(), "1").unwrap();
+        let mut transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(5).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        transaction.sign(std::slice::from_ref(signer)).unwrap();
+        transaction
+    }
+
+    #[test]
+    fn test_sign_and_round_trip() {
+        let signer = Keypair::random().unwrap();
+        let fee_source = Keypair::random().unwrap();
+        let inner = inner_transaction(&signer);
+
+        let mut fee_bump =
+            FeeBumpTransaction::new(inner.clone(), &fee_source.public_key(), 400).unwrap();
+        fee_bump.sign(std::slice::from_ref(&fee_source)).unwrap();
+        let envelope = fee_bump
+            .to_envelope()
+            .unwrap()
+            .to_xdr_base64(xdr::Limits::none())
+            .unwrap();
+
+        assert_eq!(
+            signature_base_from_envelope(&envelope, Networks::testnet()).unwrap(),
+            fee_bump.signature_base().unwrap()
+        );
+        assert_ne!(fee_bump.hash().unwrap(), inner.hash());
+        assert!(verify_envelope_signature(
+            &envelope,
+            Networks::testnet(),
+            &fee_source.public_key()
+        )
+        .unwrap());
+
+        let decoded =
+            FeeBumpTransaction::from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        assert_eq!(decoded.fee_source, fee_source.public_key());
+        assert_eq!(decoded.fee, 400);
+        assert_eq!(decoded.signatures, fee_bump.signatures);
+        assert_eq!(decoded.inner_transaction.hash(), inner.hash());
+        assert_eq!(decoded.inner_transaction.signatures, inner.signatures);
+        assert_eq!(decoded.hash().unwrap(), fee_bump.hash().unwrap());
+    }
+
+    #[test]
+    fn test_invalid_fee_bumps() {
+        let signer = Keypair::random().unwrap();
+        let inner = inner_transaction(&signer);
+
+        assert!(FeeBumpTransaction::new(inner.clone(), &signer.public_key(), 99).is_err());
+        // Twice the inner fee rate, but below the base fee for two operations.
+        assert!(FeeBumpTransaction::new(inner.clone(), &signer.public_key(), 199).is_err());
+        assert!(FeeBumpTransaction::new(inner.clone(), &signer.public_key(), 200).is_ok());
+
+        let mut expensive = inner.clone();
+        expensive.fee = 1_000;
+        assert!(FeeBumpTransaction::new(expensive.clone(), &signer.public_key(), 1_999).is_err());
+        assert!(FeeBumpTransaction::new(expensive, &signer.public_key(), 2_000).is_ok());
+
+        let mut fee_bump =
+            FeeBumpTransaction::new(inner.clone(), &signer.public_key(), 400).unwrap();
+        fee_bump.fee_source = "GABC".into();
+        assert!(fee_bump.signature_base().is_err());
+        assert!(fee_bump.sign(std::slice::from_ref(&signer)).is_err());
+        assert!(FeeBumpTransaction::new(inner.clone(), "GABC", 400).is_err());
+
+        let envelope = inner.to_envelope_base64().unwrap();
+        assert!(FeeBumpTransaction::from_xdr_envelope(&envelope, Networks::testnet()).is_err());
+    }
+}
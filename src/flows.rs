@@ -0,0 +1,103 @@
+use crate::account::{Account, AccountBehavior};
+use crate::config::Config;
+use crate::operation::Operation;
+use crate::transaction::Transaction;
+use crate::transaction_builder::{TransactionBuilder, TransactionBuilderBehavior};
+
+/// Timeout in seconds of the transactions built by [bump_to] when the config has none.
+pub const BUMP_TIMEOUT: u32 = 30;
+
+/// Returns the lowest bump target invalidating every transaction pre-signed for `account` with
+/// a sequence number up to `highest_presigned`.
+///
+/// The bump transaction itself consumes the next sequence number of `account`, so the target
+/// is never lower than that.
+pub fn min_safe_target(account: &Account, highest_presigned: i64) -> i64 {
+    let current: i64 = account
+        .sequence_number()
+        .parse()
+        .expect("Invalid sequence number");
+    highest_presigned.max(current + 1)
+}
+
+/// Builds a transaction bumping the sequence number of `source` to `sequence_target`, which
+/// invalidates all transactions of `source` with a lower or equal sequence number, e.g.
+/// pre-signed transactions during a key rotation.
+///
+/// The transaction is only valid for the timeout of `config`, or [BUMP_TIMEOUT] seconds, so a
+/// failed submission can be retried with a freshly built transaction without the previous one
+/// landing later. Bumping is idempotent: once the target is reached, further bumps to it have
+/// no effect.
+pub fn bump_to(
+    source: &mut Account,
+    sequence_target: i64,
+    config: &Config,
+) -> Result<Transaction, String> {
+    if sequence_target < 0 {
+        return Err("sequence_target must not be negative".to_string());
+    }
+    let operation = Operation::new()
+        .bump_sequence(sequence_target)
+        .map_err(|_| "sequence_target is invalid".to_string())?;
+
+    let mut builder = TransactionBuilder::with_config(source, config);
+    if config.timeout.is_none() {
+        builder.set_timeout(BUMP_TIMEOUT.into())?;
+    }
+    builder
+        .add_operation(operation)
+        .try_build()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{NetworkPassphrase, Networks};
+    use crate::xdr;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn account(sequence: &str) -> Account {
+        Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            sequence,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_min_safe_target() {
+        let source = account("10");
+        assert_eq!(min_safe_target(&source, 25), 25);
+        assert_eq!(min_safe_target(&source, 5), 11);
+    }
+
+    #[test]
+    fn test_bump_to() {
+        let mut source = account("10");
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let target = min_safe_target(&source, 25);
+        let transaction = bump_to(&mut source, target, &Config::new(Networks::testnet())).unwrap();
+
+        assert_eq!(transaction.sequence.as_deref(), Some("11"));
+        assert_eq!(source.sequence_number(), "11");
+        let max_time = transaction.time_bounds.unwrap().max_time.0;
+        assert!(max_time >= now + u64::from(BUMP_TIMEOUT));
+        assert!(max_time <= now + u64::from(BUMP_TIMEOUT) + 5);
+        assert_eq!(
+            transaction.operations.unwrap()[0].body,
+            xdr::OperationBody::BumpSequence(xdr::BumpSequenceOp {
+                bump_to: xdr::SequenceNumber(25),
+            })
+        );
+
+        let config = Config::new(Networks::testnet()).timeout(5);
+        let transaction = bump_to(&mut source, 25, &config).unwrap();
+        assert!(transaction.time_bounds.unwrap().max_time.0 <= now + 10);
+
+        assert!(bump_to(&mut source, -1, &config).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::hashing::HashingBehavior;
 use std::error::Error;
+use std::fmt;
 
 use crate::xdr;
 use crate::xdr::WriteXdr;
@@ -9,7 +10,113 @@ use crate::hashing::Sha256Hasher;
 
 // Note: you'll need to bring in equivalent Rust libraries/types for xdr, Asset, and hashing.
 use crate::asset::AssetBehavior;
-const LIQUIDITY_POOL_FEE_V18: i32 = 30;
+
+/// The only liquidity pool fee supported by the protocol, in basis points.
+pub const LIQUIDITY_POOL_FEE_V18: i32 = 30;
+
+/// Reasons [LiquidityPoolParameters] are rejected by the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityPoolError {
+    /// The fee is not [LIQUIDITY_POOL_FEE_V18].
+    InvalidFee(i32),
+    /// `asset_a` does not sort strictly before `asset_b`.
+    UnorderedAssets,
+}
+
+impl LiquidityPoolError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LiquidityPoolError::InvalidFee(_) => "fee is invalid",
+            LiquidityPoolError::UnorderedAssets => "Assets are not in lexicographic order",
+        }
+    }
+}
+
+impl fmt::Display for LiquidityPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Error for LiquidityPoolError {}
+
+/// Parameters of a constant product liquidity pool: its two assets and its fee.
+///
+/// The protocol requires `asset_a` to sort strictly before `asset_b` and the fee to be
+/// [LIQUIDITY_POOL_FEE_V18]. [new](Self::new) and [sorted](Self::sorted) enforce this, and
+/// [validate](Self::validate) checks parameters whose fields were set directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityPoolParameters {
+    pub asset_a: Asset,
+    pub asset_b: Asset,
+    pub fee: i32,
+}
+
+impl LiquidityPoolParameters {
+    /// Creates parameters from assets already in protocol order.
+    pub fn new(asset_a: Asset, asset_b: Asset, fee: i32) -> Result<Self, LiquidityPoolError> {
+        let parameters = Self {
+            asset_a,
+            asset_b,
+            fee,
+        };
+        parameters.validate()?;
+        Ok(parameters)
+    }
+
+    /// Creates parameters from two different assets in any order.
+    pub fn sorted(asset_a: &Asset, asset_b: &Asset, fee: i32) -> Result<Self, LiquidityPoolError> {
+        if Asset::compare(asset_a, asset_b) == 1 {
+            Self::new(asset_b.clone(), asset_a.clone(), fee)
+        } else {
+            Self::new(asset_a.clone(), asset_b.clone(), fee)
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), LiquidityPoolError> {
+        if self.fee != LIQUIDITY_POOL_FEE_V18 {
+            return Err(LiquidityPoolError::InvalidFee(self.fee));
+        }
+        if Asset::compare(&self.asset_a, &self.asset_b) != -1 {
+            return Err(LiquidityPoolError::UnorderedAssets);
+        }
+        Ok(())
+    }
+
+    pub fn from_xdr(parameters: &xdr::LiquidityPoolParameters) -> Result<Self, String> {
+        let xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(parameters) = parameters;
+        Self::new(
+            Asset::from_operation(parameters.asset_a.clone())?,
+            Asset::from_operation(parameters.asset_b.clone())?,
+            parameters.fee,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn to_xdr(&self) -> xdr::LiquidityPoolParameters {
+        xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(
+            xdr::LiquidityPoolConstantProductParameters {
+                asset_a: self.asset_a.to_xdr_object(),
+                asset_b: self.asset_b.to_xdr_object(),
+                fee: self.fee,
+            },
+        )
+    }
+
+    /// Returns the id of the pool, the hash of its type and parameters.
+    pub fn pool_id(&self) -> xdr::PoolId {
+        let xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(parameters) = self.to_xdr();
+        let mut payload = xdr::LiquidityPoolType::LiquidityPoolConstantProduct
+            .to_xdr(xdr::Limits::none())
+            .expect("a pool type always encodes");
+        payload.extend(
+            parameters
+                .to_xdr(xdr::Limits::none())
+                .expect("pool parameters always encode"),
+        );
+        xdr::PoolId(xdr::Hash(Sha256Hasher::hash(payload)))
+    }
+}
 
 // Define a trait for Liquidity Pool behavior
 pub trait LiquidityPoolBehavior {
@@ -35,41 +142,74 @@ impl LiquidityPoolBehavior for LiquidityPool {
                 "liquidityPoolType is invalid",
             )));
         }
-        let xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(liquidity_pool_parametes_x) =
-            liquidity_pool_parameters.clone();
+        let parameters = LiquidityPoolParameters::from_xdr(&liquidity_pool_parameters)?;
+        Ok(parameters.pool_id().0 .0.to_vec())
+    }
+}
 
-        if liquidity_pool_parametes_x.fee != LIQUIDITY_POOL_FEE_V18 {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "fee is invalid",
-            )));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if Asset::compare(
-            &Asset::from_operation(liquidity_pool_parametes_x.clone().asset_a).unwrap(),
-            &Asset::from_operation(liquidity_pool_parametes_x.clone().asset_b).unwrap(),
-        ) != -1
-        {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Assets are not in lexicographic order",
-            )));
-        }
-        let va_1 = liquidity_pool_parametes_x.clone().asset_a;
-
-        let lp_type_data =
-            xdr::LiquidityPoolType::LiquidityPoolConstantProduct.to_xdr(xdr::Limits::none());
-        let lp_params_data = xdr::LiquidityPoolConstantProductParameters {
-            asset_a: liquidity_pool_parametes_x.clone().asset_a,
-            asset_b: liquidity_pool_parametes_x.clone().asset_b,
-            fee: liquidity_pool_parametes_x.fee,
-        }
-        .to_xdr(xdr::Limits::none());
+    fn assets() -> (Asset, Asset) {
+        (
+            Asset::new(
+                "ARST",
+                Some("GB7TAYRUZGE6TVT7NHP5SMIZRNQA6PLM423EYISAOAP3MKYIQMVYP2JO"),
+            )
+            .unwrap(),
+            Asset::new(
+                "USD",
+                Some("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ"),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parameters_validation() {
+        let (arst, usd) = assets();
+        assert!(LiquidityPoolParameters::new(arst.clone(), usd.clone(), 30).is_ok());
+        assert_eq!(
+            LiquidityPoolParameters::new(usd.clone(), arst.clone(), 30),
+            Err(LiquidityPoolError::UnorderedAssets)
+        );
+        assert_eq!(
+            LiquidityPoolParameters::new(arst.clone(), usd.clone(), 10),
+            Err(LiquidityPoolError::InvalidFee(10))
+        );
+        assert_eq!(
+            LiquidityPoolParameters::sorted(&arst, &arst, 30),
+            Err(LiquidityPoolError::UnorderedAssets)
+        );
 
-        let mut payload = Vec::new();
-        payload.extend(lp_type_data.unwrap());
-        payload.extend(lp_params_data.unwrap());
+        let sorted = LiquidityPoolParameters::sorted(&usd, &arst, 30).unwrap();
+        assert_eq!(sorted.asset_a, arst);
+        assert_eq!(sorted.asset_b, usd);
+
+        let mut parameters = sorted.clone();
+        parameters.fee = 0;
+        assert_eq!(
+            parameters.validate(),
+            Err(LiquidityPoolError::InvalidFee(0))
+        );
+    }
 
-        Ok(Sha256Hasher::hash(payload).to_vec())
+    #[test]
+    fn test_parameters_pool_id() {
+        let (arst, usd) = assets();
+        let parameters = LiquidityPoolParameters::new(arst, usd, 30).unwrap();
+        assert_eq!(
+            LiquidityPoolParameters::from_xdr(&parameters.to_xdr()).unwrap(),
+            parameters
+        );
+        assert_eq!(
+            hex::encode(parameters.pool_id().0 .0),
+            "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7"
+        );
+        assert_eq!(
+            LiquidityPool::get_liquidity_pool_id("constant_product", parameters.to_xdr()).unwrap(),
+            parameters.pool_id().0 .0.to_vec()
+        );
     }
 }
@@ -0,0 +1,358 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+
+use crate::asset::{Asset, AssetBehavior};
+use crate::decoded_operation::{DecodedOperation, DecodedOperationBody};
+use crate::operation::{self, format_amount};
+
+impl DecodedOperation {
+    /// Parses an operation from its representation in Horizon's REST responses, e.g. the
+    /// records of `/accounts/{id}/operations`.
+    ///
+    /// Amounts are normalized to 7 decimals like those decoded from XDR. The source account is
+    /// the one Horizon reports, which is the transaction source when the operation has none of
+    /// its own.
+    ///
+    /// Fails with [operation::Error::InvalidField] naming the missing or invalid field, or
+    /// `type` if the operation type is not supported.
+    pub fn from_horizon_json(value: serde_json::Value) -> Result<Self, operation::Error> {
+        let source_account = value
+            .get("source_account_muxed")
+            .or_else(|| value.get("source_account"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        let body = match string(&value, "type")? {
+            "create_account" => DecodedOperationBody::CreateAccount {
+                destination: string(&value, "account")?.to_string(),
+                starting_balance: amount(&value, "starting_balance")?,
+            },
+            "payment" => DecodedOperationBody::Payment {
+                destination: muxed(&value, "to")?,
+                asset: asset(&value, "")?,
+                amount: amount(&value, "amount")?,
+            },
+            "path_payment_strict_receive" => DecodedOperationBody::PathPaymentStrictReceive {
+                send_asset: asset(&value, "source_")?,
+                send_max: amount(&value, "source_max")?,
+                destination: muxed(&value, "to")?,
+                dest_asset: asset(&value, "")?,
+                dest_amount: amount(&value, "amount")?,
+                path: path(&value)?,
+            },
+            "path_payment_strict_send" => DecodedOperationBody::PathPaymentStrictSend {
+                send_asset: asset(&value, "source_")?,
+                send_amount: amount(&value, "source_amount")?,
+                destination: muxed(&value, "to")?,
+                dest_asset: asset(&value, "")?,
+                dest_min: amount(&value, "destination_min")?,
+                path: path(&value)?,
+            },
+            "manage_sell_offer" => DecodedOperationBody::ManageSellOffer {
+                selling: asset(&value, "selling_")?,
+                buying: asset(&value, "buying_")?,
+                amount: amount(&value, "amount")?,
+                price: price(&value)?,
+                offer_id: integer(&value, "offer_id")?,
+            },
+            "manage_buy_offer" => DecodedOperationBody::ManageBuyOffer {
+                selling: asset(&value, "selling_")?,
+                buying: asset(&value, "buying_")?,
+                buy_amount: amount(&value, "amount")?,
+                price: price(&value)?,
+                offer_id: integer(&value, "offer_id")?,
+            },
+            "create_passive_sell_offer" => DecodedOperationBody::CreatePassiveSellOffer {
+                selling: asset(&value, "selling_")?,
+                buying: asset(&value, "buying_")?,
+                amount: amount(&value, "amount")?,
+                price: price(&value)?,
+            },
+            "change_trust" => DecodedOperationBody::ChangeTrust {
+                asset: asset(&value, "")?,
+                limit: amount(&value, "limit")?,
+            },
+            "account_merge" => DecodedOperationBody::AccountMerge {
+                destination: muxed(&value, "into")?,
+            },
+            "manage_data" => DecodedOperationBody::ManageData {
+                name: string(&value, "name")?.to_string(),
+                value: match value.get("value").and_then(Value::as_str) {
+                    Some(data) => Some(STANDARD.decode(data).map_err(|_| invalid("value"))?),
+                    None => None,
+                },
+            },
+            "bump_sequence" => DecodedOperationBody::BumpSequence {
+                bump_to: integer(&value, "bump_to")?,
+            },
+            _ => return Err(invalid("type")),
+        };
+
+        Ok(Self {
+            source_account,
+            body,
+        })
+    }
+}
+
+fn invalid(field: &str) -> operation::Error {
+    operation::Error::InvalidField(field.into())
+}
+
+fn string<'v>(value: &'v Value, field: &str) -> Result<&'v str, operation::Error> {
+    value
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(field))
+}
+
+/// Returns the `M...` address of `field` if Horizon reports one, its `G...` address otherwise.
+fn muxed(value: &Value, field: &str) -> Result<String, operation::Error> {
+    match value.get(format!("{field}_muxed")).and_then(Value::as_str) {
+        Some(address) => Ok(address.to_string()),
+        None => Ok(string(value, field)?.to_string()),
+    }
+}
+
+/// Integers such as offer ids and sequence numbers are strings in recent Horizon versions and
+/// numbers in older ones.
+fn integer(value: &Value, field: &str) -> Result<i64, operation::Error> {
+    match value.get(field) {
+        Some(Value::String(number)) => number.parse().map_err(|_| invalid(field)),
+        Some(Value::Number(number)) => number.as_i64().ok_or_else(|| invalid(field)),
+        _ => Err(invalid(field)),
+    }
+}
+
+fn amount(value: &Value, field: &str) -> Result<String, operation::Error> {
+    Ok(format_amount(parse_amount(string(value, field)?, field)?))
+}
+
+/// Converts a decimal `amount` with up to 7 digits after the point, e.g. `"10.5000000"`, to
+/// stroops. Fails with [operation::Error::InvalidField] naming `field`.
+pub(crate) fn parse_amount(amount: &str, field: &str) -> Result<i64, operation::Error> {
+    let (units, decimals) = amount.split_once('.').unwrap_or((amount, ""));
+    if units.is_empty()
+        || decimals.len() > 7
+        || !units
+            .bytes()
+            .chain(decimals.bytes())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid(field));
+    }
+    let units: i64 = units.parse().map_err(|_| invalid(field))?;
+    let decimals: i64 = format!("{decimals:0<7}")
+        .parse()
+        .map_err(|_| invalid(field))?;
+    units
+        .checked_mul(operation::ONE)
+        .and_then(|stroops| stroops.checked_add(decimals))
+        .ok_or_else(|| invalid(field))
+}
+
+fn price(value: &Value) -> Result<(i32, i32), operation::Error> {
+    let price = value.get("price_r").ok_or_else(|| invalid("price_r"))?;
+    let part = |name: &str| {
+        price
+            .get(name)
+            .and_then(Value::as_i64)
+            .and_then(|part| i32::try_from(part).ok())
+            .ok_or_else(|| invalid("price_r"))
+    };
+    Ok((part("n")?, part("d")?))
+}
+
+/// Parses the asset described by the `{prefix}asset_type`, `{prefix}asset_code` and
+/// `{prefix}asset_issuer` fields.
+fn asset(value: &Value, prefix: &str) -> Result<Asset, operation::Error> {
+    let field = format!("{prefix}asset");
+    match string(value, &format!("{field}_type"))? {
+        "native" => Ok(Asset::native()),
+        "credit_alphanum4" | "credit_alphanum12" => Asset::new(
+            string(value, &format!("{field}_code"))?,
+            Some(string(value, &format!("{field}_issuer"))?),
+        )
+        .map_err(|_| invalid(&field)),
+        _ => Err(invalid(&format!("{field}_type"))),
+    }
+}
+
+fn path(value: &Value) -> Result<Vec<Asset>, operation::Error> {
+    match value.get("path") {
+        Some(Value::Array(assets)) => assets.iter().map(|asset_| asset(asset_, "")).collect(),
+        None => Ok(Vec::new()),
+        _ => Err(invalid("path")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operation::Operation;
+    use serde_json::json;
+
+    const SOURCE: &str = "GB7TAYRUZGE6TVT7NHP5SMIZRNQA6PLM423EYISAOAP3MKYIQMVYP2JO";
+    const DESTINATION: &str = "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2";
+    const ISSUER: &str = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+
+    #[test]
+    fn test_payment() {
+        let parsed = DecodedOperation::from_horizon_json(json!({
+            "id": "12884905985",
+            "type": "payment",
+            "type_i": 1,
+            "source_account": SOURCE,
+            "from": SOURCE,
+            "to": DESTINATION,
+            "asset_type": "credit_alphanum4",
+            "asset_code": "USD",
+            "asset_issuer": ISSUER,
+            "amount": "10.5000000"
+        }))
+        .unwrap();
+
+        let usd = Asset::new("USD", Some(ISSUER)).unwrap();
+        assert_eq!(
+            parsed,
+            DecodedOperation {
+                source_account: Some(SOURCE.to_string()),
+                body: DecodedOperationBody::Payment {
+                    destination: DESTINATION.to_string(),
+                    asset: usd.clone(),
+                    amount: "10.5000000".to_string(),
+                },
+            }
+        );
+        assert_eq!(
+            parsed.to_operation().unwrap(),
+            Operation::with_source(SOURCE)
+                .unwrap()
+                .payment(DESTINATION, &usd, 105_000_000)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_offer_and_path_payment() {
+        let offer = DecodedOperation::from_horizon_json(json!({
+            "type": "manage_sell_offer",
+            "source_account": SOURCE,
+            "amount": "100.0000000",
+            "price": "0.5000000",
+            "price_r": { "n": 1, "d": 2 },
+            "buying_asset_type": "native",
+            "selling_asset_type": "credit_alphanum4",
+            "selling_asset_code": "USD",
+            "selling_asset_issuer": ISSUER,
+            "offer_id": "0"
+        }))
+        .unwrap();
+        let usd = Asset::new("USD", Some(ISSUER)).unwrap();
+        assert_eq!(
+            offer.to_operation().unwrap(),
+            Operation::with_source(SOURCE)
+                .unwrap()
+                .manage_sell_offer(&usd, &Asset::native(), 1_000_000_000, (1, 2), 0)
+                .unwrap()
+        );
+
+        let payment = DecodedOperation::from_horizon_json(json!({
+            "type": "path_payment_strict_send",
+            "source_account": SOURCE,
+            "to": DESTINATION,
+            "asset_type": "native",
+            "amount": "5.0000000",
+            "source_asset_type": "credit_alphanum4",
+            "source_asset_code": "USD",
+            "source_asset_issuer": ISSUER,
+            "source_amount": "1",
+            "destination_min": "4.9",
+            "path": [{ "asset_type": "native" }]
+        }))
+        .unwrap();
+        assert_eq!(
+            payment.to_operation().unwrap(),
+            Operation::with_source(SOURCE)
+                .unwrap()
+                .path_payment_strict_send(
+                    &usd,
+                    10_000_000,
+                    DESTINATION,
+                    &Asset::native(),
+                    49_000_000,
+                    &[&Asset::native()],
+                )
+                .unwrap()
+        );
+        // Horizon amounts are normalized, so the record matches the decoded operation.
+        assert_eq!(
+            DecodedOperation::from_xdr(&payment.to_operation().unwrap()).unwrap(),
+            payment
+        );
+    }
+
+    #[test]
+    fn test_manage_data_and_bump_sequence() {
+        let set = DecodedOperation::from_horizon_json(json!({
+            "type": "manage_data",
+            "source_account": SOURCE,
+            "name": "config",
+            "value": "aGVsbG8="
+        }))
+        .unwrap();
+        assert_eq!(
+            set.body,
+            DecodedOperationBody::ManageData {
+                name: "config".to_string(),
+                value: Some(b"hello".to_vec()),
+            }
+        );
+
+        let delete = DecodedOperation::from_horizon_json(json!({
+            "type": "manage_data",
+            "name": "config",
+            "value": null
+        }))
+        .unwrap();
+        assert_eq!(delete.source_account, None);
+        assert_eq!(
+            delete.to_operation().unwrap(),
+            Operation::new().manage_data("config", None).unwrap()
+        );
+
+        let bump = DecodedOperation::from_horizon_json(json!({
+            "type": "bump_sequence",
+            "bump_to": "1234"
+        }))
+        .unwrap();
+        assert_eq!(
+            bump.body,
+            DecodedOperationBody::BumpSequence { bump_to: 1234 }
+        );
+    }
+
+    #[test]
+    fn test_invalid_records() {
+        let cases = [
+            (json!({ "type": "inflation" }), "type"),
+            (
+                json!({ "type": "create_account", "account": DESTINATION, "starting_balance": "1.12345678" }),
+                "starting_balance",
+            ),
+            (
+                json!({ "type": "payment", "to": DESTINATION, "amount": "1", "asset_type": "liquidity_pool_shares" }),
+                "asset_type",
+            ),
+            (json!({ "type": "bump_sequence" }), "bump_to"),
+            (json!({}), "type"),
+        ];
+
+        for (record, field) in cases {
+            assert_eq!(
+                DecodedOperation::from_horizon_json(record),
+                Err(invalid(field))
+            );
+        }
+    }
+}
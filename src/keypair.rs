@@ -5,6 +5,7 @@
 use crate::hashing::HashingBehavior;
 use crate::hashing::Sha256Hasher;
 use crate::signing::{generate, sign, verify};
+use crate::transaction_signer::TransactionSigner;
 use crate::xdr;
 use crate::xdr::WriteXdr;
 use hex::FromHex;
@@ -12,19 +13,87 @@ use rand_core::TryRngCore;
 use rand_core::{OsRng, RngCore};
 use sha2::Sha512;
 use std::str;
-use std::{error::Error, str::FromStr};
+use std::{error::Error, fmt, str::FromStr};
 use stellar_strkey::{
     ed25519::{PrivateKey, PublicKey},
     Strkey,
 };
 
-#[derive(Debug, Clone)]
+/// Why a strkey given to [from_secret](KeypairBehavior::from_secret) or
+/// [from_public_key](KeypairBehavior::from_public_key) was rejected.
+///
+/// These methods return it boxed, so it can be recovered with `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {
+    /// Not base32 in the strkey alphabet.
+    InvalidEncoding,
+    /// Does not decode to a version byte, 32 key bytes and a checksum.
+    InvalidLength,
+    /// A valid strkey of another type, e.g. a public key where a secret seed is expected.
+    InvalidVersion,
+    /// The checksum does not match, usually because of a typo.
+    InvalidChecksum,
+}
+
+impl KeyError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyError::InvalidEncoding => "key is not a base32 strkey",
+            KeyError::InvalidLength => "key has an invalid length",
+            KeyError::InvalidVersion => "key has an invalid version byte",
+            KeyError::InvalidChecksum => "key has an invalid checksum",
+        }
+    }
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Error for KeyError {}
+
+/// Length of an ed25519 strkey: a version byte, 32 key bytes and a 2 byte checksum in base32.
+const ED25519_STRKEY_LEN: usize = 56;
+
+/// Tells why `key` was rejected as an ed25519 strkey starting with `prefix`.
+///
+/// Only called once `stellar_strkey` failed to decode it. A key that decodes as another kind
+/// of strkey, or starts with another letter, has the wrong version, so a key of the right
+/// alphabet, length and version can only have a bad checksum.
+fn classify_key_error(key: &str, prefix: char) -> KeyError {
+    if !key.bytes().all(|c| matches!(c, b'A'..=b'Z' | b'2'..=b'7')) {
+        KeyError::InvalidEncoding
+    } else if key.len() != ED25519_STRKEY_LEN {
+        KeyError::InvalidLength
+    } else if Strkey::from_string(key).is_ok() || !key.starts_with(prefix) {
+        KeyError::InvalidVersion
+    } else {
+        KeyError::InvalidChecksum
+    }
+}
+
+#[derive(Clone)]
 pub struct Keypair {
     public_key: Vec<u8>,
     secret_key: Option<Vec<u8>>,
     secret_seed: Option<Vec<u8>>,
 }
 
+const REDACTED: &str = "<redacted>";
+
+/// Prints the public key and whether the secret is known, never the secret itself.
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keypair")
+            .field("public_key", &self.public_key())
+            .field("secret_key", &self.secret_key.as_ref().map(|_| REDACTED))
+            .field("secret_seed", &self.secret_seed.as_ref().map(|_| REDACTED))
+            .finish()
+    }
+}
+
 pub trait KeypairBehavior {
     // Creates a new keypair given optional public and secret keys
     fn new(
@@ -106,10 +175,13 @@ pub trait KeypairBehavior {
     fn signature_hint(&self) -> Option<Vec<u8>>;
 
     // Returns the decorated signature (hint+sig) for arbitrary data
-    fn sign_decorated(&self, data: &[u8]) -> xdr::DecoratedSignature;
+    fn sign_decorated(&self, data: &[u8]) -> Result<xdr::DecoratedSignature, Box<dyn Error>>;
 
     // Returns the raw decorated signature (hint+sig) for a signed payload signer
-    fn sign_payload_decorated(&self, data: &[u8]) -> xdr::DecoratedSignature;
+    fn sign_payload_decorated(
+        &self,
+        data: &[u8],
+    ) -> Result<xdr::DecoratedSignature, Box<dyn Error>>;
 }
 
 impl KeypairBehavior for Keypair {
@@ -180,17 +252,20 @@ impl KeypairBehavior for Keypair {
     }
 
     /// Create Keypair obj from secret key
+    ///
+    /// Fails with a [KeyError] if `secret` is not an `S...` strkey.
     fn from_secret(secret: &str) -> Result<Self, Box<dyn Error>> {
-        let raw_secret = PrivateKey::from_str(secret).unwrap().0;
-        Keypair::from_raw_ed25519_seed(&raw_secret)
+        let raw_secret =
+            PrivateKey::from_string(secret).map_err(|_| classify_key_error(secret, 'S'))?;
+        Keypair::from_raw_ed25519_seed(&raw_secret.0)
     }
 
     /// Create Keypair obj from given public key
+    ///
+    /// Fails with a [KeyError] if `public_key` is not a `G...` strkey.
     fn from_public_key(public_key: &str) -> Result<Self, Box<dyn Error>> {
-        let decoded = PublicKey::from_str(public_key)?;
-        if decoded.0.len() != 32 {
-            return Err("Invalid Stellar public key".into());
-        }
+        let decoded =
+            PublicKey::from_string(public_key).map_err(|_| classify_key_error(public_key, 'G'))?;
 
         Ok(Self {
             public_key: decoded.0.to_vec(),
@@ -317,27 +392,34 @@ impl KeypairBehavior for Keypair {
     }
 
     /// Returns the decorated signature (hint+sig) for arbitrary data.
-    fn sign_decorated(&self, data: &[u8]) -> xdr::DecoratedSignature {
-        let signature = Self::sign(self, data).unwrap();
-        let hint = Self::signature_hint(self).unwrap();
+    ///
+    /// Fails if the keypair has no secret key.
+    fn sign_decorated(&self, data: &[u8]) -> Result<xdr::DecoratedSignature, Box<dyn Error>> {
+        let signature = Self::sign(self, data)?;
+        let hint = Self::signature_hint(self).ok_or("invalid public key")?;
         let mut hint_u8: [u8; 4] = [0; 4];
         hint_u8.copy_from_slice(&hint[..4]);
         let val = xdr::SignatureHint::from(hint_u8);
-        let signature_xdr = xdr::Signature::try_from(signature).unwrap();
-        xdr::DecoratedSignature {
+        let signature_xdr = xdr::Signature::try_from(signature)?;
+        Ok(xdr::DecoratedSignature {
             hint: val,
             signature: signature_xdr,
-        }
+        })
     }
 
     /// Returns the raw decorated signature (hint+sig) for a signed payload signer.
-    fn sign_payload_decorated(&self, data: &[u8]) -> xdr::DecoratedSignature {
-        let signature = Self::sign(self, data).unwrap();
-        let hint = Self::signature_hint(self).unwrap();
+    ///
+    /// Fails if the keypair has no secret key.
+    fn sign_payload_decorated(
+        &self,
+        data: &[u8],
+    ) -> Result<xdr::DecoratedSignature, Box<dyn Error>> {
+        let signature = Self::sign(self, data)?;
+        let hint = Self::signature_hint(self).ok_or("invalid public key")?;
         let mut key_hint_u8: [u8; 4] = [0; 4];
         key_hint_u8.copy_from_slice(&hint[..4]);
         let val = xdr::SignatureHint::from(key_hint_u8);
-        let signature_xdr = xdr::Signature::try_from(signature).unwrap();
+        let signature_xdr = xdr::Signature::try_from(signature)?;
         let mut hint: [u8; 4] = [0; 4];
 
         if data.len() >= 4 {
@@ -356,13 +438,55 @@ impl KeypairBehavior for Keypair {
 
         let val = xdr::SignatureHint::from(hint);
 
-        xdr::DecoratedSignature {
+        Ok(xdr::DecoratedSignature {
             hint: val,
             signature: signature_xdr,
+        })
+    }
+}
+
+impl TransactionSigner for Keypair {
+    fn signer_public_key(&self) -> String {
+        self.public_key()
+    }
+
+    fn sign_payload(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        match &self.secret_key {
+            Some(secret_key) => Ok(sign(data, secret_key).to_vec()),
+            None => Err("cannot sign, no secret_key available".into()),
         }
     }
 }
 
+impl Keypair {
+    /// Describes the keypair for logs: its public key and whether it can sign.
+    pub fn to_redacted_string(&self) -> String {
+        if self.can_sign() {
+            format!("{} (secret {REDACTED})", self.public_key())
+        } else {
+            self.public_key()
+        }
+    }
+
+    /// Derives the keypair of the account `account_index` from a BIP-39 mnemonic `phrase` and
+    /// its optional `passphrase`, following [SEP-5](crate::sep5).
+    ///
+    /// Fails with a [MnemonicError](crate::sep5::MnemonicError) if `phrase` is not a valid
+    /// English BIP-39 mnemonic.
+    #[cfg(feature = "sep5")]
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        account_index: u32,
+    ) -> Result<Self, Box<dyn Error>> {
+        if account_index >= 0x8000_0000 {
+            return Err("account index must be below 2^31".into());
+        }
+        let seed = crate::sep5::seed_from_mnemonic(phrase, passphrase)?;
+        Self::from_raw_ed25519_seed(&crate::sep5::derive_account_seed(&seed, account_index))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -396,19 +520,67 @@ mod tests {
         assert_eq!(keypair.secret_key().unwrap().as_str(), secret);
     }
 
+    fn key_error(result: Result<Keypair, Box<dyn Error>>) -> KeyError {
+        *result.unwrap_err().downcast_ref::<KeyError>().unwrap()
+    }
+
     #[test]
-    #[should_panic]
     fn test_create_keypair_from_invalid_secret() {
         let invalid_secrets = [
-            "hel0",
-            "SBWUBZ3SIPLLF5CCXLWUB2Z6UBTYAW34KVXOLRQ5HDAZG4ZY7MHNBWJ1",
-            "masterpassphrasemasterpassphrase",
-            "gsYRSEQhTffqA9opPepAENCr2WG6z5iBHHubxxbRzWaHf8FBWcu",
+            ("hel0", KeyError::InvalidEncoding),
+            (
+                "SBWUBZ3SIPLLF5CCXLWUB2Z6UBTYAW34KVXOLRQ5HDAZG4ZY7MHNBWJ1",
+                KeyError::InvalidEncoding,
+            ),
+            (
+                "masterpassphrasemasterpassphrase",
+                KeyError::InvalidEncoding,
+            ),
+            (
+                "gsYRSEQhTffqA9opPepAENCr2WG6z5iBHHubxxbRzWaHf8FBWcu",
+                KeyError::InvalidEncoding,
+            ),
+            (
+                "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJ",
+                KeyError::InvalidLength,
+            ),
+            (
+                "GDFQVQCYYB7GKCGSCUSIQYXTPLV5YJ3XWDMWGQMDNM4EAXAL7LITIBQ7",
+                KeyError::InvalidVersion,
+            ),
+            (
+                "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJPXHILY37",
+                KeyError::InvalidChecksum,
+            ),
         ];
-        Keypair::from_secret(invalid_secrets[0]).unwrap();
-        Keypair::from_secret(invalid_secrets[1]).unwrap();
-        Keypair::from_secret(invalid_secrets[2]).unwrap();
-        Keypair::from_secret(invalid_secrets[3]).unwrap();
+        for (secret, error) in invalid_secrets {
+            assert_eq!(key_error(Keypair::from_secret(secret)), error, "{secret}");
+        }
+    }
+
+    #[test]
+    fn test_create_keypair_from_invalid_public_key_errors() {
+        let secret = "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJPXHILY36";
+        assert_eq!(
+            key_error(Keypair::from_public_key(secret)),
+            KeyError::InvalidVersion
+        );
+        assert_eq!(
+            key_error(Keypair::from_public_key(
+                "GDFQVQCYYB7GKCGSCUSIQYXTPLV5YJ3XWDMWGQMDNM4EAXAL7LITIBQA"
+            )),
+            KeyError::InvalidChecksum
+        );
+        assert_eq!(
+            key_error(Keypair::from_public_key("GDFQVQCY")),
+            KeyError::InvalidLength
+        );
+        assert_eq!(
+            key_error(Keypair::from_public_key(
+                "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE"
+            )),
+            KeyError::InvalidVersion
+        );
     }
 
     #[test]
@@ -476,7 +648,57 @@ mod tests {
         let the_secret = "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJPXHILY36";
         let kp = Keypair::from_secret(the_secret).unwrap();
         let message = "test post please ignore".as_bytes();
-        let sign: xdr::DecoratedSignature = kp.sign_decorated(message);
+        let sign: xdr::DecoratedSignature = kp.sign_decorated(message).unwrap();
         assert_eq!(sign.hint.0.to_vec(), vec![0x0B, 0xFA, 0xD1, 0x34]);
+
+        let public_only = Keypair::from_public_key(&kp.public_key()).unwrap();
+        assert!(public_only.sign_decorated(message).is_err());
+        assert!(public_only.sign_payload_decorated(message).is_err());
+    }
+
+    #[cfg(feature = "sep5")]
+    #[test]
+    fn test_from_mnemonic() {
+        let phrase = "illness spike retreat truth genius clock brain pass fit cave bargain toe";
+        let keypair = Keypair::from_mnemonic(phrase, "", 0).unwrap();
+        assert_eq!(
+            keypair.public_key(),
+            "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6"
+        );
+        assert_eq!(
+            keypair.secret_key().unwrap(),
+            "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN"
+        );
+        assert_eq!(
+            Keypair::from_mnemonic(phrase, "", 1).unwrap().public_key(),
+            "GBAW5XGWORWVFE2XTJYDTLDHXTY2Q2MO73HYCGB3XMFMQ562Q2W2GJQX"
+        );
+        assert!(Keypair::from_mnemonic(phrase, "", 1 << 31).is_err());
+        assert!(Keypair::from_mnemonic(&phrase.replace("toe", "zoo"), "", 0).is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let secret = "SD7X7LEHBNMUIKQGKPARG5TDJNBHKC346OUARHGZL5ITC6IJPXHILY36";
+        let public_key = "GDFQVQCYYB7GKCGSCUSIQYXTPLV5YJ3XWDMWGQMDNM4EAXAL7LITIBQ7";
+        let keypair = Keypair::from_secret(secret).unwrap();
+
+        let debug = format!("{keypair:?} {keypair:#?}");
+        assert!(!debug.contains(secret));
+        assert!(!debug.contains(&hex::encode(keypair.raw_secret_key().unwrap())));
+        assert_eq!(
+            format!("{keypair:?}"),
+            format!(
+                "Keypair {{ public_key: \"{public_key}\", secret_key: Some(\"<redacted>\"), secret_seed: Some(\"<redacted>\") }}"
+            )
+        );
+        assert_eq!(
+            keypair.to_redacted_string(),
+            format!("{public_key} (secret <redacted>)")
+        );
+
+        let public_only = Keypair::from_public_key(public_key).unwrap();
+        assert!(format!("{public_only:?}").contains("secret_seed: None"));
+        assert_eq!(public_only.to_redacted_string(), public_key);
     }
 }
@@ -0,0 +1,203 @@
+//! Constructors for the [ledger keys](xdr::LedgerKey) of common entries, as used in Soroban
+//! footprints, `getLedgerEntries` requests and sponsorship revocations.
+//!
+//! Addresses are strkeys and are validated like operation fields, so errors name the
+//! offending argument.
+use std::str::FromStr;
+
+use crate::operation::{self, Error};
+use crate::xdr;
+
+pub fn account(account: &str) -> Result<xdr::LedgerKey, Error> {
+    Ok(xdr::LedgerKey::Account(xdr::LedgerKeyAccount {
+        account_id: operation::parse_account_id(account, "account")?,
+    }))
+}
+
+/// The `trustline` can be:
+/// - an [Asset](crate::asset::Asset)
+/// - a [LiquidityPoolAsset](crate::liquidity_pool_asset::LiquidityPoolAsset)
+pub fn trustline(
+    account: &str,
+    trustline: impl Into<xdr::TrustLineAsset>,
+) -> Result<xdr::LedgerKey, Error> {
+    Ok(xdr::LedgerKey::Trustline(xdr::LedgerKeyTrustLine {
+        account_id: operation::parse_account_id(account, "account")?,
+        asset: trustline.into(),
+    }))
+}
+
+/// Trustline of `account` to the shares of the pool `pool_id`, given in hex as Horizon does or
+/// as an `L...` strkey.
+pub fn pool_share_trustline(account: &str, pool_id: &str) -> Result<xdr::LedgerKey, Error> {
+    let pool_id = match hex::decode(pool_id).ok().and_then(|id| id.try_into().ok()) {
+        Some(id) => xdr::PoolId(xdr::Hash(id)),
+        None => {
+            xdr::PoolId::from_str(pool_id).map_err(|_| Error::InvalidField("pool_id".into()))?
+        }
+    };
+    trustline(account, xdr::TrustLineAsset::PoolShare(pool_id))
+}
+
+pub fn offer(seller: &str, offer_id: i64) -> Result<xdr::LedgerKey, Error> {
+    Ok(xdr::LedgerKey::Offer(xdr::LedgerKeyOffer {
+        seller_id: operation::parse_account_id(seller, "seller")?,
+        offer_id,
+    }))
+}
+
+pub fn data(account: &str, name: &str) -> Result<xdr::LedgerKey, Error> {
+    let data_name = xdr::String64(
+        name.try_into()
+            .map_err(|_| Error::InvalidField("name".into()))?,
+    );
+    Ok(xdr::LedgerKey::Data(xdr::LedgerKeyData {
+        account_id: operation::parse_account_id(account, "account")?,
+        data_name,
+    }))
+}
+
+/// The `balance_id` is a `B...` strkey.
+pub fn claimable_balance(balance_id: &str) -> Result<xdr::LedgerKey, Error> {
+    let balance_id = xdr::ClaimableBalanceId::from_str(balance_id)
+        .map_err(|_| Error::InvalidField("balance_id".into()))?;
+    Ok(xdr::LedgerKey::ClaimableBalance(
+        xdr::LedgerKeyClaimableBalance { balance_id },
+    ))
+}
+
+/// Entry stored under `key` by `contract`.
+pub fn contract_data(
+    contract: &str,
+    key: xdr::ScVal,
+    durability: xdr::ContractDataDurability,
+) -> Result<xdr::LedgerKey, Error> {
+    Ok(xdr::LedgerKey::ContractData(xdr::LedgerKeyContractData {
+        contract: operation::parse_sc_address(contract, "contract")?,
+        key,
+        durability,
+    }))
+}
+
+/// Wasm uploaded with the hash `wasm_hash`.
+pub fn contract_code(wasm_hash: [u8; 32]) -> xdr::LedgerKey {
+    xdr::LedgerKey::ContractCode(xdr::LedgerKeyContractCode {
+        hash: xdr::Hash(wasm_hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::{Asset, AssetBehavior};
+    use crate::contract::{ContractBehavior, Contracts};
+
+    const ACCOUNT: &str = "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB";
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    #[test]
+    fn test_account_keys() {
+        let account_id = xdr::AccountId::from_str(ACCOUNT).unwrap();
+        assert_eq!(
+            account(ACCOUNT).unwrap(),
+            xdr::LedgerKey::Account(xdr::LedgerKeyAccount {
+                account_id: account_id.clone()
+            })
+        );
+        assert_eq!(
+            trustline(ACCOUNT, Asset::native()).unwrap(),
+            xdr::LedgerKey::Trustline(xdr::LedgerKeyTrustLine {
+                account_id: account_id.clone(),
+                asset: xdr::TrustLineAsset::Native,
+            })
+        );
+        let pool_id = "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7";
+        assert_eq!(
+            pool_share_trustline(ACCOUNT, pool_id).unwrap(),
+            xdr::LedgerKey::Trustline(xdr::LedgerKeyTrustLine {
+                account_id: account_id.clone(),
+                asset: xdr::TrustLineAsset::PoolShare(xdr::PoolId(xdr::Hash(
+                    hex::decode(pool_id).unwrap().try_into().unwrap()
+                ))),
+            })
+        );
+        let strkey = stellar_strkey::Strkey::LiquidityPool(stellar_strkey::LiquidityPool(
+            hex::decode(pool_id).unwrap().try_into().unwrap(),
+        ))
+        .to_string();
+        assert_eq!(
+            pool_share_trustline(ACCOUNT, &strkey),
+            pool_share_trustline(ACCOUNT, pool_id)
+        );
+        assert_eq!(
+            offer(ACCOUNT, 12).unwrap(),
+            xdr::LedgerKey::Offer(xdr::LedgerKeyOffer {
+                seller_id: account_id.clone(),
+                offer_id: 12,
+            })
+        );
+        assert_eq!(
+            data(ACCOUNT, "config").unwrap(),
+            xdr::LedgerKey::Data(xdr::LedgerKeyData {
+                account_id,
+                data_name: xdr::String64("config".try_into().unwrap()),
+            })
+        );
+
+        assert!(matches!(
+            account("GBAD"),
+            Err(Error::InvalidStrkey { kind, .. }) if kind == "account"
+        ));
+        assert_eq!(
+            data(ACCOUNT, &"x".repeat(65)),
+            Err(Error::InvalidField("name".into()))
+        );
+        assert_eq!(
+            pool_share_trustline(ACCOUNT, "abc"),
+            Err(Error::InvalidField("pool_id".into()))
+        );
+    }
+
+    #[test]
+    fn test_claimable_balance_key() {
+        let balance_id = stellar_strkey::ClaimableBalance::V0([3; 32]).to_string();
+        assert_eq!(
+            claimable_balance(&balance_id).unwrap(),
+            xdr::LedgerKey::ClaimableBalance(xdr::LedgerKeyClaimableBalance {
+                balance_id: xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash([3; 32])),
+            })
+        );
+        assert!(claimable_balance("nope").is_err());
+    }
+
+    #[test]
+    fn test_contract_keys() {
+        let key = xdr::ScVal::Symbol("balance".try_into().unwrap());
+        let contract = Contracts::new(CONTRACT).unwrap();
+        assert_eq!(
+            contract_data(
+                CONTRACT,
+                key.clone(),
+                xdr::ContractDataDurability::Temporary
+            )
+            .unwrap(),
+            contract.temporary_key(key.clone())
+        );
+        assert_eq!(
+            contract_data(
+                CONTRACT,
+                xdr::ScVal::LedgerKeyContractInstance,
+                xdr::ContractDataDurability::Persistent
+            )
+            .unwrap(),
+            contract.instance_key()
+        );
+        assert!(contract_data("CBAD", key, xdr::ContractDataDurability::Persistent).is_err());
+        assert_eq!(
+            contract_code([7; 32]),
+            xdr::LedgerKey::ContractCode(xdr::LedgerKeyContractCode {
+                hash: xdr::Hash([7; 32])
+            })
+        );
+    }
+}
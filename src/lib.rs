@@ -11,18 +11,47 @@ pub mod address;
 /// or an asset code / issuer account ID pair
 pub mod asset;
 pub mod claimant;
+/// Shared defaults for building transactions
+pub mod config;
 /// `Contract` represents a single contract in the Stellar network
 pub mod contract;
+/// Contract specs and conversion of JSON values to typed contract arguments
+pub mod contract_spec;
+/// Encoding of typed values into account data entries
+pub mod data_entry;
+/// XDR values that may use types of a newer protocol
+pub mod decoded;
+/// Operations of a transaction with decimal amounts and strkey addresses
+pub mod decoded_operation;
+/// Transactions whose fee is paid by another account than their source
+pub mod fee_bump_transaction;
+/// Ready-made transactions for common account maintenance flows
+pub mod flows;
 pub mod get_liquidity_pool;
 pub mod hashing;
+/// Conversion of Horizon REST responses into values of this crate
+pub mod horizon;
 pub mod keypair;
+/// Ledger keys of common entries
+pub mod ledger_key;
 pub mod liquidity_pool_asset;
 pub mod liquidity_pool_id;
 pub mod memo;
 pub mod muxed_account;
 pub mod network;
 pub mod operation;
+/// Invocations of the Stellar Asset Contract of a classic asset
+pub mod sac;
+/// Integers of up to 256 bits as contract values
+pub mod sc_int;
+/// Conversion between Rust values and contract values
+pub mod scval;
+/// Key derivation from BIP-39 mnemonics (SEP-5)
+#[cfg(feature = "sep5")]
+pub mod sep5;
 pub mod signer_key;
+/// Master and additional keys of an account, with their weights and thresholds
+pub mod signer_set;
 pub mod signing;
 pub mod soroban;
 pub mod soroban_data_builder;
@@ -30,6 +59,8 @@ pub mod transaction;
 /// Builder pattern to construct new transactions
 /// that interact with Stellar environment
 pub mod transaction_builder;
+/// Signers holding their keys outside of this crate, e.g. hardware wallets
+pub mod transaction_signer;
 pub mod utils;
 
 /// Re-exporting XDR from stellar-xdr
@@ -42,3 +73,120 @@ pub mod xdr {
 }
 
 mod op_list;
+
+/// Compile-time configuration of the crate, see [features].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Features {
+    /// Version of this crate.
+    pub version: &'static str,
+    /// XDR types are taken from the `next` protocol instead of `curr`.
+    pub next: bool,
+    /// JSON schemas are derived for the XDR types.
+    pub schemars: bool,
+    /// XDR types implement `serde` serialization. Always enabled.
+    pub serde: bool,
+    /// Keypairs can be derived from BIP-39 mnemonics, see [sep5].
+    pub sep5: bool,
+    /// Built for `wasm32`, where signing uses a pure Rust implementation instead of libsodium.
+    pub wasm: bool,
+    /// Signing uses `ed25519-dalek` on every target, see [signing].
+    pub ed25519_dalek: bool,
+    /// Version of the linked `stellar-xdr` crate.
+    pub xdr_version: &'static str,
+    /// Protocol version the XDR definitions are for, i.e. the major version of `stellar-xdr`.
+    pub protocol_version: u32,
+}
+
+/// Returns the features this crate was compiled with and the XDR version it links, so that
+/// integrations loaded at runtime can check they are compatible.
+pub fn features() -> Features {
+    let xdr_version = stellar_xdr::VERSION.pkg;
+    Features {
+        version: env!("CARGO_PKG_VERSION"),
+        next: cfg!(feature = "next"),
+        schemars: cfg!(feature = "schemars"),
+        serde: true,
+        sep5: cfg!(feature = "sep5"),
+        wasm: cfg!(target_arch = "wasm32"),
+        ed25519_dalek: cfg!(feature = "ed25519-dalek"),
+        xdr_version,
+        protocol_version: xdr_version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse().ok())
+            .unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn public_types_are_send_and_sync() {
+        assert_send_sync::<account::Account>();
+        assert_send_sync::<address::Address>();
+        assert_send_sync::<asset::Asset>();
+        assert_send_sync::<claimant::Claimant>();
+        assert_send_sync::<config::Config>();
+        assert_send_sync::<contract::Contracts>();
+        assert_send_sync::<contract_spec::ContractSpec>();
+        assert_send_sync::<contract_spec::Error>();
+        assert_send_sync::<decoded::Decoded<transaction::Transaction>>();
+        assert_send_sync::<decoded_operation::DecodedOperation>();
+        assert_send_sync::<fee_bump_transaction::FeeBumpTransaction>();
+        assert_send_sync::<keypair::Keypair>();
+        assert_send_sync::<keypair::KeyError>();
+        assert_send_sync::<liquidity_pool_asset::LiquidityPoolAsset>();
+        assert_send_sync::<liquidity_pool_id::LiquidityPoolId>();
+        assert_send_sync::<memo::Memo>();
+        assert_send_sync::<muxed_account::MuxedAccount>();
+        assert_send_sync::<operation::Operation>();
+        assert_send_sync::<operation::Error>();
+        assert_send_sync::<sac::SacClient>();
+        assert_send_sync::<sc_int::ScInt>();
+        #[cfg(feature = "sep5")]
+        assert_send_sync::<sep5::MnemonicError>();
+        assert_send_sync::<scval::Error>();
+        assert_send_sync::<signer_key::SignerKey>();
+        assert_send_sync::<signer_set::SignerSet>();
+        assert_send_sync::<soroban::InvocationBuilder<'static>>();
+        assert_send_sync::<soroban::Simulation>();
+        assert_send_sync::<soroban_data_builder::SorobanDataBuilder>();
+        assert_send_sync::<transaction::Transaction>();
+        assert_send_sync::<transaction_builder::TransactionBuilder<'static>>();
+    }
+
+    #[allow(dead_code)]
+    fn signing_futures_are_send(
+        tx: &mut transaction::Transaction,
+        fee_bump: &mut fee_bump_transaction::FeeBumpTransaction,
+        signers: &[&dyn transaction_signer::AsyncTransactionSigner],
+    ) {
+        use fee_bump_transaction::FeeBumpTransactionBehavior;
+        use transaction::TransactionBehavior;
+
+        assert_send(&tx.sign_with_async(signers));
+        assert_send(&fee_bump.sign_with_async(signers));
+        assert_send(&transaction_signer::decorated_signatures_async(
+            &[],
+            signers,
+        ));
+    }
+
+    #[test]
+    fn reports_features() {
+        let features = features();
+        assert_eq!(features.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(features.next, cfg!(feature = "next"));
+        assert!(features.serde);
+        assert_eq!(features.sep5, cfg!(feature = "sep5"));
+        assert_eq!(features.ed25519_dalek, cfg!(feature = "ed25519-dalek"));
+        assert_eq!(features.xdr_version, stellar_xdr::VERSION.pkg);
+        assert_eq!(features.protocol_version, 25);
+    }
+}
@@ -1,36 +1,21 @@
 use crate::asset::Asset;
 use crate::asset::AssetBehavior;
-use crate::get_liquidity_pool::LiquidityPool;
-use crate::get_liquidity_pool::LiquidityPoolBehavior;
+use crate::get_liquidity_pool::LiquidityPoolParameters;
 use crate::xdr;
-const LIQUIDITY_POOL_FEE_V18: i32 = 30;
+
 #[derive(Debug)]
 pub struct LiquidityPoolAsset {
-    asset_a: Asset,
-    asset_b: Asset,
-    fee: i32,
+    parameters: LiquidityPoolParameters,
 }
 
-// TODO: fix that
 impl From<&LiquidityPoolAsset> for xdr::TrustLineAsset {
     fn from(value: &LiquidityPoolAsset) -> Self {
-        let pool_id = LiquidityPool::get_liquidity_pool_id(
-            "constant_product",
-            value.get_liquidity_pool_parameters().clone(),
-        )
-        .unwrap();
-        xdr::TrustLineAsset::PoolShare(xdr::PoolId(xdr::Hash(*pool_id.last_chunk::<32>().unwrap())))
+        xdr::TrustLineAsset::PoolShare(value.parameters.pool_id())
     }
 }
-// TODO: fix that
 impl From<LiquidityPoolAsset> for xdr::TrustLineAsset {
     fn from(value: LiquidityPoolAsset) -> Self {
-        let pool_id = LiquidityPool::get_liquidity_pool_id(
-            "constant_product",
-            value.get_liquidity_pool_parameters().clone(),
-        )
-        .unwrap();
-        xdr::TrustLineAsset::PoolShare(xdr::PoolId(xdr::Hash(*pool_id.last_chunk::<32>().unwrap())))
+        xdr::TrustLineAsset::PoolShare(value.parameters.pool_id())
     }
 }
 impl From<&LiquidityPoolAsset> for xdr::ChangeTrustAsset {
@@ -54,6 +39,7 @@ pub trait LiquidityPoolAssetBehavior {
         Self: Sized;
     fn to_xdr_object(&self) -> xdr::ChangeTrustAsset;
     fn get_liquidity_pool_parameters(&self) -> xdr::LiquidityPoolParameters;
+    fn parameters(&self) -> &LiquidityPoolParameters;
     fn equals(&self, other: &Self) -> bool;
     fn get_asset_type(&self) -> &'static str;
     fn to_string(&self) -> String;
@@ -61,60 +47,35 @@ pub trait LiquidityPoolAssetBehavior {
 
 impl LiquidityPoolAssetBehavior for LiquidityPoolAsset {
     fn new(asset_a: Asset, asset_b: Asset, fee: i32) -> Result<Self, &'static str> {
-        if Asset::compare(&asset_a, &asset_b) != -1 {
-            return Err("Assets are not in lexicographic order");
-        }
-        if fee != LIQUIDITY_POOL_FEE_V18 {
-            return Err("fee is invalid");
-        }
-
-        Ok(LiquidityPoolAsset {
-            asset_a,
-            asset_b,
-            fee,
-        })
+        let parameters =
+            LiquidityPoolParameters::new(asset_a, asset_b, fee).map_err(|e| e.as_str())?;
+        Ok(LiquidityPoolAsset { parameters })
     }
 
     fn from_operation(ct_asset_xdr: &xdr::ChangeTrustAsset) -> Result<LiquidityPoolAsset, String> {
         match ct_asset_xdr {
-            xdr::ChangeTrustAsset::PoolShare(x) => {
-                let xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(val) = x;
-
-                let asset_a = Asset::from_operation(val.asset_a.clone()).unwrap();
-                let asset_b = Asset::from_operation(val.asset_b.clone()).unwrap();
-                let fee = val.fee;
-                Ok(LiquidityPoolAsset::new(asset_a, asset_b, fee)?)
-            }
+            xdr::ChangeTrustAsset::PoolShare(x) => Ok(LiquidityPoolAsset {
+                parameters: LiquidityPoolParameters::from_xdr(x)?,
+            }),
 
             _ => Err("Invalid asset type".to_string()),
         }
     }
 
     fn to_xdr_object(&self) -> xdr::ChangeTrustAsset {
-        let lp_constant_product_params_xdr = xdr::LiquidityPoolConstantProductParameters {
-            asset_a: self.asset_a.to_xdr_object(),
-            asset_b: self.asset_b.to_xdr_object(),
-            fee: self.fee,
-        };
-
-        let lp_params_xdr = xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(
-            lp_constant_product_params_xdr,
-        );
-        xdr::ChangeTrustAsset::PoolShare(lp_params_xdr)
+        xdr::ChangeTrustAsset::PoolShare(self.parameters.to_xdr())
     }
 
     fn get_liquidity_pool_parameters(&self) -> xdr::LiquidityPoolParameters {
-        let lp_constant_product_params_xdr = xdr::LiquidityPoolConstantProductParameters {
-            asset_a: self.asset_a.to_xdr_object(),
-            asset_b: self.asset_b.to_xdr_object(),
-            fee: self.fee,
-        };
+        self.parameters.to_xdr()
+    }
 
-        xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(lp_constant_product_params_xdr)
+    fn parameters(&self) -> &LiquidityPoolParameters {
+        &self.parameters
     }
 
     fn equals(&self, other: &LiquidityPoolAsset) -> bool {
-        self.asset_a == other.asset_a && self.asset_b == other.asset_b && self.fee == other.fee
+        self.parameters == other.parameters
     }
 
     fn get_asset_type(&self) -> &'static str {
@@ -122,12 +83,10 @@ impl LiquidityPoolAssetBehavior for LiquidityPoolAsset {
     }
 
     fn to_string(&self) -> String {
-        let pool_id = LiquidityPool::get_liquidity_pool_id(
-            "constant_product",
-            self.get_liquidity_pool_parameters().clone(),
+        format!(
+            "liquidity_pool:{}",
+            hex::encode(self.parameters.pool_id().0 .0)
         )
-        .unwrap();
-        format!("liquidity_pool:{}", hex::encode(pool_id))
     }
 }
 
@@ -136,6 +95,7 @@ mod tests {
     use xdr::AlphaNum4;
 
     use super::*;
+    use crate::get_liquidity_pool::LIQUIDITY_POOL_FEE_V18;
 
     #[test]
     fn correct_attributes_does_not_panic() {
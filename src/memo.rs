@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use crate::xdr;
 use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
 
 const MEMO_NONE: &str = "none";
 const MEMO_ID: &str = "id";
@@ -17,7 +18,8 @@ pub enum MemoValue {
     ReturnValue(Vec<u8>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Memo {
     memo_type: String,
     value: Option<String>,
@@ -246,18 +248,25 @@ impl MemoBehavior for Memo {
             MEMO_TEXT => Some(xdr::Memo::Text(
                 self.value.clone().unwrap().as_str().try_into().unwrap(),
             )),
-            MEMO_HASH => Some(xdr::Memo::Hash(
-                xdr::Hash::from_str(&hex::encode(self.value.clone().unwrap().as_str())).unwrap(),
-            )),
+            MEMO_HASH => Some(xdr::Memo::Hash(hash_value(self.value.as_deref().unwrap()))),
             // MemoType::MemoReturn => Some(XDRMemo::memo_return(&self._value)),
-            MEMO_RETURN => Some(xdr::Memo::Return(
-                xdr::Hash::from_str(&hex::encode(self.value.clone().unwrap().as_str())).unwrap(),
-            )),
+            MEMO_RETURN => Some(xdr::Memo::Return(hash_value(
+                self.value.as_deref().unwrap(),
+            ))),
             _ => None,
         }
     }
 }
 
+/// Hash values are stored either as the 32 raw bytes or as their 64 character hex encoding.
+fn hash_value(value: &str) -> xdr::Hash {
+    if value.len() == 64 {
+        xdr::Hash::from_str(value).unwrap()
+    } else {
+        xdr::Hash::from_str(&hex::encode(value)).unwrap()
+    }
+}
+
 fn assert_panic<F: FnOnce(), S: AsRef<str>>(f: F, expected_msg: S) {
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
     match result {
@@ -1,25 +1,32 @@
 use crate::xdr;
 use crate::{
-    account::{Account, AccountBehavior},
+    account::{lock_account, Account, AccountBehavior},
     utils::decode_encode_muxed_account::{
         decode_address_to_muxed_account, encode_muxed_account, encode_muxed_account_to_address,
         extract_base_address,
     },
 };
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, Mutex};
 use stellar_strkey::ed25519::PublicKey;
 
+/// A muxed (`M...`) account. The base account is shared behind an `Arc<Mutex<_>>`, so several
+/// muxed accounts, possibly on different threads, track the same sequence number.
 pub struct MuxedAccount {
-    account: Rc<RefCell<Account>>,
+    account: Arc<Mutex<Account>>,
     muxed_xdr: xdr::MuxedAccount,
     m_address: String,
     id: String,
 }
 
 pub trait MuxedAccountBehavior {
-    fn new(
-        base_account: Rc<RefCell<Account>>,
-        id: &str,
+    /// Creates a muxed account from a base account and an id given as a decimal string.
+    fn new(base_account: Arc<Mutex<Account>>, id: &str) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        Self: Sized;
+    /// Creates a muxed account from a base account and a numeric id.
+    fn with_id(
+        base_account: Arc<Mutex<Account>>,
+        id: u64,
     ) -> Result<Self, Box<dyn std::error::Error>>
     where
         Self: Sized;
@@ -30,9 +37,11 @@ pub trait MuxedAccountBehavior {
     where
         Self: Sized;
     fn set_id(&mut self, id: &str) -> Result<(), Box<dyn std::error::Error>>;
-    fn base_account(&self) -> Rc<RefCell<Account>>;
+    fn set_id_u64(&mut self, id: u64) -> Result<(), Box<dyn std::error::Error>>;
+    fn base_account(&self) -> Arc<Mutex<Account>>;
     fn account_id(&self) -> &str;
     fn id(&self) -> &str;
+    fn id_u64(&self) -> u64;
     fn sequence_number(&self) -> String;
     fn increment_sequence_number(&mut self);
     fn to_xdr_object(&self) -> &xdr::MuxedAccount;
@@ -41,18 +50,24 @@ pub trait MuxedAccountBehavior {
 
 impl MuxedAccountBehavior for MuxedAccount {
     fn new(
-        base_account: Rc<RefCell<Account>>,
+        base_account: Arc<Mutex<Account>>,
         id: &str,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let account_id = base_account.borrow().account_id().to_owned();
+        Self::with_id(base_account, parse_id(id)?)
+    }
 
-        let key = PublicKey::from_string(&account_id);
+    fn with_id(
+        base_account: Arc<Mutex<Account>>,
+        id: u64,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let account_id = lock_account(&base_account).account_id().to_owned();
 
-        if key.is_err() {
-            return Err("accountId is invalid".into());
-        }
+        let key = PublicKey::from_string(&account_id).map_err(|_| "accountId is invalid")?;
 
-        let muxed_xdr = encode_muxed_account(&account_id, id);
+        let muxed_xdr = xdr::MuxedAccount::MuxedEd25519(xdr::MuxedAccountMed25519 {
+            id,
+            ed25519: xdr::Uint256(key.0),
+        });
         let m_address = encode_muxed_account_to_address(&muxed_xdr);
 
         Ok(Self {
@@ -71,7 +86,7 @@ impl MuxedAccountBehavior for MuxedAccount {
         let g_address = extract_base_address(m_address)?;
         let id = muxed_account.id;
         let mut account = Account::new(&g_address, sequence_num).unwrap();
-        let account_rc = Rc::new(RefCell::new(account));
+        let account_rc = Arc::new(Mutex::new(account));
 
         let muxed_xdr = encode_muxed_account(&g_address, &id.to_string());
         let m_address = encode_muxed_account_to_address(&muxed_xdr);
@@ -84,28 +99,28 @@ impl MuxedAccountBehavior for MuxedAccount {
     }
 
     fn set_id(&mut self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
-        if !id.chars().all(|c| c.is_ascii_digit()) {
-            return Err("id should be a string representing a number (uint64)".into());
-        }
+        self.set_id_u64(parse_id(id)?)
+    }
 
+    fn set_id_u64(&mut self, id: u64) -> Result<(), Box<dyn std::error::Error>> {
         let val = match &self.muxed_xdr {
             xdr::MuxedAccount::MuxedEd25519(x) => x,
             _ => return Err("Bad XDR".into()),
         };
 
         let muxed_xdr = xdr::MuxedAccount::MuxedEd25519(xdr::MuxedAccountMed25519 {
-            id: id.parse::<u64>().unwrap(),
+            id,
             ed25519: val.ed25519.clone(),
         });
         self.muxed_xdr = muxed_xdr;
 
-        self.m_address = encode_muxed_account_to_address(&self.muxed_xdr); // Replace with your actual encoding function
+        self.m_address = encode_muxed_account_to_address(&self.muxed_xdr);
         self.id = id.to_string();
 
         Ok(())
     }
 
-    fn base_account(&self) -> Rc<RefCell<Account>> {
+    fn base_account(&self) -> Arc<Mutex<Account>> {
         self.account.clone()
     }
 
@@ -117,12 +132,19 @@ impl MuxedAccountBehavior for MuxedAccount {
         &self.id
     }
 
+    fn id_u64(&self) -> u64 {
+        match &self.muxed_xdr {
+            xdr::MuxedAccount::MuxedEd25519(x) => x.id,
+            xdr::MuxedAccount::Ed25519(_) => 0,
+        }
+    }
+
     fn sequence_number(&self) -> String {
-        self.account.borrow().sequence_number()
+        lock_account(&self.account).sequence_number()
     }
 
     fn increment_sequence_number(&mut self) {
-        self.account.borrow_mut().increment_sequence_number();
+        lock_account(&self.account).increment_sequence_number();
     }
 
     fn to_xdr_object(&self) -> &xdr::MuxedAccount {
@@ -130,8 +152,18 @@ impl MuxedAccountBehavior for MuxedAccount {
     }
 
     fn equals(&self, other_muxed_account: &MuxedAccount) -> bool {
-        self.account.borrow().account_id() == other_muxed_account.account.borrow().account_id()
+        // Lock one account at a time, both may share the same base account.
+        let account_id = lock_account(&self.account).account_id().to_owned();
+        account_id == lock_account(&other_muxed_account.account).account_id()
+    }
+}
+
+fn parse_id(id: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err("id should be a string representing a number (uint64)".into());
     }
+    id.parse::<u64>()
+        .map_err(|_| "id should be a string representing a number (uint64)".into())
 }
 
 #[cfg(test)]
@@ -154,12 +186,12 @@ mod tests {
         let mpubkey_id = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAABUTGI4";
 
         let mut base_account = Account::new(pubkey, "1").unwrap();
-        let base_account_rc = Rc::new(RefCell::new(base_account));
+        let base_account_rc = Arc::new(Mutex::new(base_account));
 
         let mut mux =
             MuxedAccount::new(base_account_rc.clone(), "0").expect("Error creating MuxedAccount");
 
-        assert_eq!(mux.base_account().borrow().account_id(), pubkey);
+        assert_eq!(mux.base_account().lock().unwrap().account_id(), pubkey);
         assert_eq!(
             mux.account_id(),
             "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUQ"
@@ -200,31 +232,31 @@ mod tests {
     fn test_sequence_numbers() {
         let pubkey = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
         let base_account = Account::new(pubkey, "12345").unwrap();
-        let base_account_rc = Rc::new(RefCell::new(base_account));
+        let base_account_rc = Arc::new(Mutex::new(base_account));
 
         let mut mux1 = MuxedAccount::new(base_account_rc.clone(), "1").unwrap();
         let mut mux2 = MuxedAccount::new(base_account_rc.clone(), "2").unwrap();
 
-        assert_eq!(base_account_rc.borrow().sequence_number(), "12345");
+        assert_eq!(base_account_rc.lock().unwrap().sequence_number(), "12345");
         assert_eq!(mux1.sequence_number(), "12345");
         assert_eq!(mux2.sequence_number(), "12345");
 
         mux1.increment_sequence_number();
 
         // println!("Checking value {:?}",base_account.sequence_number());
-        assert_eq!(base_account_rc.borrow().sequence_number(), "12346");
+        assert_eq!(base_account_rc.lock().unwrap().sequence_number(), "12346");
         assert_eq!(mux1.sequence_number(), "12346");
         assert_eq!(mux2.sequence_number(), "12346");
 
         mux2.increment_sequence_number();
 
-        assert_eq!(base_account_rc.borrow().sequence_number(), "12347");
+        assert_eq!(base_account_rc.lock().unwrap().sequence_number(), "12347");
         assert_eq!(mux1.sequence_number(), "12347");
         assert_eq!(mux2.sequence_number(), "12347");
 
-        base_account_rc.borrow_mut().increment_sequence_number();
+        base_account_rc.lock().unwrap().increment_sequence_number();
 
-        assert_eq!(base_account_rc.borrow().sequence_number(), "12348");
+        assert_eq!(base_account_rc.lock().unwrap().sequence_number(), "12348");
         assert_eq!(mux1.sequence_number(), "12348");
         assert_eq!(mux2.sequence_number(), "12348");
     }
@@ -236,7 +268,7 @@ mod tests {
         let mpubkey_id = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAABUTGI4";
 
         let base_account = Account::new(pubkey, "12345").unwrap();
-        let base_account_rc = Rc::new(RefCell::new(base_account));
+        let base_account_rc = Arc::new(Mutex::new(base_account));
 
         let mux1 = MuxedAccount::new(base_account_rc.clone(), "1").unwrap();
 
@@ -266,7 +298,63 @@ mod tests {
 
         assert_eq!(mux1.id(), "0");
         assert_eq!(mux1.account_id(), mpubkey_zero);
-        assert_eq!(mux1.base_account().borrow().account_id(), pubkey);
+        assert_eq!(mux1.base_account().lock().unwrap().account_id(), pubkey);
         assert_eq!(mux1.sequence_number(), "123");
     }
+
+    #[test]
+    fn test_numeric_ids() {
+        let pubkey = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let mpubkey_id = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAABUTGI4";
+        let base_account_rc = Arc::new(Mutex::new(Account::new(pubkey, "12345").unwrap()));
+
+        let mut mux = MuxedAccount::with_id(base_account_rc.clone(), 420).unwrap();
+        assert_eq!(mux.id(), "420");
+        assert_eq!(mux.id_u64(), 420);
+        assert_eq!(mux.account_id(), mpubkey_id);
+
+        mux.set_id_u64(u64::MAX).unwrap();
+        assert_eq!(mux.id(), u64::MAX.to_string());
+        assert_eq!(mux.id_u64(), u64::MAX);
+
+        let other = MuxedAccount::new(base_account_rc.clone(), "420").unwrap();
+        assert_eq!(other.id_u64(), 420);
+
+        mux.increment_sequence_number();
+        assert_eq!(other.sequence_number(), "12346");
+    }
+
+    #[test]
+    fn test_invalid_ids() {
+        let pubkey = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let base_account_rc = Arc::new(Mutex::new(Account::new(pubkey, "1").unwrap()));
+
+        for id in ["", "-1", "abc", "18446744073709551616"] {
+            assert!(MuxedAccount::new(base_account_rc.clone(), id).is_err());
+        }
+
+        let mut mux = MuxedAccount::new(base_account_rc, "1").unwrap();
+        assert!(mux.set_id("18446744073709551616").is_err());
+        assert!(mux.set_id("1.5").is_err());
+        assert_eq!(mux.id_u64(), 1);
+    }
+
+    #[test]
+    fn test_poisoned_base_account() {
+        let pubkey = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let base_account = Arc::new(Mutex::new(Account::new(pubkey, "1").unwrap()));
+        let poisoned = Arc::clone(&base_account);
+        std::thread::spawn(move || {
+            let _guard = poisoned.lock().unwrap();
+            panic!("poison the base account");
+        })
+        .join()
+        .unwrap_err();
+        assert!(base_account.is_poisoned());
+
+        let mut mux = MuxedAccount::with_id(Arc::clone(&base_account), 1).unwrap();
+        mux.increment_sequence_number();
+        assert_eq!(mux.sequence_number(), "2");
+        assert!(mux.equals(&MuxedAccount::with_id(base_account, 2).unwrap()));
+    }
 }
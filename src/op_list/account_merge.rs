@@ -12,8 +12,7 @@ impl Operation {
     /// Threshold: High
     pub fn account_merge(&self, destination: &str) -> Result<xdr::Operation, operation::Error> {
         //
-        let muxed = xdr::MuxedAccount::from_str(destination)
-            .map_err(|_| operation::Error::InvalidField("destination".into()))?;
+        let muxed = operation::parse_muxed_account(destination, "destination")?;
         let body = xdr::OperationBody::AccountMerge(muxed);
         Ok(xdr::Operation {
             source_account: self.source.clone(),
@@ -83,7 +82,10 @@ mod tests {
 
         assert_eq!(
             r.err().unwrap(),
-            operation::Error::InvalidField("destination".into())
+            operation::Error::InvalidStrkey {
+                kind: "destination".into(),
+                value: dest.to_string(),
+            }
         );
     }
 }
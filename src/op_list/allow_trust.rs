@@ -17,6 +17,12 @@ impl Operation {
     /// - `2` to authorize to maintain liabilities only,
     /// - `0` to deauthorize.
     ///
+    /// The `asset_code` must be 1 to 12 alphanumeric characters.
+    ///
+    /// Deprecated since protocol 17 in favor of
+    /// [set_trustline_flags](Operation::set_trustline_flags), but still needed to build or
+    /// replay older transactions.
+    ///
     /// Threshold: Low
     pub fn allow_trust(
         &self,
@@ -25,9 +31,11 @@ impl Operation {
         flag: u32,
     ) -> Result<xdr::Operation, operation::Error> {
         //
-        let trustor = xdr::AccountId::from_str(account)
-            .map_err(|_| operation::Error::InvalidField("account".into()))?;
+        let trustor = operation::parse_account_id(account, "account")?;
 
+        if asset_code.is_empty() || !asset_code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(operation::Error::InvalidField("asset_code".into()));
+        }
         let asset = match asset_code {
             a if a.len() <= 4 => {
                 let code = xdr::AssetCode4::from_str(a)
@@ -136,6 +144,21 @@ mod tests {
         );
     }
     #[test]
+    fn test_allow_trust_invalid_code() {
+        let account = Keypair::random().unwrap();
+        let issuer = Keypair::random().unwrap();
+        for asset_code in ["", "AB_", "ABC DEF"] {
+            let op = Operation::with_source(&issuer.public_key())
+                .unwrap()
+                .allow_trust(&account.public_key(), asset_code, 1);
+
+            assert_eq!(
+                op.err(),
+                Some(operation::Error::InvalidField("asset_code".into()))
+            );
+        }
+    }
+    #[test]
     fn test_allow_trust_bad_flag() {
         let account = Keypair::random().unwrap();
         let issuer = Keypair::random().unwrap();
@@ -162,7 +185,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("account".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "account".into(),
+                value: account,
+            })
         );
     }
 }
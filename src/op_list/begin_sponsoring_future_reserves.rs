@@ -17,8 +17,7 @@ impl Operation {
         &self,
         sponsor: &str,
     ) -> Result<xdr::Operation, operation::Error> {
-        let sponsored_id = xdr::AccountId::from_str(sponsor)
-            .map_err(|_| operation::Error::InvalidField("sponsor".into()))?;
+        let sponsored_id = operation::parse_account_id(sponsor, "sponsor")?;
         let begin_sponsorship = xdr::BeginSponsoringFutureReservesOp { sponsored_id };
 
         let body = xdr::OperationBody::BeginSponsoringFutureReserves(begin_sponsorship);
@@ -69,7 +68,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("sponsor".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "sponsor".into(),
+                value: account,
+            })
         )
     }
 }
@@ -1,4 +1,6 @@
 use crate::{
+    asset::Asset,
+    get_liquidity_pool::{LiquidityPoolError, LiquidityPoolParameters},
     operation::{self, Operation},
     xdr,
 };
@@ -32,6 +34,33 @@ impl Operation {
             body,
         })
     }
+
+    /// Creates, updates, or deletes a trustline to the liquidity pool shares of `asset_a` and
+    /// `asset_b`
+    ///
+    /// The assets can be given in any order, they are sorted as required by the protocol. The
+    /// `fee` is in basis points and only `30` is currently supported.
+    ///
+    /// The `limit` will default to MAX i64 if None. A value of 0 (zero) will remove the trustline.
+    ///
+    /// Threshold: Medium
+    pub fn change_trust_pool_share(
+        &self,
+        asset_a: &Asset,
+        asset_b: &Asset,
+        fee: i32,
+        limit: impl Into<Option<i64>>,
+    ) -> Result<xdr::Operation, operation::Error> {
+        let parameters =
+            LiquidityPoolParameters::sorted(asset_a, asset_b, fee).map_err(|e| match e {
+                LiquidityPoolError::InvalidFee(_) => operation::Error::InvalidField("fee".into()),
+                LiquidityPoolError::UnorderedAssets => {
+                    operation::Error::InvalidField("asset_b".into())
+                }
+            })?;
+
+        self.change_trust(xdr::ChangeTrustAsset::PoolShare(parameters.to_xdr()), limit)
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +137,53 @@ mod tests {
         }
     }
     #[test]
+    fn test_change_trust_pool_share() {
+        let issuer = Keypair::random().unwrap().public_key();
+        let asset_a = Asset::new("TEST", Some(&issuer)).unwrap();
+        let asset_b = Asset::new("ANOTHER", Some(&issuer)).unwrap();
+        let liq_asset = LiquidityPoolAsset::new(asset_a.clone(), asset_b.clone(), 30).unwrap();
+
+        let op = Operation::new()
+            .change_trust_pool_share(&asset_b, &asset_a, 30, None)
+            .unwrap();
+
+        if let xdr::OperationBody::ChangeTrust(xdr::ChangeTrustOp { line, limit }) = op.body {
+            assert_eq!(line, liq_asset.into());
+            assert_eq!(limit, i64::MAX);
+        } else {
+            panic!("Fail")
+        }
+
+        let op_sorted = Operation::new()
+            .change_trust_pool_share(&asset_a, &asset_b, 30, None)
+            .unwrap();
+        let op_unsorted = Operation::new()
+            .change_trust_pool_share(&asset_b, &asset_a, 30, None)
+            .unwrap();
+        assert_eq!(op_sorted, op_unsorted);
+    }
+    #[test]
+    fn test_change_trust_pool_share_bad_params() {
+        let issuer = Keypair::random().unwrap().public_key();
+        let asset_a = Asset::new("TEST", Some(&issuer)).unwrap();
+        let asset_b = Asset::new("ANOTHER", Some(&issuer)).unwrap();
+
+        let op = Operation::new().change_trust_pool_share(&asset_a, &asset_b, 31, None);
+        assert_eq!(op.err(), Some(operation::Error::InvalidField("fee".into())));
+
+        let op = Operation::new().change_trust_pool_share(&asset_a, &asset_a, 30, None);
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("asset_b".into()))
+        );
+
+        let op = Operation::new().change_trust_pool_share(&asset_a, &asset_b, 30, -1);
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("limit".into()))
+        );
+    }
+    #[test]
     fn test_change_trust_bad_limit() {
         let asset_issuer = Keypair::random().unwrap();
         let asset = Asset::new("ABC", Some(&asset_issuer.public_key())).unwrap();
@@ -7,16 +7,16 @@ impl Operation {
     /// Claims a ClaimableBalanceEntry that corresponds to the `balance_id` and adds the amount
     /// of an asset on the entry to the source account
     ///
+    /// The `balance_id` can be given as hex, either the 32-byte hash or its 36-byte XDR
+    /// encoding, or as a `B...` strkey.
+    ///
     /// Threshold: Medium
     pub fn claim_claimable_balance(
         &self,
         balance_id: &str,
     ) -> Result<xdr::Operation, operation::Error> {
         //
-        let mut h = [0; 32];
-        hex::decode_to_slice(balance_id, &mut h)
-            .map_err(|_| operation::Error::InvalidField("balance_id".into()))?;
-        let xdr_balance_id = xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash(h));
+        let xdr_balance_id = operation::parse_claimable_balance_id(balance_id)?;
         let body = xdr::OperationBody::ClaimClaimableBalance(xdr::ClaimClaimableBalanceOp {
             balance_id: xdr_balance_id,
         });
@@ -18,12 +18,11 @@ impl Operation {
         from: &str,
     ) -> Result<xdr::Operation, operation::Error> {
         //
-        let asset: xdr::Asset = asset.to_xdr_object();
+        let asset: xdr::Asset = asset.try_to_xdr_object()?;
         if amount < 0 {
             return Err(operation::Error::InvalidAmount(amount));
         }
-        let from = xdr::MuxedAccount::from_str(from)
-            .map_err(|_| operation::Error::InvalidField("from".into()))?;
+        let from = operation::parse_muxed_account(from, "from")?;
         let body = xdr::OperationBody::Clawback(xdr::ClawbackOp {
             asset,
             from,
@@ -125,7 +124,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("from".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "from".into(),
+                value: from,
+            })
         );
     }
 }
@@ -4,15 +4,19 @@ use crate::{
 };
 
 impl Operation {
+    /// Claws back an unclaimed ClaimableBalanceEntry, returning the balance to the asset issuer.
+    /// The entry must have the clawback enabled flag set.
+    ///
+    /// The `balance_id` can be given as hex, either the 32-byte hash or its 36-byte XDR
+    /// encoding, or as a `B...` strkey.
+    ///
+    /// Threshold: Medium
     pub fn clawback_claimable_balance(
         &self,
         balance_id: &str,
     ) -> Result<xdr::Operation, operation::Error> {
         //
-        let mut h = [0; 32];
-        hex::decode_to_slice(balance_id, &mut h)
-            .map_err(|_| operation::Error::InvalidField("balance_id".into()))?;
-        let xdr_balance_id = xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash(h));
+        let xdr_balance_id = operation::parse_claimable_balance_id(balance_id)?;
         let body = xdr::OperationBody::ClawbackClaimableBalance(xdr::ClawbackClaimableBalanceOp {
             balance_id: xdr_balance_id,
         });
@@ -29,6 +33,7 @@ mod tests {
         operation::{self, Operation},
         xdr,
     };
+    use stellar_strkey::{ClaimableBalance, Contract, Strkey};
 
     #[test]
     fn test_clawback_cb() {
@@ -68,4 +73,67 @@ mod tests {
             Some(operation::Error::InvalidField("balance_id".into()))
         );
     }
+
+    #[test]
+    fn test_clawback_cb_id_formats() {
+        let expected = xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash([5; 32]));
+        let ids = [
+            hex::encode([5; 32]),
+            format!("00000000{}", hex::encode([5; 32])),
+            Strkey::ClaimableBalance(ClaimableBalance::V0([5; 32])).to_string(),
+        ];
+
+        for balance_id in ids {
+            let op = Operation::new()
+                .clawback_claimable_balance(&balance_id)
+                .unwrap();
+            assert_eq!(
+                op.body,
+                xdr::OperationBody::ClawbackClaimableBalance(xdr::ClawbackClaimableBalanceOp {
+                    balance_id: expected.clone(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_clawback_cb_id_invalid() {
+        let ids = [
+            format!("00000001{}", hex::encode([5; 32])),
+            Strkey::Contract(Contract([5; 32])).to_string(),
+        ];
+
+        for balance_id in ids {
+            let op = Operation::new().clawback_claimable_balance(&balance_id);
+            assert_eq!(
+                op.err(),
+                Some(operation::Error::InvalidField("balance_id".into()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_clawback_cb_id_invalid_strkey() {
+        let valid = Strkey::ClaimableBalance(ClaimableBalance::V0([5; 32])).to_string();
+        let ids = [
+            "BNOTAREALSTRKEY".to_string(),
+            format!("B{}", valid[1..].to_lowercase()),
+            format!(
+                "{}{}",
+                &valid[..valid.len() - 1],
+                if valid.ends_with('A') { 'B' } else { 'A' }
+            ),
+        ];
+
+        for balance_id in ids {
+            let op = Operation::new().clawback_claimable_balance(&balance_id);
+            assert_eq!(
+                op.err(),
+                Some(operation::Error::InvalidStrkey {
+                    kind: "balance_id".into(),
+                    value: balance_id,
+                })
+            );
+        }
+    }
 }
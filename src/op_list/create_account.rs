@@ -15,8 +15,7 @@ impl Operation {
         if starting_balance.is_negative() {
             return Err(operation::Error::InvalidAmount(starting_balance));
         }
-        let destination = xdr::AccountId::from_str(destination)
-            .map_err(|_| operation::Error::InvalidField("destination".into()))?;
+        let destination = operation::parse_account_id(destination, "destination")?;
         let body = xdr::OperationBody::CreateAccount(xdr::CreateAccountOp {
             destination,
             starting_balance,
@@ -88,7 +87,10 @@ mod tests {
 
         assert_eq!(
             op.err().unwrap(),
-            operation::Error::InvalidField("destination".into())
+            operation::Error::InvalidStrkey {
+                kind: "destination".into(),
+                value: destination.to_string(),
+            }
         );
     }
 }
@@ -1,13 +1,80 @@
 use crate::{
     asset::{Asset, AssetBehavior},
-    claimant::{Claimant, ClaimantBehavior},
+    claimant::{Claimant, ClaimantBehavior, MAX_CLAIMANTS},
+    op_list::{set_options::AccountFlags, set_trustline_flags::TrustlineFlags},
     operation::{self, Operation},
     xdr,
 };
 
+/// Flags of a ClaimableBalanceEntry, see [from_entry](ClaimableBalanceFlags::from_entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimableBalanceFlags {
+    /// The issuer can claw back the balance, inherited from the source account trustline
+    ClawbackEnabled = 1,
+}
+
+impl ClaimableBalanceFlags {
+    /// Returns the flags of a claimable balance ledger entry.
+    pub fn from_entry(entry: &xdr::ClaimableBalanceEntry) -> Option<Self> {
+        let flags = match &entry.ext {
+            xdr::ClaimableBalanceEntryExt::V0 => 0,
+            xdr::ClaimableBalanceEntryExt::V1(ext) => ext.flags,
+        };
+        Self::ClawbackEnabled
+            .is_set(flags)
+            .then_some(Self::ClawbackEnabled)
+    }
+
+    /// Returns the flags of a ledger entry, which must be a claimable balance.
+    pub fn from_ledger_entry(entry: &xdr::LedgerEntry) -> Result<Option<Self>, operation::Error> {
+        match &entry.data {
+            xdr::LedgerEntryData::ClaimableBalance(entry) => Ok(Self::from_entry(entry)),
+            _ => Err(operation::Error::InvalidField("data".into())),
+        }
+    }
+
+    /// Returns the flags a balance of `asset` created by
+    /// [create_claimable_balance](Operation::create_claimable_balance) gets.
+    ///
+    /// `source` is the ledger entry of the source trustline, or of the source account when it
+    /// is the issuer of `asset`. Native balances never get flags.
+    pub fn for_source(
+        asset: &Asset,
+        source: &xdr::LedgerEntry,
+    ) -> Result<Option<Self>, operation::Error> {
+        let clawback = match &source.data {
+            xdr::LedgerEntryData::Trustline(trustline) => {
+                trustline.flags & TrustlineFlags::TrustlineClawbackEnabled as u32 != 0
+            }
+            xdr::LedgerEntryData::Account(account) => {
+                account.flags & AccountFlags::ClawbackEnabled as u32 != 0
+            }
+            _ => return Err(operation::Error::InvalidField("source".into())),
+        };
+        Ok((clawback && !asset.is_native()).then_some(Self::ClawbackEnabled))
+    }
+
+    /// Returns `true` if this flag is set in `flags`.
+    pub fn is_set(self, flags: u32) -> bool {
+        flags & self as u32 != 0
+    }
+}
+
+impl From<ClaimableBalanceFlags> for u32 {
+    fn from(flag: ClaimableBalanceFlags) -> Self {
+        flag as u32
+    }
+}
+
 impl Operation {
     /// Moves an amount of asset from the operation source account into a new ClaimableBalanceEntry
     ///
+    /// The amount must be positive and there must be between 1 and [MAX_CLAIMANTS] claimants.
+    ///
+    /// The entry is created with [ClawbackEnabled](ClaimableBalanceFlags::ClawbackEnabled) when
+    /// the source trustline is clawback enabled, see [ClaimableBalanceFlags::for_source]. The
+    /// flag can be checked on the resulting ledger entry with [ClaimableBalanceFlags::from_entry].
+    ///
     /// Threshold: Medium
     pub fn create_claimable_balance(
         &self,
@@ -16,13 +83,16 @@ impl Operation {
         claimants: Vec<Claimant>,
     ) -> Result<xdr::Operation, operation::Error> {
         //
-        if amount < 0 {
+        if amount <= 0 {
             return Err(operation::Error::InvalidAmount(amount));
         }
+        if claimants.is_empty() || claimants.len() > MAX_CLAIMANTS {
+            return Err(operation::Error::InvalidField("claimants".into()));
+        }
         let xdr_claimants: Vec<xdr::Claimant> =
             claimants.iter().map(|c| c.to_xdr_object()).collect();
         let body = xdr::OperationBody::CreateClaimableBalance(xdr::CreateClaimableBalanceOp {
-            asset: asset.to_xdr_object(),
+            asset: asset.try_to_xdr_object()?,
             amount,
             claimants: xdr_claimants
                 .try_into()
@@ -37,10 +107,12 @@ impl Operation {
 
 #[cfg(test)]
 mod tests {
+    use super::ClaimableBalanceFlags;
     use crate::{
         asset::{Asset, AssetBehavior},
-        claimant::{Claimant, ClaimantBehavior},
+        claimant::{Claimant, ClaimantBehavior, MAX_CLAIMANTS},
         keypair::{Keypair, KeypairBehavior},
+        op_list::{set_options::AccountFlags, set_trustline_flags::TrustlineFlags},
         operation::{self, Operation},
         xdr,
     };
@@ -84,4 +156,173 @@ mod tests {
 
         assert_eq!(op.err(), Some(operation::Error::InvalidAmount(-amount)));
     }
+
+    #[test]
+    fn test_create_cb_zero_amount() {
+        let asset = Asset::native();
+        let account = Keypair::random().unwrap();
+        let claimants = vec![Claimant::new(Some(&account.public_key()), None).unwrap()];
+        let op = Operation::new().create_claimable_balance(&asset, 0, claimants);
+
+        assert_eq!(op.err(), Some(operation::Error::InvalidAmount(0)));
+    }
+
+    #[test]
+    fn test_create_cb_claimant_limits() {
+        let asset = Asset::native();
+        let amount = 100 * operation::ONE;
+
+        let op = Operation::new().create_claimable_balance(&asset, amount, vec![]);
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("claimants".into()))
+        );
+
+        let claimants = |n: usize| -> Vec<Claimant> {
+            (0..n)
+                .map(|_| {
+                    let account = Keypair::random().unwrap();
+                    Claimant::new(Some(&account.public_key()), None).unwrap()
+                })
+                .collect()
+        };
+
+        let op =
+            Operation::new().create_claimable_balance(&asset, amount, claimants(MAX_CLAIMANTS));
+        assert!(op.is_ok());
+
+        let op =
+            Operation::new().create_claimable_balance(&asset, amount, claimants(MAX_CLAIMANTS + 1));
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("claimants".into()))
+        );
+    }
+
+    #[test]
+    fn test_claimable_balance_flags() {
+        let account = Keypair::random().unwrap();
+        let claimant = Claimant::new(Some(&account.public_key()), None).unwrap();
+        let mut entry = xdr::ClaimableBalanceEntry {
+            balance_id: xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash([1; 32])),
+            claimants: vec![claimant.to_xdr_object()].try_into().unwrap(),
+            asset: Asset::native().to_xdr_object(),
+            amount: operation::ONE,
+            ext: xdr::ClaimableBalanceEntryExt::V0,
+        };
+        assert_eq!(ClaimableBalanceFlags::from_entry(&entry), None);
+        assert!(!ClaimableBalanceFlags::ClawbackEnabled.is_set(0));
+
+        entry.ext = xdr::ClaimableBalanceEntryExt::V1(xdr::ClaimableBalanceEntryExtensionV1 {
+            ext: xdr::ClaimableBalanceEntryExtensionV1Ext::V0,
+            flags: ClaimableBalanceFlags::ClawbackEnabled.into(),
+        });
+        let ledger_entry = xdr::LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data: xdr::LedgerEntryData::ClaimableBalance(entry),
+            ext: xdr::LedgerEntryExt::V0,
+        };
+        assert_eq!(
+            ClaimableBalanceFlags::from_ledger_entry(&ledger_entry),
+            Ok(Some(ClaimableBalanceFlags::ClawbackEnabled))
+        );
+
+        let ledger_entry = xdr::LedgerEntry {
+            data: xdr::LedgerEntryData::Data(xdr::DataEntry {
+                account_id: xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256(
+                    [0; 32],
+                ))),
+                data_name: xdr::String64("name".try_into().unwrap()),
+                data_value: xdr::DataValue(Default::default()),
+                ext: xdr::DataEntryExt::V0,
+            }),
+            ..ledger_entry
+        };
+        assert_eq!(
+            ClaimableBalanceFlags::from_ledger_entry(&ledger_entry),
+            Err(operation::Error::InvalidField("data".into()))
+        );
+    }
+
+    #[test]
+    fn test_claimable_balance_flags_for_source() {
+        let issuer = Keypair::random().unwrap();
+        let account = Keypair::random().unwrap();
+        let account_id = |kp: &Keypair| {
+            xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256(
+                kp.raw_pubkey(),
+            )))
+        };
+        let usd = Asset::new("USD", Some(&issuer.public_key())).unwrap();
+        let ledger_entry = |data| xdr::LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data,
+            ext: xdr::LedgerEntryExt::V0,
+        };
+        let trustline = |flags| {
+            ledger_entry(xdr::LedgerEntryData::Trustline(xdr::TrustLineEntry {
+                account_id: account_id(&account),
+                asset: usd.to_trust_line_xdr_object(),
+                balance: operation::ONE,
+                limit: i64::MAX,
+                flags,
+                ext: xdr::TrustLineEntryExt::V0,
+            }))
+        };
+        let issuer_account = |flags| {
+            ledger_entry(xdr::LedgerEntryData::Account(xdr::AccountEntry {
+                account_id: account_id(&issuer),
+                balance: operation::ONE,
+                seq_num: xdr::SequenceNumber(1),
+                num_sub_entries: 0,
+                inflation_dest: None,
+                flags,
+                home_domain: Default::default(),
+                thresholds: xdr::Thresholds([1, 0, 0, 0]),
+                signers: Default::default(),
+                ext: xdr::AccountEntryExt::V0,
+            }))
+        };
+
+        assert_eq!(
+            ClaimableBalanceFlags::for_source(&usd, &trustline(TrustlineFlags::Authorized.into())),
+            Ok(None)
+        );
+        assert_eq!(
+            ClaimableBalanceFlags::for_source(
+                &usd,
+                &trustline(TrustlineFlags::Authorized | TrustlineFlags::TrustlineClawbackEnabled)
+            ),
+            Ok(Some(ClaimableBalanceFlags::ClawbackEnabled))
+        );
+        assert_eq!(
+            ClaimableBalanceFlags::for_source(&usd, &issuer_account(0)),
+            Ok(None)
+        );
+        assert_eq!(
+            ClaimableBalanceFlags::for_source(
+                &usd,
+                &issuer_account(AccountFlags::AuthRevocable | AccountFlags::ClawbackEnabled)
+            ),
+            Ok(Some(ClaimableBalanceFlags::ClawbackEnabled))
+        );
+        assert_eq!(
+            ClaimableBalanceFlags::for_source(
+                &Asset::native(),
+                &issuer_account(AccountFlags::ClawbackEnabled.into())
+            ),
+            Ok(None)
+        );
+
+        let data = ledger_entry(xdr::LedgerEntryData::Data(xdr::DataEntry {
+            account_id: account_id(&account),
+            data_name: xdr::String64("name".try_into().unwrap()),
+            data_value: xdr::DataValue(Default::default()),
+            ext: xdr::DataEntryExt::V0,
+        }));
+        assert_eq!(
+            ClaimableBalanceFlags::for_source(&usd, &data),
+            Err(operation::Error::InvalidField("source".into()))
+        );
+    }
 }
@@ -21,8 +21,8 @@ impl Operation {
             return Err(operation::Error::InvalidPrice(n, d));
         }
         let body = xdr::OperationBody::CreatePassiveSellOffer(xdr::CreatePassiveSellOfferOp {
-            selling: selling.to_xdr_object(),
-            buying: buying.to_xdr_object(),
+            selling: selling.try_to_xdr_object()?,
+            buying: buying.try_to_xdr_object()?,
             amount,
             price: xdr::Price { n, d },
         });
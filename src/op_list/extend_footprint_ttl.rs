@@ -1,5 +1,6 @@
 use crate::{
     operation::{self, Operation},
+    soroban_data_builder::{SorobanDataBuilder, SorobanDataBuilderBehavior},
     xdr,
 };
 
@@ -24,6 +25,37 @@ impl Operation {
             body,
         })
     }
+
+    /// Extend the TTL of the Soroban entries `keys` by `extend_to` ledgers, returning the
+    /// operation with the soroban data carrying them as read-only footprint.
+    ///
+    /// `extend_to` must be between 1 and `max_entry_ttl`, the `maxEntryTTL` setting of the
+    /// network, and the keys must be contract data or contract code keys. Resources and
+    /// resource fee are left at 0, to be filled in from simulating the transaction.
+    pub fn extend_footprint_ttl_for_keys(
+        &self,
+        keys: Vec<xdr::LedgerKey>,
+        extend_to: u32,
+        max_entry_ttl: u32,
+    ) -> Result<(xdr::Operation, xdr::SorobanTransactionData), operation::Error> {
+        if !(1..=max_entry_ttl).contains(&extend_to) {
+            return Err(operation::Error::InvalidField("extend_to".into()));
+        }
+        let is_soroban_key = |key: &xdr::LedgerKey| {
+            matches!(
+                key,
+                xdr::LedgerKey::ContractData(_) | xdr::LedgerKey::ContractCode(_)
+            )
+        };
+        if keys.is_empty() || !keys.iter().all(is_soroban_key) {
+            return Err(operation::Error::InvalidField("keys".into()));
+        }
+
+        let data = SorobanDataBuilder::new(None)
+            .set_footprint(Some(keys), None)
+            .build();
+        Ok((self.extend_footprint_ttl(extend_to)?, data))
+    }
 }
 
 #[cfg(test)]
@@ -45,4 +77,43 @@ mod tests {
             panic!("Fail")
         }
     }
+
+    const MAX_ENTRY_TTL: u32 = 3_110_400;
+
+    #[test]
+    fn test_extend_ttl_for_keys() {
+        let code = crate::ledger_key::contract_code([1; 32]);
+        let (op, data) = Operation::new()
+            .extend_footprint_ttl_for_keys(vec![code.clone()], 1000, MAX_ENTRY_TTL)
+            .unwrap();
+        assert_eq!(op, Operation::new().extend_footprint_ttl(1000).unwrap());
+        assert_eq!(
+            data.resources.footprint.read_only.to_vec(),
+            vec![code.clone()]
+        );
+        assert!(data.resources.footprint.read_write.is_empty());
+
+        let invalid = |keys: Vec<xdr::LedgerKey>, extend_to| {
+            Operation::new()
+                .extend_footprint_ttl_for_keys(keys, extend_to, MAX_ENTRY_TTL)
+                .unwrap_err()
+        };
+        let field = |name: &str| crate::operation::Error::InvalidField(name.into());
+        assert_eq!(invalid(vec![code.clone()], 0), field("extend_to"));
+        assert_eq!(
+            invalid(vec![code.clone()], MAX_ENTRY_TTL + 1),
+            field("extend_to")
+        );
+        assert_eq!(
+            Operation::new()
+                .extend_footprint_ttl_for_keys(vec![code.clone()], 1000, 999)
+                .unwrap_err(),
+            field("extend_to")
+        );
+        assert_eq!(invalid(vec![], 10), field("keys"));
+        let account =
+            crate::ledger_key::account("GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB")
+                .unwrap();
+        assert_eq!(invalid(vec![code, account], 10), field("keys"));
+    }
 }
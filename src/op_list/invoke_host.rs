@@ -2,11 +2,13 @@ use rand_core::{OsRng, RngCore as _, TryRngCore};
 
 use crate::address::{Address, AddressTrait};
 use crate::asset::{Asset, AssetBehavior};
+use crate::hashing::{HashingBehavior, Sha256Hasher};
 use crate::keypair::{Keypair, KeypairBehavior};
 use crate::operation;
 use crate::operation::Operation;
 use crate::utils::decode_encode_muxed_account::encode_muxed_account_to_address;
 use crate::xdr;
+use std::borrow::Cow;
 use std::str::FromStr;
 
 impl Operation {
@@ -47,10 +49,7 @@ impl Operation {
         args: Vec<xdr::ScVal>,
         auth: Option<Vec<xdr::SorobanAuthorizationEntry>>,
     ) -> Result<xdr::Operation, operation::Error> {
-        let contract_address = Address::from_string(contract_id)
-            .map_err(|_| operation::Error::InvalidField("contract_id".into()))?
-            .to_sc_address()
-            .map_err(|_| operation::Error::InvalidField("contract_id".into()))?;
+        let contract_address = operation::parse_sc_address(contract_id, "contract_id")?;
 
         let function_name = xdr::ScSymbol(
             method
@@ -91,10 +90,7 @@ impl Operation {
             _ => xdr::Uint256(Self::get_salty()),
         };
 
-        let address = Address::from_string(deployer)
-            .map_err(|_| operation::Error::InvalidField("deployer".into()))?
-            .to_sc_address()
-            .map_err(|_| operation::Error::InvalidField("deployer".into()))?;
+        let address = operation::parse_sc_address(deployer, "deployer")?;
 
         let constructor_args: xdr::VecM<xdr::ScVal> = constructor_args
             .try_into()
@@ -111,6 +107,29 @@ impl Operation {
         self.invoke_host_function(func, auth)
     }
 
+    /// Create a new contract for the `wasm_hash`, like [create_contract](Self::create_contract)
+    /// but with the original `CreateContract` host function, which does not call a
+    /// constructor. Use it for networks or tools that do not support `CreateContractV2`.
+    pub fn create_contract_no_constructor(
+        &self,
+        deployer: &str,
+        wasm_hash: [u8; 32],
+        salt: Option<[u8; 32]>,
+        auth: Option<Vec<xdr::SorobanAuthorizationEntry>>,
+    ) -> Result<xdr::Operation, operation::Error> {
+        let salt = xdr::Uint256(salt.unwrap_or_else(Self::get_salty));
+        let address = operation::parse_sc_address(deployer, "deployer")?;
+
+        let func = xdr::HostFunction::CreateContract(xdr::CreateContractArgs {
+            contract_id_preimage: xdr::ContractIdPreimage::Address(
+                xdr::ContractIdPreimageFromAddress { address, salt },
+            ),
+            executable: xdr::ContractExecutable::Wasm(xdr::Hash(wasm_hash)),
+        });
+
+        self.invoke_host_function(func, auth)
+    }
+
     /// Create a Stellar Asset Contract for the [Asset], this wraps a classic Stellar asset in
     /// Soroban.
     pub fn wrap_asset(
@@ -119,7 +138,7 @@ impl Operation {
         auth: Option<Vec<xdr::SorobanAuthorizationEntry>>,
     ) -> Result<xdr::Operation, operation::Error> {
         let func = xdr::HostFunction::CreateContract(xdr::CreateContractArgs {
-            contract_id_preimage: xdr::ContractIdPreimage::Asset(asset.to_xdr_object()),
+            contract_id_preimage: xdr::ContractIdPreimage::Asset(asset.try_to_xdr_object()?),
             executable: xdr::ContractExecutable::StellarAsset,
         });
 
@@ -134,15 +153,36 @@ impl Operation {
         &self,
         wasm: &[u8],
         auth: Option<Vec<xdr::SorobanAuthorizationEntry>>,
+    ) -> Result<xdr::Operation, operation::Error> {
+        self.upload_wasm_owned(wasm.to_vec(), auth)
+    }
+
+    /// Upload the `wasm` executable, like [upload_wasm](Self::upload_wasm), moving the owned
+    /// buffer into the operation without copying it.
+    pub fn upload_wasm_owned(
+        &self,
+        wasm: Vec<u8>,
+        auth: Option<Vec<xdr::SorobanAuthorizationEntry>>,
     ) -> Result<xdr::Operation, operation::Error> {
         let bytes = wasm
-            .to_vec()
             .try_into()
             .map_err(|_| operation::Error::InvalidField("wasm".into()))?;
         let func = xdr::HostFunction::UploadContractWasm(bytes);
         self.invoke_host_function(func, auth)
     }
 
+    /// Upload the `wasm` executable, like [upload_wasm](Self::upload_wasm), also returning its
+    /// SHA-256 hash, which [create_contract](Self::create_contract) takes once it is uploaded.
+    pub fn upload_wasm_with_hash<'w>(
+        &self,
+        wasm: impl Into<Cow<'w, [u8]>>,
+        auth: Option<Vec<xdr::SorobanAuthorizationEntry>>,
+    ) -> Result<(xdr::Operation, [u8; 32]), operation::Error> {
+        let wasm = wasm.into();
+        let hash = Sha256Hasher::hash(&wasm);
+        Ok((self.upload_wasm_owned(wasm.into_owned(), auth)?, hash))
+    }
+
     fn get_salty() -> [u8; 32] {
         let mut salt = [0u8; 32];
         let mut rng = OsRng;
@@ -247,7 +287,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("contract_id".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "contract_id".into(),
+                value: contract_id.into(),
+            })
         );
     }
     #[test]
@@ -301,6 +344,33 @@ mod tests {
         }
         panic!("Fail")
     }
+    #[test]
+    fn test_create_contract_no_constructor() {
+        let deployer = Keypair::random().unwrap().public_key();
+        let op = Operation::new()
+            .create_contract_no_constructor(&deployer, [1; 32], Some([2; 32]), None)
+            .unwrap();
+
+        let xdr::OperationBody::InvokeHostFunction(invoke) = op.body else {
+            panic!("Fail")
+        };
+        assert_eq!(
+            invoke.host_function,
+            xdr::HostFunction::CreateContract(xdr::CreateContractArgs {
+                contract_id_preimage: xdr::ContractIdPreimage::Address(
+                    xdr::ContractIdPreimageFromAddress {
+                        address: xdr::ScAddress::from_str(&deployer).unwrap(),
+                        salt: xdr::Uint256([2; 32]),
+                    }
+                ),
+                executable: xdr::ContractExecutable::Wasm(xdr::Hash([1; 32])),
+            })
+        );
+        assert!(Operation::new()
+            .create_contract_no_constructor("GBAD", [1; 32], None, None)
+            .is_err());
+    }
+
     #[test]
     fn test_create_contract_default_salt() {
         let deployer = Keypair::random().unwrap().public_key();
@@ -343,7 +413,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("deployer".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "deployer".into(),
+                value: deployer,
+            })
         );
     }
 
@@ -384,4 +457,35 @@ mod tests {
         }
         panic!("Fail")
     }
+
+    #[test]
+    fn test_upload_wasm_owned() {
+        let wasm = vec![7; 1 << 20];
+        let ptr = wasm.as_ptr();
+        let op = Operation::new().upload_wasm_owned(wasm, None).unwrap();
+
+        let xdr::OperationBody::InvokeHostFunction(xdr::InvokeHostFunctionOp {
+            host_function: xdr::HostFunction::UploadContractWasm(bytes),
+            ..
+        }) = op.body
+        else {
+            panic!("Fail")
+        };
+        assert_eq!(bytes.len(), 1 << 20);
+        // The buffer is moved into the operation, not copied.
+        assert_eq!(bytes.as_slice().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_upload_wasm_with_hash() {
+        let wasm = b"\0asm\x01\0\0\0".to_vec();
+        let (op, hash) = Operation::new()
+            .upload_wasm_with_hash(wasm.clone(), None)
+            .unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+        );
+        assert_eq!(op, Operation::new().upload_wasm(&wasm, None).unwrap());
+    }
 }
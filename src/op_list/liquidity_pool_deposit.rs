@@ -1,4 +1,5 @@
 use crate::{
+    get_liquidity_pool::LiquidityPoolParameters,
     liquidity_pool_id::{self, LiquidityPoolId, LiquidityPoolIdBehavior},
     operation::{self, Operation},
     xdr,
@@ -66,6 +67,28 @@ impl Operation {
             body,
         })
     }
+
+    /// Deposits into the pool with the given `parameters`, see
+    /// [liquidity_pool_deposit](Self::liquidity_pool_deposit).
+    pub fn liquidity_pool_deposit_to(
+        &self,
+        parameters: &LiquidityPoolParameters,
+        max_amount_a: i64,
+        max_amount_b: i64,
+        min_price: (i32, i32),
+        max_price: (i32, i32),
+    ) -> Result<xdr::Operation, operation::Error> {
+        parameters
+            .validate()
+            .map_err(|_| operation::Error::InvalidField("parameters".into()))?;
+        self.liquidity_pool_deposit(
+            &hex::encode(parameters.pool_id().0 .0),
+            max_amount_a,
+            max_amount_b,
+            min_price,
+            max_price,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -73,6 +96,8 @@ mod tests {
     use serde::de::IntoDeserializer;
 
     use crate::{
+        asset::{Asset, AssetBehavior},
+        get_liquidity_pool::LiquidityPoolParameters,
         operation::{self, Operation},
         xdr,
     };
@@ -214,4 +239,33 @@ mod tests {
             Some(operation::Error::InvalidAmount(max_amount_a))
         );
     }
+
+    #[test]
+    fn test_lp_deposit_to() {
+        let (asset_a, asset_b) = (
+            Asset::native(),
+            Asset::new(
+                "USD",
+                Some("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ"),
+            )
+            .unwrap(),
+        );
+        let parameters = LiquidityPoolParameters::new(asset_a, asset_b, 30).unwrap();
+        let op = Operation::new()
+            .liquidity_pool_deposit_to(&parameters, 10, 20, (1, 2), (2, 1))
+            .unwrap();
+        let xdr::OperationBody::LiquidityPoolDeposit(deposit) = op.body else {
+            panic!("Fail")
+        };
+        assert_eq!(deposit.liquidity_pool_id, parameters.pool_id());
+
+        let mut invalid = parameters.clone();
+        invalid.fee = 10;
+        assert_eq!(
+            Operation::new()
+                .liquidity_pool_deposit_to(&invalid, 10, 20, (1, 2), (2, 1))
+                .err(),
+            Some(operation::Error::InvalidField("parameters".into()))
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::{
+    get_liquidity_pool::LiquidityPoolParameters,
     operation::{self, Operation},
     xdr,
 };
@@ -45,11 +46,33 @@ impl Operation {
             body,
         })
     }
+
+    /// Withdraws from the pool with the given `parameters`, see
+    /// [liquidity_pool_withdraw](Self::liquidity_pool_withdraw).
+    pub fn liquidity_pool_withdraw_from(
+        &self,
+        parameters: &LiquidityPoolParameters,
+        amount: i64,
+        min_amount_a: i64,
+        min_amount_b: i64,
+    ) -> Result<xdr::Operation, operation::Error> {
+        parameters
+            .validate()
+            .map_err(|_| operation::Error::InvalidField("parameters".into()))?;
+        self.liquidity_pool_withdraw(
+            &hex::encode(parameters.pool_id().0 .0),
+            amount,
+            min_amount_a,
+            min_amount_b,
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
+        asset::{Asset, AssetBehavior},
+        get_liquidity_pool::LiquidityPoolParameters,
         operation::{self, Operation},
         xdr,
     };
@@ -152,4 +175,24 @@ mod tests {
             Some(operation::Error::InvalidAmount(min_amount_b))
         );
     }
+
+    #[test]
+    fn test_lp_withdraw_from() {
+        let (asset_a, asset_b) = (
+            Asset::native(),
+            Asset::new(
+                "USD",
+                Some("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ"),
+            )
+            .unwrap(),
+        );
+        let parameters = LiquidityPoolParameters::new(asset_a, asset_b, 30).unwrap();
+        let op = Operation::new()
+            .liquidity_pool_withdraw_from(&parameters, 50, 1, 1)
+            .unwrap();
+        let xdr::OperationBody::LiquidityPoolWithdraw(withdraw) = op.body else {
+            panic!("Fail")
+        };
+        assert_eq!(withdraw.liquidity_pool_id, parameters.pool_id());
+    }
 }
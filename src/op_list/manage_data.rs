@@ -1,4 +1,5 @@
 use crate::{
+    data_entry::DataEntryValue,
     operation::{self, Operation},
     xdr,
 };
@@ -6,6 +7,9 @@ use crate::{
 impl Operation {
     /// Sets, modifies, or deletes a data entry (name/value pair) that is attached to an account
     ///
+    /// The `name` must be 1 to 64 printable ASCII characters and the `data` at most 64 bytes.
+    /// Passing `None` as `data` deletes the entry.
+    ///
     /// Threshold: Medium
     pub fn manage_data(
         &self,
@@ -13,6 +17,9 @@ impl Operation {
         data: Option<&Vec<u8>>,
     ) -> Result<xdr::Operation, operation::Error> {
         //
+        if name.is_empty() || !name.bytes().all(|c| (0x20..0x7f).contains(&c)) {
+            return Err(operation::Error::InvalidField("name".into()));
+        }
 
         let data_name = xdr::String64(
             name.try_into()
@@ -37,11 +44,27 @@ impl Operation {
             body,
         })
     }
+
+    /// Sets the data entry `name` to a typed `value`, encoded as described in
+    /// [data_entry](crate::data_entry).
+    ///
+    /// Threshold: Medium
+    pub fn manage_data_value(
+        &self,
+        name: &str,
+        value: &impl DataEntryValue,
+    ) -> Result<xdr::Operation, operation::Error> {
+        let value = value
+            .to_data_value()
+            .map_err(|_| operation::Error::InvalidField("data".into()))?;
+        self.manage_data(name, Some(&value.to_vec()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
+        data_entry::DataEntryValue,
         operation::{self, Operation},
         xdr,
     };
@@ -101,6 +124,26 @@ mod tests {
         );
     }
     #[test]
+    fn test_manage_data_empty_name() {
+        let op = Operation::new().manage_data("", None);
+
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("name".into()))
+        );
+    }
+    #[test]
+    fn test_manage_data_non_printable_name() {
+        for name in ["tab\tname", "new\nline", "caf\u{e9}"] {
+            let op = Operation::new().manage_data(name, None);
+
+            assert_eq!(
+                op.err(),
+                Some(operation::Error::InvalidField("name".into()))
+            );
+        }
+    }
+    #[test]
     fn test_manage_data_long_data_64() {
         let name = "Data name";
         let data = std::str::from_utf8([65; 64].as_slice()).unwrap(); // 64 letter 'A'
@@ -119,4 +162,26 @@ mod tests {
             Some(operation::Error::InvalidField("data".into()))
         );
     }
+
+    #[test]
+    fn test_manage_data_value() {
+        let op = Operation::new()
+            .manage_data_value("counter", &42_u64)
+            .unwrap();
+        if let xdr::OperationBody::ManageData(xdr::ManageDataOp {
+            data_value: Some(value),
+            ..
+        }) = op.body
+        {
+            assert_eq!(u64::from_data_value(&value).unwrap(), 42);
+        } else {
+            panic!("Fail")
+        }
+
+        let op = Operation::new().manage_data_value("note", &"a".repeat(65));
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("data".into()))
+        );
+    }
 }
@@ -22,8 +22,8 @@ impl Operation {
             return Err(operation::Error::InvalidPrice(n, d));
         }
         let body = xdr::OperationBody::ManageSellOffer(xdr::ManageSellOfferOp {
-            selling: selling.to_xdr_object(),
-            buying: buying.to_xdr_object(),
+            selling: selling.try_to_xdr_object()?,
+            buying: buying.try_to_xdr_object()?,
             amount: sell_amount,
             price: xdr::Price { n, d },
             offer_id,
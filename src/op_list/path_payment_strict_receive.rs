@@ -27,17 +27,19 @@ impl Operation {
         if dest_amount < 0 {
             return Err(operation::Error::InvalidAmount(dest_amount));
         }
-        let destination = xdr::MuxedAccount::from_str(destination)
-            .map_err(|_| operation::Error::InvalidField("destination".into()))?;
-        let xdr_path: Vec<xdr::Asset> = path.iter().map(|e| e.to_xdr_object()).collect();
+        let destination = operation::parse_muxed_account(destination, "destination")?;
+        let xdr_path: Vec<xdr::Asset> = path
+            .iter()
+            .map(|e| e.try_to_xdr_object())
+            .collect::<Result<_, _>>()?;
         let path = xdr_path
             .try_into()
             .map_err(|_| operation::Error::InvalidField("path".into()))?;
         let body = xdr::OperationBody::PathPaymentStrictReceive(PathPaymentStrictReceiveOp {
-            send_asset: send_asset.to_xdr_object(),
+            send_asset: send_asset.try_to_xdr_object()?,
             send_max,
             destination,
-            dest_asset: dest_asset.to_xdr_object(),
+            dest_asset: dest_asset.try_to_xdr_object()?,
             dest_amount,
             path,
         });
@@ -131,7 +133,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("destination".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "destination".into(),
+                value: destination.to_string(),
+            })
         );
     }
     #[test]
@@ -19,9 +19,8 @@ impl Operation {
         if amount.is_negative() {
             return Err(operation::Error::InvalidAmount(amount));
         }
-        let destination = xdr::MuxedAccount::from_str(destination)
-            .map_err(|_| operation::Error::InvalidField("destination".into()))?;
-        let asset: xdr::Asset = asset.to_xdr_object();
+        let destination = operation::parse_muxed_account(destination, "destination")?;
+        let asset: xdr::Asset = asset.try_to_xdr_object()?;
         let payment_op = xdr::PaymentOp {
             asset,
             amount,
@@ -140,7 +139,30 @@ mod tests {
 
         assert_eq!(
             r.err().unwrap(),
-            operation::Error::InvalidField("destination".into())
+            operation::Error::InvalidStrkey {
+                kind: "destination".into(),
+                value: dest.to_string(),
+            }
         );
     }
+
+    #[test]
+    fn test_payment_destination_strkey_checks() {
+        let dest = Keypair::random().unwrap().public_key();
+        let mut bad_checksum = dest.clone();
+        let last = if dest.ends_with('A') { "B" } else { "A" };
+        bad_checksum.replace_range(dest.len() - 1.., last);
+        let mixed_case = format!("G{}", dest[1..].to_lowercase());
+
+        for dest in [dest.to_lowercase(), mixed_case, bad_checksum, String::new()] {
+            let r = Operation::new().payment(&dest, &Asset::native(), operation::ONE);
+            assert_eq!(
+                r.err().unwrap(),
+                operation::Error::InvalidStrkey {
+                    kind: "destination".into(),
+                    value: dest,
+                }
+            );
+        }
+    }
 }
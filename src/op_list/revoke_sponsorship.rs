@@ -1,9 +1,8 @@
 use std::str::FromStr;
 
-use stellar_strkey::Strkey;
-
 use crate::{
     asset::{Asset, AssetBehavior},
+    ledger_key,
     operation::{self, Operation},
     xdr,
 };
@@ -23,29 +22,8 @@ impl Operation {
         account: &str,
         signer: &str,
     ) -> Result<xdr::Operation, operation::Error> {
-        let account_id = xdr::AccountId::from_str(account)
-            .map_err(|_| operation::Error::InvalidField("account".into()))?;
-        let signer_key = match Strkey::from_string(signer)
-            .map_err(|_| operation::Error::InvalidField("signer".into()))?
-        {
-            Strkey::PublicKeyEd25519(stellar_strkey::ed25519::PublicKey(key)) => {
-                xdr::SignerKey::Ed25519(xdr::Uint256(key))
-            }
-            Strkey::PreAuthTx(stellar_strkey::PreAuthTx(key)) => {
-                xdr::SignerKey::PreAuthTx(xdr::Uint256(key))
-            }
-            Strkey::HashX(stellar_strkey::HashX(key)) => xdr::SignerKey::HashX(xdr::Uint256(key)),
-            Strkey::SignedPayloadEd25519(stellar_strkey::ed25519::SignedPayload {
-                ed25519,
-                payload,
-            }) => xdr::SignerKey::Ed25519SignedPayload(xdr::SignerKeyEd25519SignedPayload {
-                ed25519: xdr::Uint256(ed25519),
-                payload: payload
-                    .try_into()
-                    .map_err(|_| operation::Error::InvalidField("signer".into()))?,
-            }),
-            _ => return Err(operation::Error::InvalidField("signer".into())),
-        };
+        let account_id = operation::parse_account_id(account, "account")?;
+        let signer_key = operation::parse_signer_key(signer, "signer")?;
 
         let body = xdr::OperationBody::RevokeSponsorship(xdr::RevokeSponsorshipOp::Signer(
             xdr::RevokeSponsorshipOpSigner {
@@ -67,10 +45,7 @@ impl Operation {
         &self,
         account: &str,
     ) -> Result<xdr::Operation, operation::Error> {
-        let account_id = xdr::AccountId::from_str(account)
-            .map_err(|_| operation::Error::InvalidField("account".into()))?;
-        let key = xdr::LedgerKey::Account(xdr::LedgerKeyAccount { account_id });
-        self.revoke_ledger_key_sponsorship(key)
+        self.revoke_ledger_key_sponsorship(ledger_key::account(account)?)
     }
 
     /// Revoke sponsorship for the `trustline` on the `account`
@@ -85,13 +60,7 @@ impl Operation {
         account: &str,
         trustline: impl Into<xdr::TrustLineAsset>,
     ) -> Result<xdr::Operation, operation::Error> {
-        let account_id = xdr::AccountId::from_str(account)
-            .map_err(|_| operation::Error::InvalidField("account".into()))?;
-        let key = xdr::LedgerKey::Trustline(xdr::LedgerKeyTrustLine {
-            account_id,
-            asset: trustline.into(),
-        });
-        self.revoke_ledger_key_sponsorship(key)
+        self.revoke_ledger_key_sponsorship(ledger_key::trustline(account, trustline)?)
     }
 
     /// Revoke sponsorship for the offer respresented by `seller` and `offer_id`
@@ -102,13 +71,7 @@ impl Operation {
         seller: &str,
         offer_id: i64,
     ) -> Result<xdr::Operation, operation::Error> {
-        let seller_id = xdr::AccountId::from_str(seller)
-            .map_err(|_| operation::Error::InvalidField("seller".into()))?;
-        let key = xdr::LedgerKey::Offer(xdr::LedgerKeyOffer {
-            seller_id,
-            offer_id,
-        });
-        self.revoke_ledger_key_sponsorship(key)
+        self.revoke_ledger_key_sponsorship(ledger_key::offer(seller, offer_id)?)
     }
 
     /// Revoke sponsorship for the data entry `name` on the `account`
@@ -119,17 +82,7 @@ impl Operation {
         account: &str,
         name: &str,
     ) -> Result<xdr::Operation, operation::Error> {
-        let account_id = xdr::AccountId::from_str(account)
-            .map_err(|_| operation::Error::InvalidField("account".into()))?;
-        let data_name = xdr::String64(
-            name.try_into()
-                .map_err(|_| operation::Error::InvalidField("name".into()))?,
-        );
-        let key = xdr::LedgerKey::Data(xdr::LedgerKeyData {
-            account_id,
-            data_name,
-        });
-        self.revoke_ledger_key_sponsorship(key)
+        self.revoke_ledger_key_sponsorship(ledger_key::data(account, name)?)
     }
 
     /// Revoke sponsorship for the claimbable balance `balance_id`
@@ -139,12 +92,7 @@ impl Operation {
         &self,
         balance_id: &str,
     ) -> Result<xdr::Operation, operation::Error> {
-        let xdr_balance_id = xdr::ClaimableBalanceId::from_str(balance_id)
-            .map_err(|_| operation::Error::InvalidField("balance_id".into()))?;
-        let key = xdr::LedgerKey::ClaimableBalance(xdr::LedgerKeyClaimableBalance {
-            balance_id: xdr_balance_id,
-        });
-        self.revoke_ledger_key_sponsorship(key)
+        self.revoke_ledger_key_sponsorship(ledger_key::claimable_balance(balance_id)?)
     }
 
     /// Revoke sponsorship for the [key](xdr::LedgerKey)
@@ -211,7 +159,7 @@ mod tests {
         let a2 = Keypair::random().unwrap();
         let data = "PAY LOAD".as_bytes();
         let signer = Keypair::random().unwrap();
-        let signed_payload = signer.sign_payload_decorated(data);
+        let signed_payload = signer.sign_payload_decorated(data).unwrap();
 
         let payload = Strkey::SignedPayloadEd25519(SignedPayload {
             ed25519: *signer.raw_public_key().last_chunk::<32>().unwrap(),
@@ -21,6 +21,14 @@ impl BitOr for AccountFlags {
     }
 }
 
+impl BitOr<AccountFlags> for u32 {
+    type Output = u32;
+
+    fn bitor(self, rhs: AccountFlags) -> Self::Output {
+        self | rhs as u32
+    }
+}
+
 impl From<AccountFlags> for u32 {
     fn from(flag: AccountFlags) -> Self {
         flag as u32
@@ -46,8 +54,7 @@ impl Operation {
         //
         let inflation_dest = match inflation_dest {
             Some(dest) => {
-                let account_id = xdr::AccountId::from_str(dest)
-                    .map_err(|_| operation::Error::InvalidField("inflation_dest".into()))?;
+                let account_id = operation::parse_account_id(dest, "inflation_dest")?;
                 Some(account_id)
             }
             _ => None,
@@ -66,8 +73,7 @@ impl Operation {
         let signer = match signer {
             Some((account, weight)) => {
                 let s = xdr::Signer {
-                    key: xdr::SignerKey::from_str(account)
-                        .map_err(|_| operation::Error::InvalidField("signer".into()))?,
+                    key: operation::parse_signer_key(account, "signer")?,
                     weight: weight as u32,
                 };
                 Some(s)
@@ -561,7 +567,10 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("signer".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "signer".into(),
+                value: signer,
+            })
         );
     }
 
@@ -706,7 +715,10 @@ mod tests {
         );
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("inflation_dest".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "inflation_dest".into(),
+                value: inflation_dest,
+            })
         );
     }
 }
@@ -25,33 +25,58 @@ impl BitOr for TrustlineFlags {
     }
 }
 
+impl BitOr<TrustlineFlags> for u32 {
+    type Output = u32;
+
+    fn bitor(self, rhs: TrustlineFlags) -> Self::Output {
+        self | rhs as u32
+    }
+}
+
 impl From<TrustlineFlags> for u32 {
     fn from(flag: TrustlineFlags) -> Self {
         flag as u32
     }
 }
 
+/// Alias of [TrustlineFlags] matching the XDR `TrustLineFlags` naming.
+pub type TrustLineFlags = TrustlineFlags;
+
+const ALL_TRUSTLINE_FLAGS: u32 = TrustlineFlags::Authorized as u32
+    | TrustlineFlags::AuthorizedToMaintainLiabilities as u32
+    | TrustlineFlags::TrustlineClawbackEnabled as u32;
+
 impl Operation {
     /// Allows issuing account to configure authorization and trustline flags to an asset
     ///
     /// The `set_flags` and `clear_flags` can be built by logical `or` on enum variants
-    /// [TrustlineFlags].
+    /// [TrustlineFlags]. A flag cannot be both set and cleared, and
+    /// [TrustlineClawbackEnabled](TrustlineFlags::TrustlineClawbackEnabled) can only be cleared.
     ///
     /// Threshold: Low
     pub fn set_trustline_flags(
         &self,
         account: &str,
         asset: &Asset,
-        set_flags: u32,
-        clear_flags: u32,
+        set_flags: impl Into<u32>,
+        clear_flags: impl Into<u32>,
     ) -> Result<xdr::Operation, operation::Error> {
         //
-        let trustor = xdr::AccountId::from_str(account)
-            .map_err(|_| operation::Error::InvalidField("account".into()))?;
+        let set_flags = set_flags.into();
+        let clear_flags = clear_flags.into();
+        if set_flags & !ALL_TRUSTLINE_FLAGS != 0
+            || set_flags & TrustlineFlags::TrustlineClawbackEnabled as u32 != 0
+        {
+            return Err(operation::Error::InvalidField("set_flags".into()));
+        }
+        if clear_flags & !ALL_TRUSTLINE_FLAGS != 0 || clear_flags & set_flags != 0 {
+            return Err(operation::Error::InvalidField("clear_flags".into()));
+        }
+        let trustor = operation::parse_account_id(account, "account")?;
 
         let body = xdr::OperationBody::SetTrustLineFlags(xdr::SetTrustLineFlagsOp {
             trustor,
-            asset: asset.to_xdr_object(),
+            asset: asset.try_to_xdr_object()?,
             clear_flags,
             set_flags,
         });
@@ -117,7 +142,70 @@ mod tests {
 
         assert_eq!(
             op.err(),
-            Some(operation::Error::InvalidField("account".into()))
+            Some(operation::Error::InvalidStrkey {
+                kind: "account".into(),
+                value: account,
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_trustline_flags_combined() {
+        let account = Keypair::random().unwrap();
+        let issuer = Keypair::random().unwrap();
+        let asset = Asset::new("ABC", Some(&issuer.public_key())).unwrap();
+        let clear_flags = TrustlineFlags::Authorized
+            | TrustlineFlags::AuthorizedToMaintainLiabilities
+            | TrustlineFlags::TrustlineClawbackEnabled;
+        let op = Operation::new()
+            .set_trustline_flags(&account.public_key(), &asset, 0_u32, clear_flags)
+            .unwrap();
+
+        if let xdr::OperationBody::SetTrustLineFlags(xdr::SetTrustLineFlagsOp {
+            clear_flags: cf,
+            set_flags: sf,
+            ..
+        }) = op.body
+        {
+            assert_eq!(cf, 7);
+            assert_eq!(sf, 0);
+        } else {
+            panic!("Fail")
+        }
+    }
+
+    #[test]
+    fn test_set_trustline_flags_invalid_flags() {
+        let account = Keypair::random().unwrap().public_key();
+        let issuer = Keypair::random().unwrap();
+        let asset = Asset::new("ABC", Some(&issuer.public_key())).unwrap();
+
+        let op = Operation::new().set_trustline_flags(
+            &account,
+            &asset,
+            TrustlineFlags::TrustlineClawbackEnabled,
+            0_u32,
+        );
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("set_flags".into()))
+        );
+
+        let op = Operation::new().set_trustline_flags(&account, &asset, 8_u32, 0_u32);
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("set_flags".into()))
+        );
+
+        let op = Operation::new().set_trustline_flags(
+            &account,
+            &asset,
+            TrustlineFlags::Authorized,
+            TrustlineFlags::Authorized | TrustlineFlags::TrustlineClawbackEnabled,
+        );
+        assert_eq!(
+            op.err(),
+            Some(operation::Error::InvalidField("clear_flags".into()))
         );
     }
 }
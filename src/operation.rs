@@ -8,6 +8,7 @@ use num_traits::ToPrimitive;
 use num_traits::{FromPrimitive, Num, Signed, Zero};
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 use stellar_strkey::ed25519::{MuxedAccount, PublicKey};
 
@@ -15,13 +16,15 @@ use crate::asset::Asset;
 use crate::asset::AssetBehavior;
 use crate::claimant::Claimant;
 use crate::claimant::ClaimantBehavior;
+use crate::get_liquidity_pool::LIQUIDITY_POOL_FEE_V18;
 use crate::liquidity_pool_asset::LiquidityPoolAsset;
 use crate::utils::decode_encode_muxed_account::{
     decode_address_to_muxed_account, encode_muxed_account_to_address,
 };
 
+pub use super::op_list::create_claimable_balance::ClaimableBalanceFlags;
 pub use super::op_list::set_options::AccountFlags;
-pub use super::op_list::set_trustline_flags::TrustlineFlags;
+pub use super::op_list::set_trustline_flags::{TrustLineFlags, TrustlineFlags};
 
 pub const ONE: i64 = 10_000_000;
 const MAX_INT64: &str = "9223372036854775807";
@@ -40,6 +43,11 @@ pub enum Error {
     InvalidField(String),
     InvalidAmount(i64),
     InvalidPrice(i32, i32),
+    /// The `value` given for the `kind` field is not a valid strkey of the expected type.
+    InvalidStrkey {
+        kind: String,
+        value: String,
+    },
 }
 
 impl Operation {
@@ -49,10 +57,7 @@ impl Operation {
 
     pub fn with_source(source: &str) -> Result<Self, Error> {
         Ok(Self {
-            source: Some(
-                xdr::MuxedAccount::from_str(source)
-                    .map_err(|_| Error::InvalidField("source".into()))?,
-            ),
+            source: Some(parse_muxed_account(source, "source")?),
         })
     }
 }
@@ -63,6 +68,52 @@ impl Default for Operation {
     }
 }
 
+impl Operation {
+    /// Returns the protocol versions in which operations of the type of `body` are applied.
+    ///
+    /// Every operation of the XDR types this crate is built with, `curr` or `next`, is listed,
+    /// so operations only known to a newer protocol fail to compile here rather than further
+    /// down in a mismatched XDR type.
+    pub fn supported_protocols(body: &xdr::OperationBody) -> RangeInclusive<u32> {
+        let first = match body {
+            xdr::OperationBody::Inflation => return 1..=11,
+            xdr::OperationBody::CreateAccount(_)
+            | xdr::OperationBody::Payment(_)
+            | xdr::OperationBody::PathPaymentStrictReceive(_)
+            | xdr::OperationBody::ManageSellOffer(_)
+            | xdr::OperationBody::CreatePassiveSellOffer(_)
+            | xdr::OperationBody::SetOptions(_)
+            | xdr::OperationBody::ChangeTrust(_)
+            | xdr::OperationBody::AllowTrust(_)
+            | xdr::OperationBody::AccountMerge(_)
+            | xdr::OperationBody::ManageData(_) => 1,
+            xdr::OperationBody::BumpSequence(_) => 10,
+            xdr::OperationBody::ManageBuyOffer(_) => 11,
+            xdr::OperationBody::PathPaymentStrictSend(_) => 12,
+            xdr::OperationBody::CreateClaimableBalance(_)
+            | xdr::OperationBody::ClaimClaimableBalance(_)
+            | xdr::OperationBody::BeginSponsoringFutureReserves(_)
+            | xdr::OperationBody::EndSponsoringFutureReserves
+            | xdr::OperationBody::RevokeSponsorship(_) => 14,
+            xdr::OperationBody::Clawback(_)
+            | xdr::OperationBody::ClawbackClaimableBalance(_)
+            | xdr::OperationBody::SetTrustLineFlags(_) => 17,
+            xdr::OperationBody::LiquidityPoolDeposit(_)
+            | xdr::OperationBody::LiquidityPoolWithdraw(_) => 18,
+            xdr::OperationBody::InvokeHostFunction(_)
+            | xdr::OperationBody::ExtendFootprintTtl(_)
+            | xdr::OperationBody::RestoreFootprint(_) => 20,
+        };
+        first..=u32::MAX
+    }
+
+    /// Returns whether a network running `protocol_version` applies operations of the type of
+    /// `body`, e.g. to skip Soroban operations when deploying to an older network.
+    pub fn is_supported(body: &xdr::OperationBody, protocol_version: u32) -> bool {
+        Self::supported_protocols(body).contains(&protocol_version)
+    }
+}
+
 /// Validates that a given amount is possible for a Stellar asset.
 pub fn is_valid_amount(value: &str, allow_zero: bool) -> bool {
     if !value.is_empty() {
@@ -111,12 +162,106 @@ pub fn from_xdr_amount(value: u64) -> f64 {
     round_to((value.to_f64().unwrap() / ONE as f64), 7)
 }
 
+/// Formats an amount in stroops as a decimal with 7 digits after the point, e.g.
+/// `"10.5000000"`, like Horizon does. Unlike [from_xdr_amount] it is exact for any amount.
+pub fn format_amount(stroops: i64) -> String {
+    let sign = if stroops < 0 { "-" } else { "" };
+    let stroops = stroops.unsigned_abs();
+    let one = ONE as u64;
+    format!("{sign}{}.{:07}", stroops / one, stroops % one)
+}
+
 // Utility function to round an f64 to a specific number of decimal places
 pub fn round_to(value: f64, decimal_places: u32) -> f64 {
     let multiplier = 10f64.powi(decimal_places as i32);
     (value * multiplier).round() / multiplier
 }
 
+pub(crate) fn invalid_strkey(value: &str, kind: &str) -> Error {
+    Error::InvalidStrkey {
+        kind: kind.into(),
+        value: value.into(),
+    }
+}
+
+/// Checks that `value` only uses the uppercase strkey alphabet, so that lowercase or
+/// mixed-case inputs are rejected whatever the decoder tolerates. The checksum is verified
+/// by the decoder itself.
+pub(crate) fn check_strkey(value: &str, kind: &str) -> Result<(), Error> {
+    if value.is_empty()
+        || !value
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b))
+    {
+        return Err(invalid_strkey(value, kind));
+    }
+    Ok(())
+}
+
+/// Parses an account id (`G...`) given for the `kind` field.
+pub(crate) fn parse_account_id(value: &str, kind: &str) -> Result<xdr::AccountId, Error> {
+    check_strkey(value, kind)?;
+    xdr::AccountId::from_str(value).map_err(|_| invalid_strkey(value, kind))
+}
+
+/// Parses an account (`G...`) or muxed account (`M...`) given for the `kind` field.
+pub(crate) fn parse_muxed_account(value: &str, kind: &str) -> Result<xdr::MuxedAccount, Error> {
+    check_strkey(value, kind)?;
+    xdr::MuxedAccount::from_str(value).map_err(|_| invalid_strkey(value, kind))
+}
+
+/// Parses an account (`G...`), muxed account (`M...`) or contract (`C...`) address given for
+/// the `kind` field.
+pub(crate) fn parse_sc_address(value: &str, kind: &str) -> Result<xdr::ScAddress, Error> {
+    use crate::address::{Address, AddressTrait};
+
+    check_strkey(value, kind)?;
+    Address::from_string(value)
+        .and_then(|address| address.to_sc_address())
+        .map_err(|_| invalid_strkey(value, kind))
+}
+
+/// Parses a signer (`G...`, `T...`, `X...` or `P...`) given for the `kind` field.
+pub(crate) fn parse_signer_key(value: &str, kind: &str) -> Result<xdr::SignerKey, Error> {
+    check_strkey(value, kind)?;
+    let key =
+        crate::signer_key::SignerKey::from_str(value).map_err(|_| invalid_strkey(value, kind))?;
+    key.to_xdr().map_err(|_| Error::InvalidField(kind.into()))
+}
+
+/// Parses a claimable balance id given either as hex (the bare 32-byte hash, or the 36-byte
+/// XDR encoding returned by Horizon) or as a `B...` strkey.
+pub(crate) fn parse_claimable_balance_id(
+    balance_id: &str,
+) -> Result<xdr::ClaimableBalanceId, Error> {
+    let invalid = || Error::InvalidField("balance_id".into());
+
+    if balance_id.starts_with('B') {
+        check_strkey(balance_id, "balance_id")?;
+        return match stellar_strkey::Strkey::from_string(balance_id)
+            .map_err(|_| invalid_strkey(balance_id, "balance_id"))?
+        {
+            stellar_strkey::Strkey::ClaimableBalance(stellar_strkey::ClaimableBalance::V0(h)) => {
+                Ok(xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(
+                    xdr::Hash(h),
+                ))
+            }
+            _ => Err(invalid_strkey(balance_id, "balance_id")),
+        };
+    }
+
+    let hash = match balance_id.len() {
+        64 => balance_id,
+        72 => balance_id.strip_prefix("00000000").ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+    let mut h = [0; 32];
+    hex::decode_to_slice(hash, &mut h).map_err(|_| invalid())?;
+    Ok(xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(
+        xdr::Hash(h),
+    ))
+}
+
 fn account_id_to_address(account_id: &xdr::AccountId) -> String {
     let xdr::PublicKey::PublicKeyTypeEd25519(val) = account_id.0.clone();
     let key: Result<PublicKey, stellar_strkey::DecodeError> =
@@ -148,3 +293,232 @@ fn convert_xdr_signer_key_to_object(
         _ => panic!("Invalid Type"),
     }
 }
+
+/// Returns the protocol constraints `op` violates, as checked by stellar-core before applying
+/// it. Constraints already guaranteed by the XDR types, such as string lengths, are skipped.
+pub(crate) fn protocol_violations(op: &xdr::Operation) -> Vec<String> {
+    let mut violations = Vec::new();
+    let v = &mut violations;
+    match &op.body {
+        xdr::OperationBody::CreateAccount(op) => {
+            non_negative(v, "starting_balance", op.starting_balance);
+        }
+        xdr::OperationBody::Payment(op) => {
+            check_asset(v, "asset", &op.asset);
+            positive(v, "amount", op.amount);
+        }
+        xdr::OperationBody::PathPaymentStrictReceive(op) => {
+            check_asset(v, "send_asset", &op.send_asset);
+            check_asset(v, "dest_asset", &op.dest_asset);
+            op.path
+                .iter()
+                .for_each(|asset| check_asset(v, "path", asset));
+            positive(v, "send_max", op.send_max);
+            positive(v, "dest_amount", op.dest_amount);
+        }
+        xdr::OperationBody::PathPaymentStrictSend(op) => {
+            check_asset(v, "send_asset", &op.send_asset);
+            check_asset(v, "dest_asset", &op.dest_asset);
+            op.path
+                .iter()
+                .for_each(|asset| check_asset(v, "path", asset));
+            positive(v, "send_amount", op.send_amount);
+            positive(v, "dest_min", op.dest_min);
+        }
+        xdr::OperationBody::ManageSellOffer(op) => {
+            check_offer(v, &op.selling, &op.buying, &op.price);
+            non_negative(v, "amount", op.amount);
+        }
+        xdr::OperationBody::ManageBuyOffer(op) => {
+            check_offer(v, &op.selling, &op.buying, &op.price);
+            non_negative(v, "buy_amount", op.buy_amount);
+        }
+        xdr::OperationBody::CreatePassiveSellOffer(op) => {
+            check_offer(v, &op.selling, &op.buying, &op.price);
+            positive(v, "amount", op.amount);
+        }
+        xdr::OperationBody::SetOptions(op) => {
+            let weights = [
+                ("master_weight", op.master_weight),
+                ("low_threshold", op.low_threshold),
+                ("med_threshold", op.med_threshold),
+                ("high_threshold", op.high_threshold),
+                ("signer.weight", op.signer.as_ref().map(|s| s.weight)),
+            ];
+            for (name, weight) in weights {
+                if weight.is_some_and(|w| w > u8::MAX as u32) {
+                    v.push(format!("{} cannot be greater than {}", name, u8::MAX));
+                }
+            }
+            if let (Some(set), Some(clear)) = (op.set_flags, op.clear_flags) {
+                if set & clear != 0 {
+                    v.push("set_flags and clear_flags overlap".to_string());
+                }
+            }
+        }
+        xdr::OperationBody::ChangeTrust(op) => {
+            match &op.line {
+                xdr::ChangeTrustAsset::Native => v.push("line cannot be native".to_string()),
+                xdr::ChangeTrustAsset::CreditAlphanum4(a) => {
+                    check_code(v, "line", &a.asset_code.0, 1)
+                }
+                xdr::ChangeTrustAsset::CreditAlphanum12(a) => {
+                    check_code(v, "line", &a.asset_code.0, 5)
+                }
+                xdr::ChangeTrustAsset::PoolShare(
+                    xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(params),
+                ) => {
+                    check_asset(v, "line.asset_a", &params.asset_a);
+                    check_asset(v, "line.asset_b", &params.asset_b);
+                    if params.fee != LIQUIDITY_POOL_FEE_V18 {
+                        v.push(format!(
+                            "line.fee must be {}, got {}",
+                            LIQUIDITY_POOL_FEE_V18, params.fee
+                        ));
+                    }
+                    let assets = (
+                        Asset::from_operation(params.asset_a.clone()),
+                        Asset::from_operation(params.asset_b.clone()),
+                    );
+                    if let (Ok(asset_a), Ok(asset_b)) = assets {
+                        if Asset::compare(&asset_a, &asset_b) != -1 {
+                            v.push(
+                                "line.asset_a must sort strictly before line.asset_b".to_string(),
+                            );
+                        }
+                    }
+                }
+            }
+            non_negative(v, "limit", op.limit);
+        }
+        xdr::OperationBody::AllowTrust(op) => match &op.asset {
+            xdr::AssetCode::CreditAlphanum4(code) => check_code(v, "asset", &code.0, 1),
+            xdr::AssetCode::CreditAlphanum12(code) => check_code(v, "asset", &code.0, 5),
+        },
+        xdr::OperationBody::ManageData(op) if op.data_name.is_empty() => {
+            v.push("data_name cannot be empty".to_string());
+        }
+        xdr::OperationBody::BumpSequence(op) => {
+            non_negative(v, "bump_to", op.bump_to.0);
+        }
+        xdr::OperationBody::CreateClaimableBalance(op) => {
+            check_asset(v, "asset", &op.asset);
+            positive(v, "amount", op.amount);
+            if op.claimants.is_empty() {
+                v.push("claimants cannot be empty".to_string());
+            }
+        }
+        xdr::OperationBody::Clawback(op) => {
+            check_asset(v, "asset", &op.asset);
+            positive(v, "amount", op.amount);
+        }
+        xdr::OperationBody::SetTrustLineFlags(op) => {
+            check_asset(v, "asset", &op.asset);
+        }
+        xdr::OperationBody::LiquidityPoolDeposit(op) => {
+            positive(v, "max_amount_a", op.max_amount_a);
+            positive(v, "max_amount_b", op.max_amount_b);
+            check_price(v, "min_price", &op.min_price);
+            check_price(v, "max_price", &op.max_price);
+        }
+        xdr::OperationBody::LiquidityPoolWithdraw(op) => {
+            positive(v, "amount", op.amount);
+            non_negative(v, "min_amount_a", op.min_amount_a);
+            non_negative(v, "min_amount_b", op.min_amount_b);
+        }
+        _ => {}
+    }
+    violations
+}
+
+fn positive(violations: &mut Vec<String>, name: &str, amount: i64) {
+    if amount <= 0 {
+        violations.push(format!("{} must be positive, got {}", name, amount));
+    }
+}
+
+fn non_negative(violations: &mut Vec<String>, name: &str, amount: i64) {
+    if amount < 0 {
+        violations.push(format!("{} cannot be negative, got {}", name, amount));
+    }
+}
+
+fn check_price(violations: &mut Vec<String>, name: &str, price: &xdr::Price) {
+    if price.n <= 0 || price.d <= 0 {
+        violations.push(format!(
+            "{} must be positive, got {}/{}",
+            name, price.n, price.d
+        ));
+    }
+}
+
+fn check_offer(
+    violations: &mut Vec<String>,
+    selling: &xdr::Asset,
+    buying: &xdr::Asset,
+    price: &xdr::Price,
+) {
+    check_asset(violations, "selling", selling);
+    check_asset(violations, "buying", buying);
+    if selling == buying {
+        violations.push("selling and buying must be different assets".to_string());
+    }
+    check_price(violations, "price", price);
+}
+
+fn check_asset(violations: &mut Vec<String>, name: &str, asset: &xdr::Asset) {
+    match asset {
+        xdr::Asset::Native => {}
+        xdr::Asset::CreditAlphanum4(a) => check_code(violations, name, &a.asset_code.0, 1),
+        xdr::Asset::CreditAlphanum12(a) => check_code(violations, name, &a.asset_code.0, 5),
+    }
+}
+
+/// Asset codes are `min_len` to `code.len()` ASCII alphanumeric characters, padded with zeros.
+fn check_code(violations: &mut Vec<String>, name: &str, code: &[u8], min_len: usize) {
+    let len = code.iter().position(|&c| c == 0).unwrap_or(code.len());
+    let (chars, padding) = code.split_at(len);
+    if len < min_len
+        || !chars.iter().all(u8::is_ascii_alphanumeric)
+        || padding.iter().any(|&c| c != 0)
+    {
+        violations.push(format!(
+            "{} has an invalid asset code {:?}",
+            name,
+            String::from_utf8_lossy(code)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(0), "0.0000000");
+        assert_eq!(format_amount(105_000_000), "10.5000000");
+        assert_eq!(format_amount(1), "0.0000001");
+        assert_eq!(format_amount(-ONE), "-1.0000000");
+        assert_eq!(format_amount(i64::MAX), "922337203685.4775807");
+        assert_eq!(format_amount(i64::MIN), "-922337203685.4775808");
+    }
+
+    #[test]
+    fn test_is_supported() {
+        let bump = Operation::new().bump_sequence(1).unwrap().body;
+        assert!(!Operation::is_supported(&bump, 9));
+        assert!(Operation::is_supported(&bump, 10));
+        assert!(Operation::is_supported(&bump, 25));
+
+        let restore = Operation::new().restore_footprint().unwrap().body;
+        assert!(!Operation::is_supported(&restore, 19));
+        assert!(Operation::is_supported(&restore, 20));
+
+        assert_eq!(
+            Operation::supported_protocols(&xdr::OperationBody::Inflation),
+            1..=11
+        );
+        assert!(!Operation::is_supported(&xdr::OperationBody::Inflation, 12));
+    }
+}
@@ -0,0 +1,188 @@
+//! Invocations of the Stellar Asset Contract (SAC) deployed for a classic asset.
+//!
+//! Amounts are raw `i128` token units, i.e. stroops for assets with 7 decimals, see
+//! [Soroban::parse_token_amount](crate::soroban::SorobanBehavior::parse_token_amount).
+use crate::asset::Asset;
+use crate::contract::{self, ContractBehavior, Contracts};
+use crate::operation::{self, Error};
+use crate::xdr;
+
+/// Builds the operations invoking the SAC of an asset.
+#[derive(Clone, Debug)]
+pub struct SacClient {
+    contract: Contracts,
+}
+
+impl SacClient {
+    /// Client for the SAC of `asset` on the network with passphrase `network`.
+    pub fn new(asset: &Asset, network: &str) -> Self {
+        Self::from_contract_id(&contract::contract_id_from_asset(asset, network))
+            .expect("contract ids derived from an asset are valid")
+    }
+
+    /// Client for an already known SAC id.
+    pub fn from_contract_id(contract_id: &str) -> Result<Self, Error> {
+        let contract = Contracts::new(contract_id)
+            .map_err(|_| operation::invalid_strkey(contract_id, "contract"))?;
+        Ok(Self { contract })
+    }
+
+    pub fn contract(&self) -> &Contracts {
+        &self.contract
+    }
+
+    pub fn contract_id(&self) -> String {
+        self.contract.contract_id()
+    }
+
+    /// Moves `amount` from `from` to `to`, which must authorize the invocation.
+    pub fn transfer(&self, from: &str, to: &str, amount: i128) -> Result<xdr::Operation, Error> {
+        let from = address(from, "from")?;
+        let to = address(to, "to")?;
+        self.invoke("transfer", vec![from, to, token_amount(amount)?])
+    }
+
+    /// Issues `amount` to `to`, authorized by the asset admin.
+    pub fn mint(&self, to: &str, amount: i128) -> Result<xdr::Operation, Error> {
+        let to = address(to, "to")?;
+        self.invoke("mint", vec![to, token_amount(amount)?])
+    }
+
+    /// Burns `amount` held by `from`.
+    pub fn burn(&self, from: &str, amount: i128) -> Result<xdr::Operation, Error> {
+        let from = address(from, "from")?;
+        self.invoke("burn", vec![from, token_amount(amount)?])
+    }
+
+    /// Allows `spender` to spend `amount` of `from` until `expiration_ledger`.
+    pub fn approve(
+        &self,
+        from: &str,
+        spender: &str,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<xdr::Operation, Error> {
+        let from = address(from, "from")?;
+        let spender = address(spender, "spender")?;
+        self.invoke(
+            "approve",
+            vec![
+                from,
+                spender,
+                token_amount(amount)?,
+                expiration_ledger.into(),
+            ],
+        )
+    }
+
+    /// Reads the balance of `id`, usually through a simulation.
+    pub fn balance(&self, id: &str) -> Result<xdr::Operation, Error> {
+        let id = address(id, "id")?;
+        self.invoke("balance", vec![id])
+    }
+
+    fn invoke(&self, method: &str, args: Vec<xdr::ScVal>) -> Result<xdr::Operation, Error> {
+        Ok(self.contract.call(method, Some(args)))
+    }
+}
+
+fn address(value: &str, kind: &str) -> Result<xdr::ScVal, Error> {
+    Ok(xdr::ScVal::Address(operation::parse_sc_address(
+        value, kind,
+    )?))
+}
+
+fn token_amount(amount: i128) -> Result<xdr::ScVal, Error> {
+    if amount < 0 {
+        return Err(Error::InvalidField("amount".into()));
+    }
+    Ok(amount.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::AssetBehavior;
+    use crate::network::{NetworkPassphrase, Networks};
+
+    const FROM: &str = "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB";
+    const TO: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    fn invoke_args(op: &xdr::Operation) -> (String, Vec<xdr::ScVal>) {
+        let xdr::OperationBody::InvokeHostFunction(invoke) = &op.body else {
+            panic!("expected an invocation");
+        };
+        let xdr::HostFunction::InvokeContract(args) = &invoke.host_function else {
+            panic!("expected a contract call");
+        };
+        (
+            args.function_name.0.to_utf8_string_lossy(),
+            args.args.to_vec(),
+        )
+    }
+
+    fn sc_address(value: &str) -> xdr::ScVal {
+        xdr::ScVal::Address(operation::parse_sc_address(value, "test").unwrap())
+    }
+
+    #[test]
+    fn test_native_client() {
+        let sac = SacClient::new(&Asset::native(), Networks::testnet());
+        assert_eq!(
+            sac.contract_id(),
+            "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"
+        );
+        assert!(SacClient::from_contract_id("CBAD").is_err());
+    }
+
+    #[test]
+    fn test_operations() {
+        let sac = SacClient::new(&Asset::native(), Networks::testnet());
+        let amount = xdr::ScVal::from(10_000_000i128);
+
+        assert_eq!(
+            invoke_args(&sac.transfer(FROM, TO, 10_000_000).unwrap()),
+            (
+                "transfer".into(),
+                vec![sc_address(FROM), sc_address(TO), amount.clone()]
+            )
+        );
+        assert_eq!(
+            invoke_args(&sac.mint(TO, 10_000_000).unwrap()),
+            ("mint".into(), vec![sc_address(TO), amount.clone()])
+        );
+        assert_eq!(
+            invoke_args(&sac.burn(FROM, 10_000_000).unwrap()),
+            ("burn".into(), vec![sc_address(FROM), amount.clone()])
+        );
+        assert_eq!(
+            invoke_args(&sac.approve(FROM, TO, 10_000_000, 1000).unwrap()),
+            (
+                "approve".into(),
+                vec![
+                    sc_address(FROM),
+                    sc_address(TO),
+                    amount,
+                    xdr::ScVal::U32(1000)
+                ]
+            )
+        );
+        assert_eq!(
+            invoke_args(&sac.balance(FROM).unwrap()),
+            ("balance".into(), vec![sc_address(FROM)])
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let sac = SacClient::new(&Asset::native(), Networks::testnet());
+        assert_eq!(
+            sac.transfer(FROM, TO, -1),
+            Err(Error::InvalidField("amount".into()))
+        );
+        assert!(matches!(
+            sac.mint("GBAD", 1),
+            Err(Error::InvalidStrkey { kind, .. }) if kind == "to"
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+//! The 128 and 256 bit integer contract values, built from and read as Rust integers, bytes
+//! or decimal strings.
+//!
+//! Token amounts are `i128`, so an amount of 10 tokens with 7 decimals is
+//! `ScInt::from_i128(ScIntType::I128, 100_000_000)`. The host splits these values into 64-bit
+//! limbs; [ScInt] takes care of the splitting and of the two's complement of negative values.
+use std::fmt;
+use std::str::FromStr;
+
+use crate::scval::{Error, FromScVal, ToScVal};
+use crate::xdr;
+
+/// The contract value type of an [ScInt].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScIntType {
+    I128,
+    U128,
+    I256,
+    U256,
+}
+
+impl ScIntType {
+    /// Width of the type in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            ScIntType::I128 | ScIntType::U128 => 16,
+            ScIntType::I256 | ScIntType::U256 => 32,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, ScIntType::I128 | ScIntType::I256)
+    }
+
+    /// Number of bits available for the magnitude of a positive value.
+    fn magnitude_bits(self) -> u32 {
+        self.size() as u32 * 8 - self.is_signed() as u32
+    }
+}
+
+/// A 128 or 256 bit integer of a given [ScIntType].
+///
+/// Values are checked against the range of their type when they are built, so converting to
+/// a contract value cannot fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScInt {
+    kind: ScIntType,
+    negative: bool,
+    /// Absolute value as little-endian 64-bit limbs.
+    magnitude: [u64; 4],
+}
+
+impl ScInt {
+    fn new(kind: ScIntType, negative: bool, magnitude: [u64; 4]) -> Result<Self, Error> {
+        let negative = negative && magnitude != [0; 4];
+        let bits = kind.magnitude_bits();
+        let length = bit_length(&magnitude);
+        let fits = if !negative {
+            length <= bits
+        } else {
+            // The most negative value has a magnitude one past the largest positive one.
+            kind.is_signed()
+                && (length <= bits || (length == bits + 1 && bit_length_of_rest(&magnitude) == 0))
+        };
+        if !fits {
+            return Err(Error::OutOfRange);
+        }
+        Ok(ScInt {
+            kind,
+            negative,
+            magnitude,
+        })
+    }
+
+    /// Builds a value from two's complement limbs, sign-extended to 256 bits for signed types.
+    fn from_limbs(kind: ScIntType, limbs: [u64; 4]) -> Result<Self, Error> {
+        let negative = kind.is_signed() && limbs[3] >> 63 == 1;
+        let magnitude = if negative { negate(limbs) } else { limbs };
+        Self::new(kind, negative, magnitude)
+    }
+
+    pub fn from_i128(kind: ScIntType, value: i128) -> Result<Self, Error> {
+        let abs = value.unsigned_abs();
+        Self::new(kind, value < 0, [abs as u64, (abs >> 64) as u64, 0, 0])
+    }
+
+    pub fn from_u128(kind: ScIntType, value: u128) -> Result<Self, Error> {
+        Self::new(kind, false, [value as u64, (value >> 64) as u64, 0, 0])
+    }
+
+    /// Reads the big-endian two's complement representation of the type, e.g. 16 bytes for
+    /// [ScIntType::I128].
+    pub fn from_be_bytes(kind: ScIntType, bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != kind.size() {
+            return Err(Error::InvalidLength {
+                expected: kind.size(),
+                actual: bytes.len(),
+            });
+        }
+        let fill = if kind.is_signed() && bytes[0] >> 7 == 1 {
+            0xff
+        } else {
+            0
+        };
+        let mut extended = [fill; 32];
+        extended[32 - bytes.len()..].copy_from_slice(bytes);
+        let mut limbs = [0; 4];
+        for (i, chunk) in extended.chunks(8).rev().enumerate() {
+            limbs[i] = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        Self::from_limbs(kind, limbs)
+    }
+
+    /// Parses a decimal integer with an optional sign, e.g. `-1000000000000000000000`.
+    pub fn parse(kind: ScIntType, value: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidNumber(value.to_string());
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        if digits.is_empty() {
+            return Err(invalid());
+        }
+        let mut magnitude = [0; 4];
+        for c in digits.chars() {
+            let digit = c.to_digit(10).ok_or_else(invalid)?;
+            magnitude = mul_add(magnitude, 10, digit as u64).ok_or(Error::OutOfRange)?;
+        }
+        Self::new(kind, negative, magnitude)
+    }
+
+    pub fn kind(&self) -> ScIntType {
+        self.kind
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i128(&self) -> Result<i128, Error> {
+        Self::new(ScIntType::I128, self.negative, self.magnitude)?;
+        let abs = (self.magnitude[1] as u128) << 64 | self.magnitude[0] as u128;
+        Ok(if self.negative {
+            0_i128.wrapping_sub_unsigned(abs)
+        } else {
+            abs as i128
+        })
+    }
+
+    pub fn to_u128(&self) -> Result<u128, Error> {
+        Self::new(ScIntType::U128, self.negative, self.magnitude)?;
+        Ok((self.magnitude[1] as u128) << 64 | self.magnitude[0] as u128)
+    }
+
+    /// Big-endian two's complement representation, as many bytes as the type is wide.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let limbs = self.limbs();
+        let bytes: Vec<u8> = limbs.iter().rev().flat_map(|l| l.to_be_bytes()).collect();
+        bytes[32 - self.kind.size()..].to_vec()
+    }
+
+    /// Converts to another type, if the value fits in it.
+    pub fn with_kind(&self, kind: ScIntType) -> Result<Self, Error> {
+        Self::new(kind, self.negative, self.magnitude)
+    }
+
+    pub fn to_scval(&self) -> xdr::ScVal {
+        let [l0, l1, l2, l3] = self.limbs();
+        match self.kind {
+            ScIntType::I128 => xdr::ScVal::I128(xdr::Int128Parts {
+                hi: l1 as i64,
+                lo: l0,
+            }),
+            ScIntType::U128 => xdr::ScVal::U128(xdr::UInt128Parts { hi: l1, lo: l0 }),
+            ScIntType::I256 => xdr::ScVal::I256(xdr::Int256Parts {
+                hi_hi: l3 as i64,
+                hi_lo: l2,
+                lo_hi: l1,
+                lo_lo: l0,
+            }),
+            ScIntType::U256 => xdr::ScVal::U256(xdr::UInt256Parts {
+                hi_hi: l3,
+                hi_lo: l2,
+                lo_hi: l1,
+                lo_lo: l0,
+            }),
+        }
+    }
+
+    /// Two's complement limbs of the value over 256 bits.
+    fn limbs(&self) -> [u64; 4] {
+        if self.negative {
+            negate(self.magnitude)
+        } else {
+            self.magnitude
+        }
+    }
+}
+
+impl ToScVal for ScInt {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        Ok(ScInt::to_scval(self))
+    }
+}
+
+impl FromScVal for ScInt {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        let sign = |hi: i64| if hi < 0 { u64::MAX } else { 0 };
+        match value {
+            xdr::ScVal::I128(parts) => ScInt::from_limbs(
+                ScIntType::I128,
+                [parts.lo, parts.hi as u64, sign(parts.hi), sign(parts.hi)],
+            ),
+            xdr::ScVal::U128(parts) => {
+                ScInt::from_limbs(ScIntType::U128, [parts.lo, parts.hi, 0, 0])
+            }
+            xdr::ScVal::I256(parts) => ScInt::from_limbs(
+                ScIntType::I256,
+                [parts.lo_lo, parts.lo_hi, parts.hi_lo, parts.hi_hi as u64],
+            ),
+            xdr::ScVal::U256(parts) => ScInt::from_limbs(
+                ScIntType::U256,
+                [parts.lo_lo, parts.lo_hi, parts.hi_lo, parts.hi_hi],
+            ),
+            _ => Err(Error::UnexpectedType {
+                expected: "I128, U128, I256 or U256",
+                found: value.discriminant(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<&xdr::ScVal> for ScInt {
+    type Error = Error;
+
+    fn try_from(value: &xdr::ScVal) -> Result<Self, Error> {
+        ScInt::from_scval(value)
+    }
+}
+
+impl From<ScInt> for xdr::ScVal {
+    fn from(value: ScInt) -> Self {
+        value.to_scval()
+    }
+}
+
+/// Parses a signed value as [ScIntType::I128], or [ScIntType::I256] if it does not fit.
+impl FromStr for ScInt {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match ScInt::parse(ScIntType::I128, s) {
+            Err(Error::OutOfRange) => ScInt::parse(ScIntType::I256, s),
+            result => result,
+        }
+    }
+}
+
+impl fmt::Display for ScInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = Vec::new();
+        let mut rest = self.magnitude;
+        loop {
+            let (quotient, digit) = div_rem(rest, 10);
+            digits.push(b'0' + digit as u8);
+            rest = quotient;
+            if rest == [0; 4] {
+                break;
+            }
+        }
+        if self.negative {
+            digits.push(b'-');
+        }
+        digits.reverse();
+        f.write_str(std::str::from_utf8(&digits).unwrap())
+    }
+}
+
+fn bit_length(limbs: &[u64; 4]) -> u32 {
+    (0..4)
+        .rev()
+        .find(|&i| limbs[i] != 0)
+        .map_or(0, |i| i as u32 * 64 + 64 - limbs[i].leading_zeros())
+}
+
+/// Bit length of `limbs` without its highest set bit.
+fn bit_length_of_rest(limbs: &[u64; 4]) -> u32 {
+    let length = bit_length(limbs);
+    if length == 0 {
+        return 0;
+    }
+    let mut rest = *limbs;
+    let top = (length - 1) as usize;
+    rest[top / 64] &= !(1 << (top % 64));
+    bit_length(&rest)
+}
+
+fn negate(limbs: [u64; 4]) -> [u64; 4] {
+    let mut result = [0; 4];
+    let mut carry = true;
+    for i in 0..4 {
+        let (value, overflow) = (!limbs[i]).overflowing_add(carry as u64);
+        result[i] = value;
+        carry = overflow;
+    }
+    result
+}
+
+/// `limbs * factor + addend`, or `None` on overflow.
+fn mul_add(limbs: [u64; 4], factor: u64, addend: u64) -> Option<[u64; 4]> {
+    let mut result = [0; 4];
+    let mut carry = addend as u128;
+    for i in 0..4 {
+        let value = limbs[i] as u128 * factor as u128 + carry;
+        result[i] = value as u64;
+        carry = value >> 64;
+    }
+    (carry == 0).then_some(result)
+}
+
+fn div_rem(limbs: [u64; 4], divisor: u64) -> ([u64; 4], u64) {
+    let mut quotient = [0; 4];
+    let mut remainder = 0_u128;
+    for i in (0..4).rev() {
+        let value = remainder << 64 | limbs[i] as u128;
+        quotient[i] = (value / divisor as u128) as u64;
+        remainder = value % divisor as u128;
+    }
+    (quotient, remainder as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scval::from_scval;
+
+    #[test]
+    fn test_i128_limbs() {
+        let value = ScInt::from_i128(ScIntType::I128, -1).unwrap();
+        assert_eq!(
+            value.to_scval(),
+            xdr::ScVal::I128(xdr::Int128Parts {
+                hi: -1,
+                lo: u64::MAX
+            })
+        );
+        let value = ScInt::from_i128(ScIntType::I128, 1 << 64 | 5).unwrap();
+        assert_eq!(
+            value.to_scval(),
+            xdr::ScVal::I128(xdr::Int128Parts { hi: 1, lo: 5 })
+        );
+
+        for n in [0, 1, -1, i128::MAX, i128::MIN, -(1 << 70)] {
+            let scval = (n).to_scval().unwrap();
+            let value = from_scval::<ScInt>(&scval).unwrap();
+            assert_eq!(value.to_i128(), Ok(n));
+            assert_eq!(value.to_scval(), scval);
+            assert_eq!(value.to_string(), n.to_string());
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            ScInt::from_i128(ScIntType::U128, -1),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            ScInt::from_u128(ScIntType::I128, u128::MAX),
+            Err(Error::OutOfRange)
+        );
+        let max = ScInt::from_u128(ScIntType::U256, u128::MAX).unwrap();
+        assert_eq!(max.to_u128(), Ok(u128::MAX));
+        assert_eq!(max.to_i128(), Err(Error::OutOfRange));
+
+        let min_i256 =
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+        let value = ScInt::parse(ScIntType::I256, min_i256).unwrap();
+        assert_eq!(value.to_string(), min_i256);
+        assert_eq!(
+            value.to_scval(),
+            xdr::ScVal::I256(xdr::Int256Parts {
+                hi_hi: i64::MIN,
+                hi_lo: 0,
+                lo_hi: 0,
+                lo_lo: 0
+            })
+        );
+        assert_eq!(value.with_kind(ScIntType::U256), Err(Error::OutOfRange));
+        // One below the minimum.
+        let below =
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819969";
+        assert_eq!(ScInt::parse(ScIntType::I256, below), Err(Error::OutOfRange));
+
+        let max_u256 =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        let value = ScInt::parse(ScIntType::U256, max_u256).unwrap();
+        assert_eq!(value.to_be_bytes(), vec![0xff; 32]);
+        assert_eq!(value.to_string(), max_u256);
+        assert_eq!(
+            ScInt::parse(
+                ScIntType::U256,
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            ),
+            Err(Error::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_strings_and_bytes() {
+        assert_eq!(
+            ScInt::parse(ScIntType::I128, "12a"),
+            Err(Error::InvalidNumber("12a".into()))
+        );
+        assert!(ScInt::parse(ScIntType::I128, "-").is_err());
+        assert_eq!("+42".parse::<ScInt>().unwrap().to_i128(), Ok(42));
+        let big: ScInt = "-170141183460469231731687303715884105729".parse().unwrap();
+        assert_eq!(big.kind(), ScIntType::I256);
+
+        let value = ScInt::from_be_bytes(ScIntType::I128, &[0xff; 16]).unwrap();
+        assert_eq!(value.to_i128(), Ok(-1));
+        assert_eq!(value.to_be_bytes(), vec![0xff; 16]);
+        let value = ScInt::from_be_bytes(ScIntType::U128, &[0xff; 16]).unwrap();
+        assert_eq!(value.to_u128(), Ok(u128::MAX));
+        assert_eq!(
+            ScInt::from_be_bytes(ScIntType::I256, &[0; 16]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                actual: 16
+            })
+        );
+        assert!(from_scval::<ScInt>(&xdr::ScVal::U32(1)).is_err());
+    }
+}
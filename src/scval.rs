@@ -0,0 +1,450 @@
+//! Conversion between Rust values and contract values.
+//!
+//! [ToScVal] maps the usual Rust types to the [xdr::ScVal] a contract expects, and
+//! [FromScVal], used through [from_scval], maps them back:
+//! - integers to the integer value of the same width and signedness,
+//! - `String` and `&str` to strings (use [xdr::ScSymbol] for symbols),
+//! - `Vec<u8>`, `[u8]` and `[u8; N]` to bytes,
+//! - other vectors to vectors, and maps to maps sorted by key as the host requires,
+//! - `None` to void,
+//! - [Address] to an address and [Asset] to its `CODE:ISSUER` (or `native`) name.
+//!
+//! Strings are also read from symbols. Integers are only read from values of their own type.
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+use crate::address::{Address, AddressTrait};
+use crate::asset::{Asset, AssetBehavior};
+use crate::xdr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A string, byte string, vector or map has more elements than XDR allows.
+    TooLong(usize),
+    /// The address cannot be represented as a contract value.
+    InvalidAddress(&'static str),
+    /// The value is not of the type expected for the Rust type.
+    UnexpectedType {
+        expected: &'static str,
+        found: xdr::ScValType,
+    },
+    /// Bytes do not have the length of the requested array.
+    InvalidLength { expected: usize, actual: usize },
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// A string is not a valid asset name.
+    InvalidAsset(String),
+    /// A number does not fit in the requested type.
+    OutOfRange,
+    /// A string is not a decimal integer.
+    InvalidNumber(String),
+}
+
+/// A Rust value that can be passed to a contract.
+pub trait ToScVal {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error>;
+}
+
+fn too_long<T>(len: usize) -> impl FnOnce(T) -> Error {
+    move |_| Error::TooLong(len)
+}
+
+macro_rules! impl_to_scval_from {
+    ($($ty:ty),*) => {
+        $(
+            impl ToScVal for $ty {
+                fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+                    Ok((*self).into())
+                }
+            }
+        )*
+    };
+}
+
+impl_to_scval_from!(bool, i32, u32, i64, u64, i128, u128);
+
+impl ToScVal for xdr::ScVal {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl ToScVal for xdr::ScSymbol {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        Ok(xdr::ScVal::Symbol(self.clone()))
+    }
+}
+
+impl ToScVal for str {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        let string = self.as_bytes().to_vec();
+        Ok(xdr::ScVal::String(xdr::ScString(
+            string.try_into().map_err(too_long(self.len()))?,
+        )))
+    }
+}
+
+impl ToScVal for String {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        self.as_str().to_scval()
+    }
+}
+
+impl ToScVal for [u8] {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        Ok(xdr::ScVal::Bytes(xdr::ScBytes(
+            self.to_vec().try_into().map_err(too_long(self.len()))?,
+        )))
+    }
+}
+
+impl<const N: usize> ToScVal for [u8; N] {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        self.as_slice().to_scval()
+    }
+}
+
+impl ToScVal for Vec<u8> {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        self.as_slice().to_scval()
+    }
+}
+
+impl<T: ToScVal> ToScVal for Vec<T> {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        let values = self
+            .iter()
+            .map(ToScVal::to_scval)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(xdr::ScVal::Vec(Some(xdr::ScVec(
+            values.try_into().map_err(too_long(self.len()))?,
+        ))))
+    }
+}
+
+impl<T: ToScVal> ToScVal for Option<T> {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        match self {
+            Some(value) => value.to_scval(),
+            None => Ok(xdr::ScVal::Void),
+        }
+    }
+}
+
+impl<T: ToScVal + ?Sized> ToScVal for &T {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        (**self).to_scval()
+    }
+}
+
+fn map<'a, K, V>(
+    entries: impl ExactSizeIterator<Item = (&'a K, &'a V)>,
+) -> Result<xdr::ScVal, Error>
+where
+    K: ToScVal + 'a,
+    V: ToScVal + 'a,
+{
+    let len = entries.len();
+    let mut entries = entries
+        .map(|(key, val)| {
+            Ok(xdr::ScMapEntry {
+                key: key.to_scval()?,
+                val: val.to_scval()?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(xdr::ScVal::Map(Some(xdr::ScMap(
+        entries.try_into().map_err(too_long(len))?,
+    ))))
+}
+
+impl<K: ToScVal, V: ToScVal, S> ToScVal for HashMap<K, V, S> {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        map(self.iter())
+    }
+}
+
+impl<K: ToScVal, V: ToScVal> ToScVal for BTreeMap<K, V> {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        map(self.iter())
+    }
+}
+
+impl ToScVal for Address {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        Ok(xdr::ScVal::Address(
+            self.to_sc_address().map_err(Error::InvalidAddress)?,
+        ))
+    }
+}
+
+impl ToScVal for Asset {
+    fn to_scval(&self) -> Result<xdr::ScVal, Error> {
+        self.to_string_asset().to_scval()
+    }
+}
+
+/// A Rust value that can be read from a contract value.
+pub trait FromScVal: Sized {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error>;
+}
+
+/// Converts `value`, e.g. the return value of a contract call, to `T`.
+pub fn from_scval<T: FromScVal>(value: &xdr::ScVal) -> Result<T, Error> {
+    T::from_scval(value)
+}
+
+fn unexpected(expected: &'static str, found: &xdr::ScVal) -> Error {
+    Error::UnexpectedType {
+        expected,
+        found: found.discriminant(),
+    }
+}
+
+macro_rules! impl_from_scval {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl FromScVal for $ty {
+                fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+                    match value {
+                        xdr::ScVal::$variant(_) => Ok(value.clone().try_into().expect(
+                            "values of the matching type always convert",
+                        )),
+                        _ => Err(unexpected(stringify!($variant), value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_scval!(
+    bool => Bool,
+    i32 => I32,
+    u32 => U32,
+    i64 => I64,
+    u64 => U64,
+    i128 => I128,
+    u128 => U128
+);
+
+impl FromScVal for xdr::ScVal {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        Ok(value.clone())
+    }
+}
+
+impl FromScVal for String {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        let bytes = match value {
+            xdr::ScVal::String(string) => string.as_slice(),
+            xdr::ScVal::Symbol(symbol) => symbol.as_slice(),
+            _ => return Err(unexpected("String", value)),
+        };
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::InvalidUtf8)
+    }
+}
+
+impl FromScVal for Vec<u8> {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        match value {
+            xdr::ScVal::Bytes(bytes) => Ok(bytes.to_vec()),
+            _ => Err(unexpected("Bytes", value)),
+        }
+    }
+}
+
+impl<const N: usize> FromScVal for [u8; N] {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        let bytes = Vec::<u8>::from_scval(value)?;
+        bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::InvalidLength {
+                expected: N,
+                actual: bytes.len(),
+            })
+    }
+}
+
+impl<T: FromScVal> FromScVal for Vec<T> {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        match value {
+            xdr::ScVal::Vec(Some(values)) => values.iter().map(T::from_scval).collect(),
+            _ => Err(unexpected("Vec", value)),
+        }
+    }
+}
+
+impl<T: FromScVal> FromScVal for Option<T> {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        match value {
+            xdr::ScVal::Void => Ok(None),
+            _ => T::from_scval(value).map(Some),
+        }
+    }
+}
+
+fn map_entries(value: &xdr::ScVal) -> Result<&[xdr::ScMapEntry], Error> {
+    match value {
+        xdr::ScVal::Map(Some(entries)) => Ok(entries.as_slice()),
+        _ => Err(unexpected("Map", value)),
+    }
+}
+
+impl<K: FromScVal + Eq + Hash, V: FromScVal> FromScVal for HashMap<K, V> {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        map_entries(value)?
+            .iter()
+            .map(|entry| Ok((K::from_scval(&entry.key)?, V::from_scval(&entry.val)?)))
+            .collect()
+    }
+}
+
+impl<K: FromScVal + Ord, V: FromScVal> FromScVal for BTreeMap<K, V> {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        map_entries(value)?
+            .iter()
+            .map(|entry| Ok((K::from_scval(&entry.key)?, V::from_scval(&entry.val)?)))
+            .collect()
+    }
+}
+
+impl FromScVal for Address {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        match value {
+            xdr::ScVal::Address(address) => {
+                Address::from_sc_address(address).map_err(Error::InvalidAddress)
+            }
+            _ => Err(unexpected("Address", value)),
+        }
+    }
+}
+
+impl FromScVal for Asset {
+    fn from_scval(value: &xdr::ScVal) -> Result<Self, Error> {
+        let name = String::from_scval(value)?;
+        if name == "native" {
+            return Ok(Asset::native());
+        }
+        let (code, issuer) = name
+            .split_once(':')
+            .ok_or_else(|| Error::InvalidAsset(name.clone()))?;
+        Asset::new(code, Some(issuer)).map_err(|_| Error::InvalidAsset(name.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(true.to_scval().unwrap(), xdr::ScVal::Bool(true));
+        assert_eq!((-1_i32).to_scval().unwrap(), xdr::ScVal::I32(-1));
+        assert_eq!(7_u64.to_scval().unwrap(), xdr::ScVal::U64(7));
+        assert_eq!(
+            (-5_i128).to_scval().unwrap(),
+            xdr::ScVal::I128(xdr::Int128Parts {
+                hi: -1,
+                lo: u64::MAX - 4
+            })
+        );
+        assert_eq!(
+            "hello".to_scval().unwrap(),
+            xdr::ScVal::String(xdr::ScString("hello".try_into().unwrap()))
+        );
+        assert_eq!(
+            vec![1_u8, 2].to_scval().unwrap(),
+            xdr::ScVal::Bytes(xdr::ScBytes(vec![1, 2].try_into().unwrap()))
+        );
+        assert_eq!([3_u8; 4].to_scval(), [3_u8; 4][..].to_scval());
+        assert_eq!(None::<u32>.to_scval().unwrap(), xdr::ScVal::Void);
+    }
+
+    #[test]
+    fn test_collections() {
+        assert_eq!(
+            vec![1_u32, 2].to_scval().unwrap(),
+            xdr::ScVal::Vec(Some(xdr::ScVec(
+                vec![xdr::ScVal::U32(1), xdr::ScVal::U32(2)]
+                    .try_into()
+                    .unwrap()
+            )))
+        );
+
+        let map = HashMap::from([("b", 2_u32), ("a", 1), ("c", 3)]);
+        let xdr::ScVal::Map(Some(entries)) = map.to_scval().unwrap() else {
+            panic!("expected a map");
+        };
+        let keys: Vec<_> = entries.iter().map(|entry| entry.key.clone()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "a".to_scval().unwrap(),
+                "b".to_scval().unwrap(),
+                "c".to_scval().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_scval() {
+        assert_eq!(from_scval::<u32>(&xdr::ScVal::U32(3)), Ok(3));
+        assert_eq!(from_scval::<i128>(&(-5_i128).to_scval().unwrap()), Ok(-5));
+        assert_eq!(
+            from_scval::<u64>(&xdr::ScVal::U32(3)),
+            Err(Error::UnexpectedType {
+                expected: "U64",
+                found: xdr::ScValType::U32
+            })
+        );
+        let symbol = xdr::ScVal::Symbol("transfer".try_into().unwrap());
+        assert_eq!(from_scval::<String>(&symbol).unwrap(), "transfer");
+        assert_eq!(from_scval::<Option<u32>>(&xdr::ScVal::Void), Ok(None));
+        assert_eq!(
+            from_scval::<[u8; 2]>(&[1_u8, 2, 3].to_scval().unwrap()),
+            Err(Error::InvalidLength {
+                expected: 2,
+                actual: 3
+            })
+        );
+
+        let values = vec![Some(1_u32), None];
+        assert_eq!(from_scval(&values.to_scval().unwrap()), Ok(values));
+        let map = HashMap::from([("a".to_string(), vec![1_u8]), ("b".to_string(), vec![])]);
+        assert_eq!(from_scval(&map.to_scval().unwrap()), Ok(map));
+        let map = BTreeMap::from([(1_i64, true), (-1, false)]);
+        assert_eq!(from_scval(&map.to_scval().unwrap()), Ok(map));
+    }
+
+    #[test]
+    fn test_address_and_asset() {
+        let account = "GB7TAYRUZGE6TVT7NHP5SMIZRNQA6PLM423EYISAOAP3MKYIQMVYP2JO";
+        let address = Address::new(account).unwrap();
+        assert_eq!(
+            address.to_scval().unwrap(),
+            xdr::ScVal::Address(address.to_sc_address().unwrap())
+        );
+        assert_eq!(
+            Asset::new("USD", Some(account))
+                .unwrap()
+                .to_scval()
+                .unwrap(),
+            format!("USD:{account}").to_scval().unwrap()
+        );
+        assert_eq!(
+            Asset::native().to_scval().unwrap(),
+            "native".to_scval().unwrap()
+        );
+
+        assert_eq!(from_scval(&address.to_scval().unwrap()), Ok(address));
+        let usd = Asset::new("USD", Some(account)).unwrap();
+        assert_eq!(from_scval(&usd.to_scval().unwrap()), Ok(usd));
+        assert_eq!(
+            from_scval::<Asset>(&"USD".to_scval().unwrap()),
+            Err(Error::InvalidAsset("USD".to_string()))
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Key derivation from BIP-39 mnemonics as specified by
+//! [SEP-5](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0005.md):
+//! the BIP-39 seed of the phrase is derived along the SLIP-10 ed25519 path `m/44'/148'/i'`.
+//!
+//! Phrases are checked against the BIP-39 English word list and their checksum. Phrases and
+//! passphrases are used as given, words separated by single spaces, so they must be ASCII:
+//! other text would need Unicode NFKD normalization first.
+use std::error::Error;
+use std::fmt;
+
+use sha2::{Digest, Sha256, Sha512};
+
+const BLOCK_SIZE: usize = 128;
+const PBKDF2_ROUNDS: u32 = 2048;
+const HARDENED: u32 = 0x8000_0000;
+const ENGLISH: &str = include_str!("sep5/english.txt");
+
+/// Why a mnemonic or passphrase was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MnemonicError {
+    /// Contains characters outside of ASCII, which are not normalized.
+    NonAscii,
+    /// Has a number of words other than 12, 15, 18, 21 or 24.
+    InvalidWordCount(usize),
+    /// Has a word that is not in the BIP-39 English word list.
+    UnknownWord(String),
+    /// The checksum does not match, usually because of a typo or a swapped word.
+    InvalidChecksum,
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MnemonicError::NonAscii => f.write_str("mnemonic is not ASCII"),
+            MnemonicError::InvalidWordCount(count) => {
+                write!(
+                    f,
+                    "mnemonic has {count} words, expected 12, 15, 18, 21 or 24"
+                )
+            }
+            MnemonicError::UnknownWord(word) => {
+                write!(f, "{word:?} is not in the BIP-39 English word list")
+            }
+            MnemonicError::InvalidChecksum => f.write_str("mnemonic has an invalid checksum"),
+        }
+    }
+}
+
+impl Error for MnemonicError {}
+
+/// Checks that `phrase` is a BIP-39 mnemonic of the English word list with a valid checksum.
+pub fn check_mnemonic(phrase: &str) -> Result<(), MnemonicError> {
+    if !phrase.is_ascii() {
+        return Err(MnemonicError::NonAscii);
+    }
+    let words = phrase.split_whitespace().collect::<Vec<_>>();
+    if !matches!(words.len(), 12 | 15 | 18 | 21 | 24) {
+        return Err(MnemonicError::InvalidWordCount(words.len()));
+    }
+
+    let word_list = ENGLISH.lines().collect::<Vec<_>>();
+    let mut bits = Vec::with_capacity(words.len() * 11);
+    for word in words {
+        let index = word_list
+            .binary_search(&word)
+            .map_err(|_| MnemonicError::UnknownWord(word.to_string()))?;
+        bits.extend((0..11).rev().map(|i| (index >> i) & 1 == 1));
+    }
+
+    // Each 3 words hold 32 bits of entropy and 1 bit of checksum.
+    let (entropy_bits, checksum_bits) = bits.split_at(bits.len() / 33 * 32);
+    let entropy = entropy_bits
+        .chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .fold(0u8, |acc, &bit| (acc << 1) | u8::from(bit))
+        })
+        .collect::<Vec<_>>();
+    let hash = Sha256::digest(&entropy);
+    let expected = (0..checksum_bits.len()).map(|i| (hash[i / 8] >> (7 - i % 8)) & 1 == 1);
+    if !expected.eq(checksum_bits.iter().copied()) {
+        return Err(MnemonicError::InvalidChecksum);
+    }
+    Ok(())
+}
+
+/// Returns the 64 byte BIP-39 seed of `phrase` protected by `passphrase`, once `phrase` is
+/// checked with [check_mnemonic].
+pub fn seed_from_mnemonic(phrase: &str, passphrase: &str) -> Result<[u8; 64], MnemonicError> {
+    check_mnemonic(phrase)?;
+    seed_from_mnemonic_unchecked(phrase, passphrase)
+}
+
+/// Returns the BIP-39 seed like [seed_from_mnemonic] without checking the words of `phrase`,
+/// e.g. for a phrase of another ASCII word list.
+///
+/// Still fails if `phrase` or `passphrase` is not ASCII.
+pub fn seed_from_mnemonic_unchecked(
+    phrase: &str,
+    passphrase: &str,
+) -> Result<[u8; 64], MnemonicError> {
+    if !phrase.is_ascii() || !passphrase.is_ascii() {
+        return Err(MnemonicError::NonAscii);
+    }
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let salt = format!("mnemonic{passphrase}");
+    Ok(pbkdf2_hmac_sha512(
+        phrase.as_bytes(),
+        salt.as_bytes(),
+        PBKDF2_ROUNDS,
+    ))
+}
+
+/// Returns the ed25519 seed of the account `account_index` of the BIP-39 `seed`, i.e. the
+/// key at the path `m/44'/148'/account_index'`.
+pub fn derive_account_seed(seed: &[u8], account_index: u32) -> [u8; 32] {
+    let (mut key, mut chain_code) = split(hmac_sha512(b"ed25519 seed", seed));
+    for index in [44, 148, account_index] {
+        let mut data = Vec::with_capacity(37);
+        data.push(0);
+        data.extend_from_slice(&key);
+        data.extend_from_slice(&(index | HARDENED).to_be_bytes());
+        (key, chain_code) = split(hmac_sha512(&chain_code, &data));
+    }
+    key
+}
+
+fn split(digest: [u8; 64]) -> ([u8; 32], [u8; 32]) {
+    let mut key = [0; 32];
+    let mut chain_code = [0; 32];
+    key.copy_from_slice(&digest[..32]);
+    chain_code.copy_from_slice(&digest[32..]);
+    (key, chain_code)
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..64].copy_from_slice(&Sha512::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha512::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha512::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// PBKDF2 with a single block of output, which is all a 64 byte key needs.
+fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], rounds: u32) -> [u8; 64] {
+    let mut data = salt.to_vec();
+    data.extend_from_slice(&1u32.to_be_bytes());
+    let mut u = hmac_sha512(password, &data);
+    let mut result = u;
+    for _ in 1..rounds {
+        u = hmac_sha512(password, &u);
+        result.iter_mut().zip(u).for_each(|(r, u)| *r ^= u);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "illness spike retreat truth genius clock brain pass fit cave bargain toe";
+
+    #[test]
+    fn test_hmac_sha512() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hex::encode(hmac_sha512(b"Jefe", b"what do ya want for nothing?")),
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        );
+    }
+
+    #[test]
+    fn test_seed_from_mnemonic() {
+        assert_eq!(
+            hex::encode(seed_from_mnemonic(PHRASE, "").unwrap()),
+            "e4a5a632e70943ae7f07659df1332160937fad82587216a4c64315a0fb39497ee4a01f76ddab4cba68147977f3a147b6ad584c41808e8238a07f6cc4b582f186"
+        );
+        assert_eq!(
+            seed_from_mnemonic(&format!("  {}  ", PHRASE.replace(' ', "\n")), ""),
+            seed_from_mnemonic(PHRASE, "")
+        );
+        assert_ne!(
+            seed_from_mnemonic(PHRASE, "p4ssphr4se"),
+            seed_from_mnemonic(PHRASE, "")
+        );
+        assert_eq!(
+            seed_from_mnemonic(PHRASE, "pässphrase"),
+            Err(MnemonicError::NonAscii)
+        );
+    }
+
+    #[test]
+    fn test_check_mnemonic() {
+        let valid = [
+            PHRASE,
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+        ];
+        for phrase in valid {
+            assert_eq!(check_mnemonic(phrase), Ok(()), "{phrase}");
+        }
+
+        assert_eq!(
+            check_mnemonic(&"abandon ".repeat(12)),
+            Err(MnemonicError::InvalidChecksum)
+        );
+        assert_eq!(
+            check_mnemonic(&PHRASE.replace("toe", "zoo")),
+            Err(MnemonicError::InvalidChecksum)
+        );
+        assert_eq!(
+            check_mnemonic(&PHRASE.replace("toe", "toes")),
+            Err(MnemonicError::UnknownWord("toes".into()))
+        );
+        assert_eq!(
+            check_mnemonic(&"abandon ".repeat(11)),
+            Err(MnemonicError::InvalidWordCount(11))
+        );
+        assert_eq!(
+            check_mnemonic(&PHRASE.replace("toe", "tōe")),
+            Err(MnemonicError::NonAscii)
+        );
+        assert!(seed_from_mnemonic_unchecked(&"abandon ".repeat(12), "").is_ok());
+    }
+}
@@ -1,5 +1,4 @@
-use core::panic;
-use std::{collections::HashMap, str::FromStr};
+use std::{fmt, str::FromStr};
 
 use crate::xdr;
 use crate::xdr::{SignerKey as XDRSignerKey, SignerKeyEd25519SignedPayload};
@@ -8,56 +7,157 @@ use stellar_strkey::{
     HashX, PreAuthTx,
 };
 
-pub struct SignerKey;
+/// A signer of a Stellar account, as encoded by its strkey.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SignerKey {
+    /// An ed25519 public key (`G...`).
+    Ed25519([u8; 32]),
+    /// The hash of a pre-authorized transaction (`T...`).
+    PreAuthTx([u8; 32]),
+    /// The sha256 hash of a preimage (`X...`).
+    HashX([u8; 32]),
+    /// An ed25519 public key that must sign the given payload (`P...`).
+    Ed25519SignedPayload { ed25519: [u8; 32], payload: Vec<u8> },
+}
 
-// Define a trait for SignerKey behavior
-pub trait SignerKeyBehavior {
-    fn decode_address(address: &str) -> XDRSignerKey;
-    fn encode_signer_key(signer_key: &XDRSignerKey) -> String;
+impl SignerKey {
+    /// Converts the signer to its xdr representation.
+    ///
+    /// Fails if a signed payload is longer than 64 bytes.
+    pub fn to_xdr(&self) -> Result<XDRSignerKey, &'static str> {
+        Ok(match self {
+            SignerKey::Ed25519(key) => XDRSignerKey::Ed25519(xdr::Uint256(*key)),
+            SignerKey::PreAuthTx(hash) => XDRSignerKey::PreAuthTx(xdr::Uint256(*hash)),
+            SignerKey::HashX(hash) => XDRSignerKey::HashX(xdr::Uint256(*hash)),
+            SignerKey::Ed25519SignedPayload { ed25519, payload } => {
+                XDRSignerKey::Ed25519SignedPayload(SignerKeyEd25519SignedPayload {
+                    ed25519: xdr::Uint256(*ed25519),
+                    payload: payload
+                        .clone()
+                        .try_into()
+                        .map_err(|_| "Signed payload is too long")?,
+                })
+            }
+        })
+    }
+
+    /// Builds the signer part of a [RevokeSponsorship](xdr::RevokeSponsorshipOp::Signer)
+    /// operation for this signer on `account_id`.
+    pub fn to_revoke_sponsorship_signer(
+        &self,
+        account_id: xdr::AccountId,
+    ) -> Result<xdr::RevokeSponsorshipOpSigner, &'static str> {
+        Ok(xdr::RevokeSponsorshipOpSigner {
+            account_id,
+            signer_key: self.to_xdr()?,
+        })
+    }
 }
 
-impl SignerKeyBehavior for SignerKey {
-    fn decode_address(address: &str) -> XDRSignerKey {
-        let val = stellar_strkey::Strkey::from_string(address);
-        if val.is_err() {
-            panic!("Invalid Type")
-        }
+impl FromStr for SignerKey {
+    type Err = &'static str;
 
-        match val.unwrap() {
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        match stellar_strkey::Strkey::from_string(address).map_err(|_| "Invalid Type")? {
+            stellar_strkey::Strkey::PublicKeyEd25519(x) => Ok(SignerKey::Ed25519(x.0)),
+            stellar_strkey::Strkey::PreAuthTx(x) => Ok(SignerKey::PreAuthTx(x.0)),
+            stellar_strkey::Strkey::HashX(x) => Ok(SignerKey::HashX(x.0)),
             stellar_strkey::Strkey::SignedPayloadEd25519(x) => {
-                XDRSignerKey::Ed25519SignedPayload(SignerKeyEd25519SignedPayload {
-                    ed25519: xdr::Uint256(x.ed25519),
-                    payload: x.payload.try_into().unwrap(),
+                Ok(SignerKey::Ed25519SignedPayload {
+                    ed25519: x.ed25519,
+                    payload: x.payload,
                 })
             }
-            stellar_strkey::Strkey::PublicKeyEd25519(x) => XDRSignerKey::Ed25519(xdr::Uint256(x.0)),
-            stellar_strkey::Strkey::PreAuthTx(x) => XDRSignerKey::PreAuthTx(xdr::Uint256(x.0)),
-            stellar_strkey::Strkey::HashX(x) => XDRSignerKey::HashX(xdr::Uint256(x.0)),
-            _ => panic!("Invalid Type"),
+            _ => Err("Invalid Type"),
         }
     }
+}
 
-    fn encode_signer_key(signer_key: &XDRSignerKey) -> String {
-        match signer_key {
-            XDRSignerKey::Ed25519(x) => {
-                stellar_strkey::Strkey::PublicKeyEd25519(PublicKey::from_payload(&x.0).unwrap())
-                    .to_string()
-            }
-            XDRSignerKey::PreAuthTx(x) => {
-                stellar_strkey::Strkey::PreAuthTx(PreAuthTx(x.0)).to_string()
-            }
-            XDRSignerKey::HashX(x) => stellar_strkey::Strkey::HashX(HashX(x.0)).to_string(),
-            XDRSignerKey::Ed25519SignedPayload(x) => {
+impl TryFrom<&str> for SignerKey {
+    type Error = &'static str;
+
+    fn try_from(address: &str) -> Result<Self, Self::Error> {
+        address.parse()
+    }
+}
+
+impl fmt::Display for SignerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let strkey = match self {
+            SignerKey::Ed25519(key) => stellar_strkey::Strkey::PublicKeyEd25519(PublicKey(*key)),
+            SignerKey::PreAuthTx(hash) => stellar_strkey::Strkey::PreAuthTx(PreAuthTx(*hash)),
+            SignerKey::HashX(hash) => stellar_strkey::Strkey::HashX(HashX(*hash)),
+            SignerKey::Ed25519SignedPayload { ed25519, payload } => {
                 stellar_strkey::Strkey::SignedPayloadEd25519(SignedPayload {
-                    ed25519: x.ed25519.0,
-                    payload: x.payload.clone().into_vec(),
+                    ed25519: *ed25519,
+                    payload: payload.clone(),
                 })
-                .to_string()
             }
+        };
+        write!(f, "{}", strkey)
+    }
+}
+
+impl From<&XDRSignerKey> for SignerKey {
+    fn from(value: &XDRSignerKey) -> Self {
+        match value {
+            XDRSignerKey::Ed25519(x) => SignerKey::Ed25519(x.0),
+            XDRSignerKey::PreAuthTx(x) => SignerKey::PreAuthTx(x.0),
+            XDRSignerKey::HashX(x) => SignerKey::HashX(x.0),
+            XDRSignerKey::Ed25519SignedPayload(x) => SignerKey::Ed25519SignedPayload {
+                ed25519: x.ed25519.0,
+                payload: x.payload.to_vec(),
+            },
         }
     }
 }
 
+impl From<XDRSignerKey> for SignerKey {
+    fn from(value: XDRSignerKey) -> Self {
+        SignerKey::from(&value)
+    }
+}
+
+impl From<&xdr::RevokeSponsorshipOpSigner> for SignerKey {
+    fn from(value: &xdr::RevokeSponsorshipOpSigner) -> Self {
+        SignerKey::from(&value.signer_key)
+    }
+}
+
+impl TryFrom<&SignerKey> for XDRSignerKey {
+    type Error = &'static str;
+
+    fn try_from(value: &SignerKey) -> Result<Self, Self::Error> {
+        value.to_xdr()
+    }
+}
+
+impl TryFrom<SignerKey> for XDRSignerKey {
+    type Error = &'static str;
+
+    fn try_from(value: SignerKey) -> Result<Self, Self::Error> {
+        value.to_xdr()
+    }
+}
+
+// Define a trait for SignerKey behavior
+pub trait SignerKeyBehavior {
+    fn decode_address(address: &str) -> XDRSignerKey;
+    fn encode_signer_key(signer_key: &XDRSignerKey) -> String;
+}
+
+impl SignerKeyBehavior for SignerKey {
+    fn decode_address(address: &str) -> XDRSignerKey {
+        SignerKey::from_str(address)
+            .and_then(|key| key.to_xdr())
+            .unwrap_or_else(|_| panic!("Invalid Type"))
+    }
+
+    fn encode_signer_key(signer_key: &XDRSignerKey) -> String {
+        SignerKey::from(signer_key).to_string()
+    }
+}
+
 fn assert_panic<F: FnOnce(), S: AsRef<str>>(f: F, expected_msg: S) {
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
     match result {
@@ -145,4 +245,39 @@ mod tests {
         };
         assert_panic(scenario_1, "Invalid Type")
     }
+
+    #[test]
+    fn test_signer_key_enum_roundtrip() {
+        for test_case in &TEST_CASES {
+            let key = SignerKey::from_str(test_case.strkey).unwrap();
+            assert_eq!(key.to_string(), test_case.strkey);
+
+            let xdr_key = xdr::SignerKey::try_from(&key).unwrap();
+            assert_eq!(xdr_key.discriminant(), test_case.r#type);
+            assert_eq!(SignerKey::from(&xdr_key), key);
+
+            let account_id = xdr::AccountId::from_str(TEST_CASES[0].strkey).unwrap();
+            let revoke = key
+                .to_revoke_sponsorship_signer(account_id.clone())
+                .unwrap();
+            assert_eq!(revoke.account_id, account_id);
+            assert_eq!(revoke.signer_key, xdr_key);
+            assert_eq!(SignerKey::from(&revoke), key);
+        }
+    }
+
+    #[test]
+    fn test_signer_key_enum_errors() {
+        assert_eq!(
+            SignerKey::from_str("SAB5556L5AN5KSR5WF7UOEFDCIODEWEO7H2UR4S5R62DFTQOGLKOVZDY"),
+            Err("Invalid Type")
+        );
+        assert_eq!(SignerKey::from_str("NONSENSE"), Err("Invalid Type"));
+
+        let key = SignerKey::Ed25519SignedPayload {
+            ed25519: [0; 32],
+            payload: vec![0; 65],
+        };
+        assert_eq!(key.to_xdr(), Err("Signed payload is too long"));
+    }
 }
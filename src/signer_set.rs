@@ -0,0 +1,298 @@
+use std::str::FromStr;
+
+use crate::keypair::{Keypair, KeypairBehavior};
+use crate::operation::{self, Operation};
+use crate::transaction::Transaction;
+use crate::xdr;
+
+/// Signature weights an account requires for low, medium and high threshold operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Thresholds {
+    pub low: u8,
+    pub med: u8,
+    pub high: u8,
+}
+
+/// The signing setup of a single account: its master key plus additional weighted keys,
+/// e.g. one per device.
+///
+/// A `SignerSet` can produce the SetOptions operations configuring the account on the network,
+/// and pick which of its keys to sign a transaction with.
+#[derive(Debug, Clone)]
+pub struct SignerSet {
+    master: Keypair,
+    master_weight: u8,
+    signers: Vec<(Keypair, u8)>,
+    thresholds: Thresholds,
+}
+
+impl SignerSet {
+    /// Creates a set for the account of `master`, with a master weight of 1 and all
+    /// thresholds at 0, like a newly created account.
+    pub fn new(master: Keypair) -> Self {
+        Self {
+            master,
+            master_weight: 1,
+            signers: Vec::new(),
+            thresholds: Thresholds::default(),
+        }
+    }
+
+    pub fn master(&self) -> &Keypair {
+        &self.master
+    }
+
+    pub fn master_weight(&mut self, weight: u8) -> &mut Self {
+        self.master_weight = weight;
+        self
+    }
+
+    /// Adds `key` with `weight`, replacing the weight if the key is already part of the set.
+    pub fn add_signer(&mut self, key: Keypair, weight: u8) -> &mut Self {
+        match self
+            .signers
+            .iter_mut()
+            .find(|(signer, _)| signer.raw_pubkey() == key.raw_pubkey())
+        {
+            Some((_, w)) => *w = weight,
+            None => self.signers.push((key, weight)),
+        }
+        self
+    }
+
+    pub fn thresholds(&mut self, thresholds: Thresholds) -> &mut Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Returns the SetOptions operations configuring the account with this set: one per
+    /// additional signer, followed by one setting the master weight and thresholds.
+    ///
+    /// Signers are added first so that the account cannot be locked out halfway through.
+    /// The operations have no source, so the transaction source must be the master account.
+    pub fn operations(&self) -> Result<Vec<xdr::Operation>, operation::Error> {
+        let mut operations = self
+            .signers
+            .iter()
+            .map(|(key, weight)| {
+                Operation::new().set_options(
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some((&key.public_key(), *weight)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        operations.push(Operation::new().set_options(
+            None,
+            None,
+            None,
+            self.master_weight,
+            self.thresholds.low,
+            self.thresholds.med,
+            self.thresholds.high,
+            None,
+            None,
+        )?);
+        Ok(operations)
+    }
+
+    /// Returns the signature weight the account must provide for `tx`: the highest threshold
+    /// among the operations it is the source of, and at least the low threshold if it is the
+    /// transaction source. Returns `None` if the account is not involved in `tx`.
+    pub fn required_threshold(&self, tx: &Transaction) -> Option<u8> {
+        let account = self.master.raw_pubkey();
+        let tx_source = tx
+            .source
+            .as_deref()
+            .and_then(|source| xdr::MuxedAccount::from_str(source).ok())
+            .map(|source| account_key(&source));
+
+        let mut required = (tx_source == Some(account)).then_some(self.thresholds.low);
+        for op in tx.operations.iter().flatten() {
+            let source = op.source_account.as_ref().map(account_key).or(tx_source);
+            if source == Some(account) {
+                let threshold = self.threshold_for(op);
+                required = Some(required.map_or(threshold, |r| r.max(threshold)));
+            }
+        }
+        required
+    }
+
+    /// Returns the keys to sign `tx` with, heaviest first, stopping as soon as their combined
+    /// weight meets the required threshold. Returns `None` if the whole set is not enough.
+    ///
+    /// An empty list is returned if the account is not involved in `tx`.
+    pub fn signers_for(&self, tx: &Transaction) -> Option<Vec<&Keypair>> {
+        let Some(threshold) = self.required_threshold(tx) else {
+            return Some(Vec::new());
+        };
+        // A signature is always needed, even if the threshold is 0.
+        let threshold = u32::from(threshold.max(1));
+
+        let mut keys = std::iter::once((&self.master, self.master_weight))
+            .chain(self.signers.iter().map(|(key, weight)| (key, *weight)))
+            .filter(|(_, weight)| *weight > 0)
+            .collect::<Vec<_>>();
+        keys.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+
+        let mut total = 0;
+        let mut signers = Vec::new();
+        for (key, weight) in keys {
+            signers.push(key);
+            total += u32::from(weight);
+            if total >= threshold {
+                return Some(signers);
+            }
+        }
+        None
+    }
+
+    fn threshold_for(&self, op: &xdr::Operation) -> u8 {
+        match &op.body {
+            xdr::OperationBody::AllowTrust(_)
+            | xdr::OperationBody::SetTrustLineFlags(_)
+            | xdr::OperationBody::BumpSequence(_)
+            | xdr::OperationBody::ClaimClaimableBalance(_)
+            | xdr::OperationBody::ExtendFootprintTtl(_)
+            | xdr::OperationBody::RestoreFootprint(_)
+            | xdr::OperationBody::Inflation => self.thresholds.low,
+            xdr::OperationBody::AccountMerge(_) => self.thresholds.high,
+            xdr::OperationBody::SetOptions(op)
+                if op.master_weight.is_some()
+                    || op.low_threshold.is_some()
+                    || op.med_threshold.is_some()
+                    || op.high_threshold.is_some()
+                    || op.signer.is_some() =>
+            {
+                self.thresholds.high
+            }
+            _ => self.thresholds.med,
+        }
+    }
+}
+
+fn account_key(account: &xdr::MuxedAccount) -> [u8; 32] {
+    match account {
+        xdr::MuxedAccount::Ed25519(xdr::Uint256(key)) => *key,
+        xdr::MuxedAccount::MuxedEd25519(muxed) => muxed.ed25519.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::{Account, AccountBehavior};
+    use crate::network::{NetworkPassphrase, Networks};
+    use crate::transaction_builder::{
+        TransactionBuilder, TransactionBuilderBehavior, TIMEOUT_INFINITE,
+    };
+
+    fn transaction(source: &Keypair, operations: Vec<xdr::Operation>) -> Transaction {
+        let mut account = Account::new(&source.public_key(), "1").unwrap();
+        let mut builder = TransactionBuilder::new(&mut account, Networks::testnet(), None);
+        builder.fee(100_u32);
+        for op in operations {
+            builder.add_operation(op);
+        }
+        builder
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap()
+    }
+
+    fn signer_set() -> (SignerSet, Keypair, Keypair) {
+        let phone = Keypair::random().unwrap();
+        let laptop = Keypair::random().unwrap();
+        let mut set = SignerSet::new(Keypair::random().unwrap());
+        set.master_weight(10)
+            .add_signer(phone.clone(), 5)
+            .add_signer(laptop.clone(), 3)
+            .thresholds(Thresholds {
+                low: 1,
+                med: 5,
+                high: 10,
+            });
+        (set, phone, laptop)
+    }
+
+    #[test]
+    fn test_operations() {
+        let (set, phone, _) = signer_set();
+        let operations = set.operations().unwrap();
+        assert_eq!(operations.len(), 3);
+
+        let xdr::OperationBody::SetOptions(first) = &operations[0].body else {
+            panic!("expected set_options");
+        };
+        assert_eq!(
+            first.signer,
+            Some(xdr::Signer {
+                key: xdr::SignerKey::Ed25519(xdr::Uint256(phone.raw_pubkey())),
+                weight: 5,
+            })
+        );
+
+        let xdr::OperationBody::SetOptions(last) = &operations[2].body else {
+            panic!("expected set_options");
+        };
+        assert_eq!(last.master_weight, Some(10));
+        assert_eq!(last.low_threshold, Some(1));
+        assert_eq!(last.med_threshold, Some(5));
+        assert_eq!(last.high_threshold, Some(10));
+        assert_eq!(last.signer, None);
+    }
+
+    #[test]
+    fn test_signers_for() {
+        let (mut set, phone, laptop) = signer_set();
+        let master = set.master().clone();
+
+        let bump = transaction(&master, vec![Operation::new().bump_sequence(2).unwrap()]);
+        assert_eq!(set.required_threshold(&bump), Some(1));
+        let signers = set.signers_for(&bump).unwrap();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].public_key(), master.public_key());
+
+        let merge = transaction(
+            &master,
+            vec![Operation::new().account_merge(&phone.public_key()).unwrap()],
+        );
+        assert_eq!(set.required_threshold(&merge), Some(10));
+
+        set.master_weight(0);
+        assert!(set.signers_for(&merge).is_none());
+        let signers = set.signers_for(&bump).unwrap();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].public_key(), phone.public_key());
+
+        set.thresholds(Thresholds {
+            low: 7,
+            med: 7,
+            high: 7,
+        });
+        let keys = set
+            .signers_for(&bump)
+            .unwrap()
+            .iter()
+            .map(|key| key.public_key())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![phone.public_key(), laptop.public_key()]);
+    }
+
+    #[test]
+    fn test_signers_for_other_source() {
+        let (set, _, _) = signer_set();
+        let other = Keypair::random().unwrap();
+        let tx = transaction(&other, vec![Operation::new().bump_sequence(2).unwrap()]);
+        assert_eq!(set.required_threshold(&tx), None);
+        assert!(set.signers_for(&tx).unwrap().is_empty());
+    }
+}
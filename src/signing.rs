@@ -1,21 +1,125 @@
 //! This module provides the signing functionality used by the stellar network
+//!
+//! The ed25519 implementation is an [Ed25519Backend]: libsodium natively and `nacl` on
+//! `wasm32` by default, or `ed25519-dalek` on every target with the `ed25519-dalek` feature.
 
 /// Sign the message with the given secrey key
 pub fn sign(data: &[u8], secret_key: &[u8]) -> [u8; 64] {
-    signing_impl::sign(data, secret_key)
+    <DefaultBackend as Ed25519Backend>::sign(data, secret_key)
 }
 /// Verify the signature
 pub fn verify(data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
-    signing_impl::verify(data, signature, public_key)
+    <DefaultBackend as Ed25519Backend>::verify(data, signature, public_key)
 }
 
 /// Generate Keypair
 pub fn generate(secret_key: &[u8]) -> [u8; 32] {
-    signing_impl::generate(secret_key)
+    <DefaultBackend as Ed25519Backend>::generate(secret_key)
+}
+
+/// An implementation of ed25519 signatures.
+pub trait Ed25519Backend {
+    /// Returns the public key of the 32 byte `secret_seed`.
+    fn generate(secret_seed: &[u8]) -> [u8; 32];
+    /// Signs `data` with the 64 byte `secret_key`, the seed followed by the public key.
+    fn sign(data: &[u8], secret_key: &[u8]) -> [u8; 64];
+    /// Checks that `signature` is a signature of `data` by `public_key`.
+    fn verify(data: &[u8], signature: &[u8], public_key: &[u8]) -> bool;
+}
+
+/// The backend of [sign], [verify] and [generate].
+#[cfg(feature = "ed25519-dalek")]
+pub type DefaultBackend = Dalek;
+/// The backend of [sign], [verify] and [generate].
+#[cfg(all(not(feature = "ed25519-dalek"), not(target_arch = "wasm32")))]
+pub type DefaultBackend = Sodium;
+/// The backend of [sign], [verify] and [generate].
+#[cfg(all(
+    not(feature = "ed25519-dalek"),
+    target_arch = "wasm32",
+    target_os = "unknown"
+))]
+pub type DefaultBackend = Nacl;
+
+/// libsodium, the default on native targets.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Sodium;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Ed25519Backend for Sodium {
+    fn generate(secret_seed: &[u8]) -> [u8; 32] {
+        sodium::generate(secret_seed)
+    }
+
+    fn sign(data: &[u8], secret_key: &[u8]) -> [u8; 64] {
+        sodium::sign(data, secret_key)
+    }
+
+    fn verify(data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+        sodium::verify(data, signature, public_key)
+    }
+}
+
+/// The pure Rust `nacl` crate, the default on `wasm32`.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub struct Nacl;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Ed25519Backend for Nacl {
+    fn generate(secret_seed: &[u8]) -> [u8; 32] {
+        nacl_impl::generate(secret_seed)
+    }
+
+    fn sign(data: &[u8], secret_key: &[u8]) -> [u8; 64] {
+        nacl_impl::sign(data, secret_key)
+    }
+
+    fn verify(data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+        nacl_impl::verify(data, signature, public_key)
+    }
+}
+
+/// `ed25519-dalek`, an audited constant-time implementation, with the `ed25519-dalek`
+/// feature.
+#[cfg(feature = "ed25519-dalek")]
+pub struct Dalek;
+
+#[cfg(feature = "ed25519-dalek")]
+impl Ed25519Backend for Dalek {
+    fn generate(secret_seed: &[u8]) -> [u8; 32] {
+        let seed: &[u8; 32] = secret_seed.try_into().expect("secret seed is 32 bytes");
+        ed25519_dalek::SigningKey::from_bytes(seed)
+            .verifying_key()
+            .to_bytes()
+    }
+
+    fn sign(data: &[u8], secret_key: &[u8]) -> [u8; 64] {
+        use ed25519_dalek::Signer;
+
+        let seed: &[u8; 32] = secret_key[..32]
+            .try_into()
+            .expect("secret key starts with the 32 byte seed");
+        ed25519_dalek::SigningKey::from_bytes(seed)
+            .sign(data)
+            .to_bytes()
+    }
+
+    fn verify(data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+        let Ok(public_key) = public_key.try_into() else {
+            return false;
+        };
+        let Ok(public_key) = ed25519_dalek::VerifyingKey::from_bytes(public_key) else {
+            return false;
+        };
+        let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+            return false;
+        };
+        public_key.verify_strict(data, &signature).is_ok()
+    }
 }
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-mod signing_impl {
+mod nacl_impl {
     pub fn generate(secret_key: &[u8]) -> [u8; 32] {
         let secret_key_u8: &[u8; 32] = secret_key.try_into().unwrap();
         let nacl_keys = nacl::sign::generate_keypair(secret_key_u8);
@@ -43,7 +147,7 @@ mod signing_impl {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-mod signing_impl {
+mod sodium {
     use libsodium_sys::crypto_sign_detached;
     use libsodium_sys::crypto_sign_seed_keypair;
 
@@ -142,4 +246,25 @@ mod tests {
         assert!(!verify(b"corrupted", &sig, &public_key));
         assert!(!verify(data, &bad_sig, &public_key));
     }
+
+    #[cfg(all(feature = "ed25519-dalek", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_dalek_matches_sodium() {
+        let seed = [7u8; 32];
+        let public_key = Dalek::generate(&seed);
+        assert_eq!(public_key, Sodium::generate(&seed));
+
+        let mut secret_key = seed.to_vec();
+        secret_key.extend_from_slice(&public_key);
+        let signature = Dalek::sign(b"hello world", &secret_key);
+        assert_eq!(signature, Sodium::sign(b"hello world", &secret_key));
+        assert!(Sodium::verify(b"hello world", &signature, &public_key));
+        assert!(Dalek::verify(b"hello world", &signature, &public_key));
+        assert!(!Dalek::verify(b"corrupted", &signature, &public_key));
+        assert!(!Dalek::verify(
+            b"hello world",
+            &signature[..63],
+            &public_key
+        ));
+    }
 }
@@ -1,3 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::account::{Account, AccountBehavior};
+use crate::address::{Address, AddressTrait};
+use crate::contract::{ContractBehavior, Contracts};
+use crate::hashing::{HashingBehavior, Sha256Hasher};
+use crate::keypair::{Keypair, KeypairBehavior};
+use crate::soroban_data_builder::{SorobanDataBuilder, SorobanDataBuilderBehavior};
+use crate::transaction::{Transaction, TransactionBehavior};
+use crate::transaction_builder::{TransactionBuilder, TransactionBuilderBehavior};
+use crate::xdr;
+use crate::xdr::WriteXdr;
+use rand_core::{OsRng, TryRngCore};
+
+/// Re-exports of everything needed to build, simulate and sign a contract invocation.
+pub mod prelude {
+    pub use super::{
+        assemble_transaction, authorize_invocation, decode_diagnostic_events, decode_host_error,
+        decode_host_error_from_events, events_from_meta, return_value_from_meta, sign_auth_entry,
+        sign_auth_entry_with, ContractEvent, DiagnosticError, FunctionCall, HostErrorKind,
+        InvocationBuilder, Simulation,
+    };
+    pub use crate::account::{Account, AccountBehavior};
+    pub use crate::contract::{ContractBehavior, Contracts};
+    pub use crate::keypair::{Keypair, KeypairBehavior};
+    pub use crate::network::{NetworkPassphrase, Networks};
+    pub use crate::sac::SacClient;
+    pub use crate::transaction::{Transaction, TransactionBehavior};
+    pub use crate::xdr;
+}
+
 pub struct Soroban;
 
 // Define a trait for Soroban behavior
@@ -84,11 +119,724 @@ impl SorobanBehavior for Soroban {
     }
 }
 
+/// The parts of a `simulateTransaction` response needed to submit an invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// Footprint and resources of the invocation.
+    pub transaction_data: xdr::SorobanTransactionData,
+    /// Resource fee to add on top of the inclusion fee, in stroops.
+    pub min_resource_fee: i64,
+    /// Authorization entries the invocation requires, still unsigned.
+    pub auth: Vec<xdr::SorobanAuthorizationEntry>,
+}
+
+/// Signs an authorization entry with `signer`, valid until `valid_until_ledger` (inclusive).
+///
+/// Entries using source-account credentials are returned unchanged, as they are authorized by
+/// the transaction signature. Fails if the entry credentials are for another address.
+pub fn sign_auth_entry(
+    entry: &xdr::SorobanAuthorizationEntry,
+    signer: &Keypair,
+    valid_until_ledger: u32,
+    network: &str,
+) -> Result<xdr::SorobanAuthorizationEntry, Box<dyn Error>> {
+    let public_key = signer.raw_pubkey();
+    sign_auth_entry_with(
+        entry,
+        &public_key,
+        |payload| signer.sign(payload),
+        valid_until_ledger,
+        network,
+    )
+}
+
+/// Signs an authorization entry for the ed25519 `public_key` with the `sign` callback, which
+/// returns the signature of the 32-byte payload it is given, e.g. from a hardware wallet or a
+/// key management service. See [sign_auth_entry].
+pub fn sign_auth_entry_with(
+    entry: &xdr::SorobanAuthorizationEntry,
+    public_key: &[u8; 32],
+    sign: impl FnOnce(&[u8; 32]) -> Result<Vec<u8>, Box<dyn Error>>,
+    valid_until_ledger: u32,
+    network: &str,
+) -> Result<xdr::SorobanAuthorizationEntry, Box<dyn Error>> {
+    let xdr::SorobanCredentials::Address(credentials) = &entry.credentials else {
+        return Ok(entry.clone());
+    };
+    if Address::from_sc_address(&credentials.address)? != Address::account(public_key)? {
+        return Err("signer does not match the authorization entry address".into());
+    }
+
+    let preimage =
+        xdr::HashIdPreimage::SorobanAuthorization(xdr::HashIdPreimageSorobanAuthorization {
+            network_id: xdr::Hash(Sha256Hasher::hash(network)),
+            nonce: credentials.nonce,
+            signature_expiration_ledger: valid_until_ledger,
+            invocation: entry.root_invocation.clone(),
+        });
+    let payload = Sha256Hasher::hash(preimage.to_xdr(xdr::Limits::none())?);
+    let signature = sign(&payload)?;
+
+    let signature = xdr::ScVal::Vec(Some(xdr::ScVec(
+        vec![xdr::ScVal::Map(Some(xdr::ScMap(
+            vec![
+                xdr::ScMapEntry {
+                    key: xdr::ScVal::Symbol(xdr::StringM::from_str("public_key")?.into()),
+                    val: xdr::ScVal::Bytes(public_key.to_vec().try_into()?),
+                },
+                xdr::ScMapEntry {
+                    key: xdr::ScVal::Symbol(xdr::StringM::from_str("signature")?.into()),
+                    val: xdr::ScVal::Bytes(signature.try_into()?),
+                },
+            ]
+            .try_into()?,
+        )))]
+        .try_into()?,
+    )));
+
+    Ok(xdr::SorobanAuthorizationEntry {
+        credentials: xdr::SorobanCredentials::Address(xdr::SorobanAddressCredentials {
+            signature_expiration_ledger: valid_until_ledger,
+            signature,
+            ..credentials.clone()
+        }),
+        root_invocation: entry.root_invocation.clone(),
+    })
+}
+
+/// Builds and signs an authorization entry for `invocation` on behalf of `signer`, valid
+/// until `valid_until_ledger` (inclusive), with a random nonce.
+///
+/// Use it when the authorized invocation is known upfront; entries returned by a simulation
+/// are signed with [sign_auth_entry] instead.
+pub fn authorize_invocation(
+    signer: &Keypair,
+    valid_until_ledger: u32,
+    invocation: xdr::SorobanAuthorizedInvocation,
+    network: &str,
+) -> Result<xdr::SorobanAuthorizationEntry, Box<dyn Error>> {
+    let mut nonce = [0; 8];
+    OsRng
+        .try_fill_bytes(&mut nonce)
+        .map_err(|_| "random nonce generation failed")?;
+    let entry = xdr::SorobanAuthorizationEntry {
+        credentials: xdr::SorobanCredentials::Address(xdr::SorobanAddressCredentials {
+            address: Address::account(&signer.raw_pubkey())?.to_sc_address()?,
+            nonce: i64::from_be_bytes(nonce),
+            signature_expiration_ledger: valid_until_ledger,
+            signature: xdr::ScVal::Void,
+        }),
+        root_invocation: invocation,
+    };
+    sign_auth_entry(&entry, signer, valid_until_ledger, network)
+}
+
+/// Returns a copy of the Soroban transaction `tx` with `simulation` applied, independently of
+/// the RPC client used to simulate it.
+///
+/// The simulated transaction data and resource fee replace any previous ones, keeping the
+/// inclusion fee of `tx`. Simulated authorization entries are only used if the invocation has
+/// none yet, and still need signing, see [sign_auth_entry]. Signatures of `tx` are dropped,
+/// as they do not cover the assembled transaction.
+pub fn assemble_transaction(
+    tx: &Transaction,
+    simulation: &Simulation,
+) -> Result<Transaction, Box<dyn Error>> {
+    if !tx.is_soroban_transaction() {
+        return Err("transaction is not a Soroban transaction".into());
+    }
+    let previous_resource_fee = tx.soroban_data.as_ref().map_or(0, |data| data.resource_fee);
+    let inclusion_fee = i64::from(tx.fee) - previous_resource_fee;
+    let fee = inclusion_fee
+        .checked_add(simulation.min_resource_fee)
+        .and_then(|fee| u32::try_from(fee).ok())
+        .ok_or("assembled fee does not fit in a u32")?;
+
+    let mut assembled = tx.clone();
+    assembled.fee = fee;
+    assembled.soroban_data = Some(xdr::SorobanTransactionData {
+        resource_fee: simulation.min_resource_fee,
+        ..simulation.transaction_data.clone()
+    });
+    if let Some(xdr::OperationBody::InvokeHostFunction(invoke)) = assembled
+        .operations
+        .as_mut()
+        .and_then(|ops| ops.first_mut())
+        .map(|op| &mut op.body)
+    {
+        if invoke.auth.is_empty() {
+            invoke.auth = simulation.auth.clone().try_into()?;
+        }
+    }
+    assembled.clear_signatures();
+    Ok(assembled)
+}
+
+/// Builds a contract invocation from the call to the signed envelope.
+///
+/// ```
+/// use stellar_baselib::soroban::prelude::*;
+///
+/// # fn run(simulate: impl Fn(&Transaction) -> Simulation) -> Result<(), Box<dyn std::error::Error>> {
+/// let signer = Keypair::random()?;
+/// let mut source = Account::new(&signer.public_key(), "1")?;
+///
+/// let mut invocation = InvocationBuilder::new(&mut source, Networks::testnet());
+/// invocation
+///     .contract("CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE")
+///     .method("transfer")
+///     .arg(1_i64)
+///     .auth_signer(&signer)
+///     .valid_until_ledger(1_000);
+///
+/// let simulation = simulate(&invocation.build_for_simulation()?);
+/// let envelope = invocation.apply_simulation(simulation).sign(&[signer])?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct InvocationBuilder<'a> {
+    source: &'a mut Account,
+    network: String,
+    contract: Option<String>,
+    method: Option<String>,
+    args: Vec<xdr::ScVal>,
+    auth_signers: Vec<Keypair>,
+    valid_until_ledger: Option<u32>,
+    base_fee: u32,
+    timeout: i64,
+    simulation: Option<Simulation>,
+}
+
+impl<'a> InvocationBuilder<'a> {
+    pub fn new(source: &'a mut Account, network: &str) -> Self {
+        Self {
+            source,
+            network: network.to_string(),
+            contract: None,
+            method: None,
+            args: Vec::new(),
+            auth_signers: Vec::new(),
+            valid_until_ledger: None,
+            base_fee: 100,
+            timeout: 300,
+            simulation: None,
+        }
+    }
+
+    /// Sets the `C...` address of the contract to invoke.
+    pub fn contract(&mut self, contract_id: &str) -> &mut Self {
+        self.contract = Some(contract_id.to_string());
+        self
+    }
+
+    pub fn method(&mut self, method: &str) -> &mut Self {
+        self.method = Some(method.to_string());
+        self
+    }
+
+    /// Appends an argument, converting native values such as integers and booleans.
+    pub fn arg(&mut self, arg: impl Into<xdr::ScVal>) -> &mut Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args(&mut self, args: impl IntoIterator<Item = xdr::ScVal>) -> &mut Self {
+        self.args.extend(args);
+        self
+    }
+
+    /// Adds a signer for the authorization entries returned by the simulation.
+    pub fn auth_signer(&mut self, signer: &Keypair) -> &mut Self {
+        self.auth_signers.push(signer.clone());
+        self
+    }
+
+    /// Sets the last ledger at which the signed authorization entries are valid.
+    pub fn valid_until_ledger(&mut self, ledger: u32) -> &mut Self {
+        self.valid_until_ledger = Some(ledger);
+        self
+    }
+
+    /// Sets the inclusion fee, in stroops. The resource fee from the simulation is added to it.
+    pub fn base_fee(&mut self, fee: u32) -> &mut Self {
+        self.base_fee = fee;
+        self
+    }
+
+    pub fn timeout(&mut self, timeout_seconds: i64) -> &mut Self {
+        self.timeout = timeout_seconds;
+        self
+    }
+
+    pub fn apply_simulation(&mut self, simulation: Simulation) -> &mut Self {
+        self.simulation = Some(simulation);
+        self
+    }
+
+    /// Returns the InvokeHostFunction operation, with the simulated authorization entries
+    /// signed by the matching auth signers.
+    pub fn operation(&self) -> Result<xdr::Operation, Box<dyn Error>> {
+        let contract = Contracts::new(self.contract.as_deref().ok_or("contract is not set")?)?;
+        let method = self.method.as_deref().ok_or("method is not set")?;
+        xdr::ScSymbol::try_from(method).map_err(|_| "method is not a valid symbol")?;
+        let mut op = contract.call(method, Some(self.args.clone()));
+
+        let Some(simulation) = &self.simulation else {
+            return Ok(op);
+        };
+        let mut auth = Vec::with_capacity(simulation.auth.len());
+        for entry in &simulation.auth {
+            auth.push(self.sign_entry(entry)?);
+        }
+        if let xdr::OperationBody::InvokeHostFunction(invoke) = &mut op.body {
+            invoke.auth = auth.try_into()?;
+        }
+        Ok(op)
+    }
+
+    /// Builds the unsigned transaction to simulate, without incrementing the source sequence.
+    pub fn build_for_simulation(&mut self) -> Result<Transaction, Box<dyn Error>> {
+        let op = self.operation()?;
+        let mut builder = TransactionBuilder::new(self.source, &self.network, None);
+        builder.fee(self.base_fee).add_operation(op);
+        Ok(builder.build_for_simulation())
+    }
+
+    /// Builds the transaction with the applied simulation, signs it and returns its envelope.
+    ///
+    /// The source sequence number is incremented.
+    pub fn sign(
+        &mut self,
+        signers: &[Keypair],
+    ) -> Result<xdr::TransactionEnvelope, Box<dyn Error>> {
+        let simulation = self
+            .simulation
+            .as_ref()
+            .ok_or("simulation has not been applied")?;
+        let transaction_data = xdr::SorobanTransactionData {
+            resource_fee: simulation.min_resource_fee,
+            ..simulation.transaction_data.clone()
+        };
+        let op = self.operation()?;
+
+        let mut builder = TransactionBuilder::new(self.source, &self.network, None);
+        builder
+            .fee(self.base_fee)
+            .add_operation(op)
+            .set_soroban_data(transaction_data)
+            .set_timeout(self.timeout)?;
+        let mut tx = builder.try_build()?;
+        tx.sign(signers)?;
+        tx.to_envelope()
+    }
+
+    fn sign_entry(
+        &self,
+        entry: &xdr::SorobanAuthorizationEntry,
+    ) -> Result<xdr::SorobanAuthorizationEntry, Box<dyn Error>> {
+        let xdr::SorobanCredentials::Address(credentials) = &entry.credentials else {
+            return Ok(entry.clone());
+        };
+        let address = Address::from_sc_address(&credentials.address)?;
+        let signer = self
+            .auth_signers
+            .iter()
+            .find(|signer| Address::account(&signer.raw_pubkey()).is_ok_and(|a| a == address));
+        match signer {
+            Some(signer) => {
+                let valid_until_ledger = self
+                    .valid_until_ledger
+                    .ok_or("valid_until_ledger is not set")?;
+                sign_auth_entry(entry, signer, valid_until_ledger, &self.network)
+            }
+            None => Ok(entry.clone()),
+        }
+    }
+}
+
+/// Wraps `bytes` in an `ScVal::Bytes`, e.g. for a contract argument.
+///
+/// An owned `Vec<u8>` is moved into the value without copying. Fails if `bytes` is longer
+/// than the XDR limit.
+pub fn bytes_val<'a>(bytes: impl Into<Cow<'a, [u8]>>) -> Result<xdr::ScVal, xdr::Error> {
+    Ok(xdr::ScVal::Bytes(xdr::ScBytes(
+        bytes.into().into_owned().try_into()?,
+    )))
+}
+
+/// Returns the keys of `entries`, given with their `live_until_ledger`, that are archived at
+/// `current_ledger` and must be restored before use. Duplicates are dropped.
+pub fn needs_restore(
+    entries: &[(xdr::LedgerKey, u32)],
+    current_ledger: u32,
+) -> Vec<xdr::LedgerKey> {
+    let mut keys: Vec<xdr::LedgerKey> = Vec::new();
+    for (key, live_until_ledger) in entries {
+        if *live_until_ledger < current_ledger && !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+/// Builds the soroban data of a RestoreFootprint transaction for `keys`, which all go in the
+/// read-write footprint. The resources and resource fee are left at 0, to be filled in from
+/// simulating the transaction.
+pub fn restore_footprint_data(keys: Vec<xdr::LedgerKey>) -> xdr::SorobanTransactionData {
+    SorobanDataBuilder::new(None)
+        .set_footprint(None, Some(keys))
+        .build()
+}
+
+/// Rent parameters of the network, from its `StateArchival` and `ContractLedgerCost` settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NetworkConfig {
+    /// Rent fee per 1KB of entry size, in stroops.
+    pub fee_per_rent_1kb: i64,
+    /// Divides the rent fee of persistent entries.
+    pub persistent_rent_rate_denominator: i64,
+    /// Divides the rent fee of temporary entries.
+    pub temporary_rent_rate_denominator: i64,
+}
+
+/// Estimates the rent fee, in stroops, for extending the TTL of an entry of
+/// `entry_size_bytes` by `ttl_extension_ledgers` ledgers, following the rent formula of the
+/// host: `size * fee_per_rent_1kb * ledgers / (1024 * rent_rate_denominator)`, rounded up.
+///
+/// Contract code and instances are persistent entries. This only covers rent; the write fee
+/// of the TTL entry and the inclusion fee are not part of the estimate.
+pub fn estimate_rent_fee(
+    entry_size_bytes: u32,
+    ttl_extension_ledgers: u32,
+    durability: xdr::ContractDataDurability,
+    config: &NetworkConfig,
+) -> i64 {
+    let denominator = match durability {
+        xdr::ContractDataDurability::Persistent => config.persistent_rent_rate_denominator,
+        xdr::ContractDataDurability::Temporary => config.temporary_rent_rate_denominator,
+    };
+    let numerator = i128::from(entry_size_bytes)
+        * i128::from(config.fee_per_rent_1kb)
+        * i128::from(ttl_extension_ledgers);
+    let denominator = 1024 * i128::from(denominator.max(1));
+    i64::try_from((numerator + denominator - 1) / denominator).unwrap_or(i64::MAX)
+}
+
+/// Broad classification of a Soroban host error, telling contract-defined failures apart from
+/// resource exhaustion and authorization problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostErrorKind {
+    /// Error code returned by the contract itself.
+    Contract(u32),
+    /// The CPU or memory budget of the invocation was exhausted.
+    BudgetExceeded,
+    /// A storage access failed, e.g. an entry outside the footprint or an archived entry.
+    Storage(xdr::ScErrorCode),
+    /// Authorization of the invocation failed.
+    Auth(xdr::ScErrorCode),
+    /// Any other host failure.
+    Host(xdr::ScErrorType, xdr::ScErrorCode),
+}
+
+impl HostErrorKind {
+    /// Returns `true` for errors raised by the contract logic, which retrying won't fix.
+    pub fn is_contract_error(&self) -> bool {
+        matches!(self, HostErrorKind::Contract(_))
+    }
+
+    /// Returns `true` for errors caused by insufficient resources, which may succeed after
+    /// re-simulating with a larger footprint or budget.
+    pub fn is_resource_error(&self) -> bool {
+        matches!(
+            self,
+            HostErrorKind::BudgetExceeded
+                | HostErrorKind::Storage(
+                    xdr::ScErrorCode::ExceededLimit | xdr::ScErrorCode::MissingValue
+                )
+        )
+    }
+}
+
+impl fmt::Display for HostErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostErrorKind::Contract(code) => write!(f, "contract error #{}", code),
+            HostErrorKind::BudgetExceeded => write!(f, "resource budget exceeded"),
+            HostErrorKind::Storage(xdr::ScErrorCode::MissingValue) => {
+                write!(f, "storage entry missing or archived")
+            }
+            HostErrorKind::Storage(xdr::ScErrorCode::ExceededLimit) => {
+                write!(f, "storage access exceeded the footprint or limits")
+            }
+            HostErrorKind::Storage(code) => write!(f, "storage error: {}", code.name()),
+            HostErrorKind::Auth(code) => write!(f, "authorization failed: {}", code.name()),
+            HostErrorKind::Host(kind, code) => {
+                write!(f, "host error: {} {}", kind.name(), code.name())
+            }
+        }
+    }
+}
+
+/// Classifies a host error returned by a failed invocation.
+pub fn decode_host_error(error: &xdr::ScError) -> HostErrorKind {
+    match error {
+        xdr::ScError::Contract(code) => HostErrorKind::Contract(*code),
+        xdr::ScError::Budget(xdr::ScErrorCode::ExceededLimit) => HostErrorKind::BudgetExceeded,
+        xdr::ScError::Storage(code) => HostErrorKind::Storage(*code),
+        xdr::ScError::Auth(code) => HostErrorKind::Auth(*code),
+        xdr::ScError::WasmVm(code)
+        | xdr::ScError::Context(code)
+        | xdr::ScError::Object(code)
+        | xdr::ScError::Crypto(code)
+        | xdr::ScError::Events(code)
+        | xdr::ScError::Budget(code)
+        | xdr::ScError::Value(code) => HostErrorKind::Host(error.discriminant(), *code),
+    }
+}
+
+/// Finds the first error event among the diagnostic events of a failed invocation and
+/// classifies it. Returns `None` if no event carries an error.
+pub fn decode_host_error_from_events(events: &[xdr::DiagnosticEvent]) -> Option<HostErrorKind> {
+    events.iter().find_map(|event| {
+        let xdr::ContractEventBody::V0(body) = &event.event.body;
+        match body.topics.as_slice() {
+            [xdr::ScVal::Symbol(topic), xdr::ScVal::Error(error), ..]
+                if topic.as_vec().as_slice() == b"error" =>
+            {
+                Some(decode_host_error(error))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// A contract function call recorded in diagnostic events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCall {
+    /// `C...` address of the called contract.
+    pub contract_id: String,
+    pub function: String,
+    pub args: Vec<xdr::ScVal>,
+}
+
+/// The error that made an invocation fail, as reported by its diagnostic events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticError {
+    pub kind: HostErrorKind,
+    /// `C...` address of the contract the error was raised in, if any.
+    pub contract_id: Option<String>,
+    /// Message of the error event, e.g. the message of a contract panic.
+    pub message: Option<String>,
+    /// Values attached to the error event besides the message.
+    pub data: Vec<xdr::ScVal>,
+    /// Calls in progress when the error was raised, outermost first.
+    pub call_stack: Vec<FunctionCall>,
+}
+
+impl DiagnosticError {
+    /// Returns the innermost call in progress, i.e. the function that failed.
+    pub fn failed_call(&self) -> Option<&FunctionCall> {
+        self.call_stack.last()
+    }
+}
+
+impl fmt::Display for DiagnosticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(call) = self.failed_call() {
+            write!(f, " in {} of {}", call.function, call.contract_id)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for DiagnosticError {}
+
+/// Decodes the diagnostic events of a failed simulation or invocation into the first error
+/// they report, with its message and the contract calls that led to it. Returns `None` if no
+/// event carries an error.
+pub fn decode_diagnostic_events(events: &[xdr::DiagnosticEvent]) -> Option<DiagnosticError> {
+    let mut call_stack: Vec<FunctionCall> = Vec::new();
+    for event in events {
+        let xdr::ContractEventBody::V0(body) = &event.event.body;
+        let values = |data: &xdr::ScVal| match data {
+            xdr::ScVal::Void => Vec::new(),
+            xdr::ScVal::Vec(Some(values)) => values.to_vec(),
+            value => vec![value.clone()],
+        };
+        match body.topics.as_slice() {
+            [xdr::ScVal::Symbol(topic), xdr::ScVal::Bytes(id), xdr::ScVal::Symbol(function), ..]
+                if topic.as_vec().as_slice() == b"fn_call" =>
+            {
+                let Ok(id) = <[u8; 32]>::try_from(id.as_slice()) else {
+                    continue;
+                };
+                call_stack.push(FunctionCall {
+                    contract_id: stellar_strkey::Contract(id).to_string(),
+                    function: function.to_utf8_string_lossy(),
+                    args: values(&body.data),
+                });
+            }
+            [xdr::ScVal::Symbol(topic), ..] if topic.as_vec().as_slice() == b"fn_return" => {
+                call_stack.pop();
+            }
+            [xdr::ScVal::Symbol(topic), xdr::ScVal::Error(error), ..]
+                if topic.as_vec().as_slice() == b"error" =>
+            {
+                let mut data = values(&body.data);
+                let message = match data.first() {
+                    Some(xdr::ScVal::String(message)) => {
+                        let message = message.to_utf8_string_lossy();
+                        data.remove(0);
+                        Some(message)
+                    }
+                    _ => None,
+                };
+                return Some(DiagnosticError {
+                    kind: decode_host_error(error),
+                    contract_id: event
+                        .event
+                        .contract_id
+                        .as_ref()
+                        .map(|id| stellar_strkey::Contract(id.0 .0).to_string()),
+                    message,
+                    data,
+                    call_stack,
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the value returned by the contract function a transaction invoked, from the
+/// `resultMetaXdr` of a `getTransaction` response.
+///
+/// Returns `None` for meta of transactions that invoked no contract function, including
+/// versions older than V3 which predate Soroban.
+pub fn return_value_from_meta(meta: &xdr::TransactionMeta) -> Option<xdr::ScVal> {
+    match meta {
+        xdr::TransactionMeta::V3(meta) => meta
+            .soroban_meta
+            .as_ref()
+            .map(|soroban_meta| soroban_meta.return_value.clone()),
+        xdr::TransactionMeta::V4(meta) => meta
+            .soroban_meta
+            .as_ref()
+            .and_then(|soroban_meta| soroban_meta.return_value.clone()),
+        _ => None,
+    }
+}
+
+/// An event emitted by a contract during a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    /// `C...` address of the emitting contract, if any.
+    pub contract_id: Option<String>,
+    pub topics: Vec<xdr::ScVal>,
+    pub data: xdr::ScVal,
+}
+
+impl From<&xdr::ContractEvent> for ContractEvent {
+    fn from(event: &xdr::ContractEvent) -> Self {
+        let xdr::ContractEventBody::V0(body) = &event.body;
+        ContractEvent {
+            contract_id: event
+                .contract_id
+                .as_ref()
+                .map(|id| stellar_strkey::Contract(id.0 .0).to_string()),
+            topics: body.topics.to_vec(),
+            data: body.data.clone(),
+        }
+    }
+}
+
+/// Returns the events emitted by the operations of a transaction, in order, from its meta.
+///
+/// Diagnostic events and, for V4 meta, the fee events of the transaction itself are not
+/// included.
+pub fn events_from_meta(meta: &xdr::TransactionMeta) -> Vec<ContractEvent> {
+    match meta {
+        xdr::TransactionMeta::V3(meta) => meta
+            .soroban_meta
+            .iter()
+            .flat_map(|soroban_meta| soroban_meta.events.iter())
+            .map(ContractEvent::from)
+            .collect(),
+        xdr::TransactionMeta::V4(meta) => meta
+            .operations
+            .iter()
+            .flat_map(|operation| operation.events.iter())
+            .map(ContractEvent::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the addresses that must sign the authorization entries of an InvokeHostFunction
+/// operation, without duplicates and in the order they first appear.
+///
+/// Entries using source-account credentials are satisfied by the source signature: they add
+/// the operation source if one is set, and nothing otherwise since the transaction source
+/// signs anyway. Any other operation requires no authorization and yields an empty list.
+pub fn required_signers(op: &xdr::Operation) -> Vec<Address> {
+    let xdr::OperationBody::InvokeHostFunction(invoke) = &op.body else {
+        return Vec::new();
+    };
+
+    let mut signers: Vec<Address> = Vec::new();
+    for entry in invoke.auth.iter() {
+        let signer = match &entry.credentials {
+            xdr::SorobanCredentials::Address(credentials) => {
+                Address::from_sc_address(&credentials.address).ok()
+            }
+            xdr::SorobanCredentials::SourceAccount => match &op.source_account {
+                Some(xdr::MuxedAccount::Ed25519(xdr::Uint256(key))) => Address::account(key).ok(),
+                Some(xdr::MuxedAccount::MuxedEd25519(xdr::MuxedAccountMed25519 {
+                    id,
+                    ed25519: xdr::Uint256(key),
+                })) => {
+                    let mut payload = key.to_vec();
+                    payload.extend_from_slice(&id.to_be_bytes());
+                    Address::muxed_account(&payload).ok()
+                }
+                None => None,
+            },
+        };
+        if let Some(signer) = signer {
+            if !signers.contains(&signer) {
+                signers.push(signer);
+            }
+        }
+    }
+    signers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::network::{NetworkPassphrase, Networks};
     use std::panic;
 
+    #[test]
+    fn wraps_bytes_without_copy() {
+        let bytes = vec![1, 2, 3];
+        let ptr = bytes.as_ptr();
+        let xdr::ScVal::Bytes(val) = bytes_val(bytes).unwrap() else {
+            panic!("expected bytes");
+        };
+        assert_eq!(val.as_slice().as_ptr(), ptr);
+        assert_eq!(
+            bytes_val(&[4, 5][..]).unwrap(),
+            xdr::ScVal::Bytes(vec![4, 5].try_into().unwrap())
+        );
+    }
+
     #[test]
     fn test_format_token_amount_success_cases() {
         let test_cases = [
@@ -177,4 +925,622 @@ mod tests {
             }
         }
     }
+
+    fn auth_entry(credentials: xdr::SorobanCredentials) -> xdr::SorobanAuthorizationEntry {
+        xdr::SorobanAuthorizationEntry {
+            credentials,
+            root_invocation: xdr::SorobanAuthorizedInvocation {
+                function: xdr::SorobanAuthorizedFunction::ContractFn(xdr::InvokeContractArgs {
+                    contract_address: xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash([1; 32]))),
+                    function_name: "transfer".try_into().unwrap(),
+                    args: Default::default(),
+                }),
+                sub_invocations: Default::default(),
+            },
+        }
+    }
+
+    fn address_credentials(address: xdr::ScAddress) -> xdr::SorobanCredentials {
+        xdr::SorobanCredentials::Address(xdr::SorobanAddressCredentials {
+            address,
+            nonce: 0,
+            signature_expiration_ledger: 0,
+            signature: xdr::ScVal::Void,
+        })
+    }
+
+    #[test]
+    fn test_authorize_invocation() {
+        let signer = Keypair::random().unwrap();
+        let invocation = auth_entry(xdr::SorobanCredentials::SourceAccount).root_invocation;
+
+        let entry =
+            authorize_invocation(&signer, 1_000, invocation.clone(), Networks::testnet()).unwrap();
+        let xdr::SorobanCredentials::Address(credentials) = &entry.credentials else {
+            panic!("expected address credentials");
+        };
+        assert_eq!(entry.root_invocation, invocation);
+        assert_eq!(credentials.signature_expiration_ledger, 1_000);
+        assert_eq!(
+            Address::from_sc_address(&credentials.address).unwrap(),
+            Address::account(&signer.raw_pubkey()).unwrap()
+        );
+
+        let unsigned = xdr::SorobanAuthorizationEntry {
+            credentials: xdr::SorobanCredentials::Address(xdr::SorobanAddressCredentials {
+                signature: xdr::ScVal::Void,
+                ..credentials.clone()
+            }),
+            ..entry.clone()
+        };
+        assert_eq!(
+            sign_auth_entry(&unsigned, &signer, 1_000, Networks::testnet()).unwrap(),
+            entry
+        );
+        assert_ne!(
+            authorize_invocation(&signer, 1_000, invocation, Networks::testnet()).unwrap(),
+            entry
+        );
+
+        // An external signer only sees the payload.
+        let public_key = signer.raw_pubkey();
+        let mut signed_payload = None;
+        let external = sign_auth_entry_with(
+            &unsigned,
+            &public_key,
+            |payload| {
+                signed_payload = Some(*payload);
+                signer.sign(payload)
+            },
+            1_000,
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(external, entry);
+        assert!(signed_payload.is_some());
+
+        let failing = sign_auth_entry_with(
+            &unsigned,
+            &public_key,
+            |_| Err("device rejected the request".into()),
+            1_000,
+            Networks::testnet(),
+        );
+        assert_eq!(
+            failing.unwrap_err().to_string(),
+            "device rejected the request"
+        );
+    }
+
+    #[test]
+    fn test_required_signers() {
+        let account = xdr::ScAddress::Account(xdr::AccountId(
+            xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256([2; 32])),
+        ));
+        let contract = xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash([3; 32])));
+        let op = xdr::Operation {
+            source_account: Some(xdr::MuxedAccount::Ed25519(xdr::Uint256([4; 32]))),
+            body: xdr::OperationBody::InvokeHostFunction(xdr::InvokeHostFunctionOp {
+                host_function: xdr::HostFunction::UploadContractWasm(Default::default()),
+                auth: vec![
+                    auth_entry(address_credentials(account.clone())),
+                    auth_entry(xdr::SorobanCredentials::SourceAccount),
+                    auth_entry(address_credentials(contract.clone())),
+                    auth_entry(address_credentials(account.clone())),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+        };
+
+        let signers = required_signers(&op);
+        assert_eq!(
+            signers,
+            vec![
+                Address::from_sc_address(&account).unwrap(),
+                Address::account(&[4; 32]).unwrap(),
+                Address::from_sc_address(&contract).unwrap(),
+            ]
+        );
+
+        let op = xdr::Operation {
+            source_account: None,
+            ..op
+        };
+        assert_eq!(required_signers(&op).len(), 2);
+    }
+
+    #[test]
+    fn test_required_signers_non_soroban_op() {
+        let op = xdr::Operation {
+            source_account: None,
+            body: xdr::OperationBody::BumpSequence(xdr::BumpSequenceOp {
+                bump_to: xdr::SequenceNumber(1),
+            }),
+        };
+        assert!(required_signers(&op).is_empty());
+    }
+
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    fn simulation_for(invocation: &xdr::Operation, signer: &Keypair) -> Simulation {
+        let xdr::OperationBody::InvokeHostFunction(xdr::InvokeHostFunctionOp {
+            host_function: xdr::HostFunction::InvokeContract(args),
+            ..
+        }) = &invocation.body
+        else {
+            panic!("not an invocation");
+        };
+        let root_invocation = xdr::SorobanAuthorizedInvocation {
+            function: xdr::SorobanAuthorizedFunction::ContractFn(args.clone()),
+            sub_invocations: Default::default(),
+        };
+        Simulation {
+            transaction_data: xdr::SorobanTransactionData {
+                ext: xdr::SorobanTransactionDataExt::V0,
+                resources: xdr::SorobanResources {
+                    footprint: xdr::LedgerFootprint {
+                        read_only: Default::default(),
+                        read_write: Default::default(),
+                    },
+                    instructions: 1,
+                    disk_read_bytes: 2,
+                    write_bytes: 3,
+                },
+                resource_fee: 5000,
+            },
+            min_resource_fee: 5000,
+            auth: vec![
+                xdr::SorobanAuthorizationEntry {
+                    credentials: xdr::SorobanCredentials::Address(xdr::SorobanAddressCredentials {
+                        address: Address::account(&signer.raw_pubkey())
+                            .unwrap()
+                            .to_sc_address()
+                            .unwrap(),
+                        nonce: 7,
+                        signature_expiration_ledger: 0,
+                        signature: xdr::ScVal::Void,
+                    }),
+                    root_invocation: root_invocation.clone(),
+                },
+                xdr::SorobanAuthorizationEntry {
+                    credentials: xdr::SorobanCredentials::SourceAccount,
+                    root_invocation,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_assemble_transaction() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "10").unwrap();
+        let mut invocation = InvocationBuilder::new(&mut source, Networks::testnet());
+        invocation.contract(CONTRACT).method("transfer");
+        let mut tx = invocation.build_for_simulation().unwrap();
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
+        let simulation = simulation_for(&tx.operations.as_ref().unwrap()[0], &signer);
+
+        let assembled = assemble_transaction(&tx, &simulation).unwrap();
+        assert_eq!(assembled.fee, 5100);
+        assert_eq!(
+            assembled.soroban_data,
+            Some(simulation.transaction_data.clone())
+        );
+        assert!(assembled.signatures.is_empty());
+        assert_ne!(assembled.hash(), tx.hash());
+        let xdr::OperationBody::InvokeHostFunction(invoke) =
+            &assembled.operations.as_ref().unwrap()[0].body
+        else {
+            panic!("unexpected operation");
+        };
+        assert_eq!(invoke.auth.to_vec(), simulation.auth);
+
+        let resimulated = Simulation {
+            min_resource_fee: 7000,
+            auth: Vec::new(),
+            ..simulation.clone()
+        };
+        let reassembled = assemble_transaction(&assembled, &resimulated).unwrap();
+        assert_eq!(reassembled.fee, 7100);
+        assert_eq!(reassembled.soroban_data.unwrap().resource_fee, 7000);
+        assert_eq!(reassembled.operations, assembled.operations);
+
+        tx.operations = Some(vec![crate::operation::Operation::new()
+            .bump_sequence(1)
+            .unwrap()]);
+        assert!(assemble_transaction(&tx, &simulation).is_err());
+    }
+
+    #[test]
+    fn test_invocation_builder() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "10").unwrap();
+
+        let mut invocation = InvocationBuilder::new(&mut source, Networks::testnet());
+        invocation
+            .contract(CONTRACT)
+            .method("transfer")
+            .arg(1_i64)
+            .arg(true)
+            .auth_signer(&signer)
+            .valid_until_ledger(1000);
+
+        let tx = invocation.build_for_simulation().unwrap();
+        assert_eq!(tx.sequence.as_deref(), Some("11"));
+        let op = tx.operations.unwrap().remove(0);
+        let simulation = simulation_for(&op, &signer);
+
+        let envelope = invocation
+            .apply_simulation(simulation.clone())
+            .sign(std::slice::from_ref(&signer))
+            .unwrap();
+        assert_eq!(source.sequence_number(), "11");
+
+        let xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope { tx, signatures }) = &envelope
+        else {
+            panic!("unexpected envelope type");
+        };
+        assert_eq!(tx.fee, 5100);
+        assert_eq!(
+            tx.ext,
+            xdr::TransactionExt::V1(simulation.transaction_data.clone())
+        );
+        assert_eq!(signatures.len(), 1);
+        assert!(crate::transaction::verify_envelope_signature(
+            &envelope.to_xdr_base64(xdr::Limits::none()).unwrap(),
+            Networks::testnet(),
+            &signer.public_key(),
+        )
+        .unwrap());
+
+        let xdr::OperationBody::InvokeHostFunction(invoke) = &tx.operations[0].body else {
+            panic!("unexpected operation");
+        };
+        let xdr::InvokeContractArgs { args, .. } = match &invoke.host_function {
+            xdr::HostFunction::InvokeContract(args) => args,
+            _ => panic!("unexpected host function"),
+        };
+        assert_eq!(
+            args.to_vec(),
+            vec![xdr::ScVal::I64(1), xdr::ScVal::Bool(true)]
+        );
+        assert_eq!(invoke.auth.len(), 2);
+        assert_eq!(invoke.auth[1], simulation.auth[1]);
+        assert_eq!(
+            invoke.auth[0],
+            sign_auth_entry(&simulation.auth[0], &signer, 1000, Networks::testnet()).unwrap()
+        );
+
+        let xdr::SorobanCredentials::Address(credentials) = &invoke.auth[0].credentials else {
+            panic!("unexpected credentials");
+        };
+        assert_eq!(credentials.signature_expiration_ledger, 1000);
+        let preimage =
+            xdr::HashIdPreimage::SorobanAuthorization(xdr::HashIdPreimageSorobanAuthorization {
+                network_id: xdr::Hash(Sha256Hasher::hash(Networks::testnet())),
+                nonce: 7,
+                signature_expiration_ledger: 1000,
+                invocation: invoke.auth[0].root_invocation.clone(),
+            });
+        let payload = Sha256Hasher::hash(preimage.to_xdr(xdr::Limits::none()).unwrap());
+        let xdr::ScVal::Vec(Some(sigs)) = &credentials.signature else {
+            panic!("unexpected signature");
+        };
+        let xdr::ScVal::Map(Some(map)) = &sigs[0] else {
+            panic!("unexpected signature");
+        };
+        let xdr::ScVal::Bytes(sig) = &map[1].val else {
+            panic!("unexpected signature");
+        };
+        assert!(signer.verify(&payload, sig));
+    }
+
+    #[test]
+    fn test_invocation_builder_errors() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "10").unwrap();
+
+        let mut invocation = InvocationBuilder::new(&mut source, Networks::testnet());
+        assert!(invocation.operation().is_err());
+        invocation.contract(CONTRACT).method("transfer");
+        assert!(invocation.sign(&[]).is_err());
+
+        let op = invocation.operation().unwrap();
+        invocation
+            .auth_signer(&signer)
+            .apply_simulation(simulation_for(&op, &signer));
+        assert_eq!(
+            invocation.sign(&[]).unwrap_err().to_string(),
+            "valid_until_ledger is not set"
+        );
+        assert_eq!(source.sequence_number(), "10");
+
+        let other = Keypair::random().unwrap();
+        let entry = &simulation_for(&op, &signer).auth[0];
+        assert!(sign_auth_entry(entry, &other, 1000, Networks::testnet()).is_err());
+    }
+
+    #[test]
+    fn test_decode_host_error() {
+        let cases = [
+            (xdr::ScError::Contract(3), HostErrorKind::Contract(3)),
+            (
+                xdr::ScError::Budget(xdr::ScErrorCode::ExceededLimit),
+                HostErrorKind::BudgetExceeded,
+            ),
+            (
+                xdr::ScError::Storage(xdr::ScErrorCode::MissingValue),
+                HostErrorKind::Storage(xdr::ScErrorCode::MissingValue),
+            ),
+            (
+                xdr::ScError::Auth(xdr::ScErrorCode::InvalidAction),
+                HostErrorKind::Auth(xdr::ScErrorCode::InvalidAction),
+            ),
+            (
+                xdr::ScError::WasmVm(xdr::ScErrorCode::InvalidAction),
+                HostErrorKind::Host(xdr::ScErrorType::WasmVm, xdr::ScErrorCode::InvalidAction),
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(decode_host_error(&error), expected);
+        }
+
+        assert!(HostErrorKind::Contract(3).is_contract_error());
+        assert!(!HostErrorKind::Contract(3).is_resource_error());
+        assert!(HostErrorKind::BudgetExceeded.is_resource_error());
+        assert!(!HostErrorKind::Auth(xdr::ScErrorCode::InvalidAction).is_resource_error());
+        assert_eq!(HostErrorKind::Contract(3).to_string(), "contract error #3");
+        assert_eq!(
+            HostErrorKind::BudgetExceeded.to_string(),
+            "resource budget exceeded"
+        );
+    }
+
+    #[test]
+    fn test_decode_host_error_from_events() {
+        let event = |topics: Vec<xdr::ScVal>| xdr::DiagnosticEvent {
+            in_successful_contract_call: false,
+            event: xdr::ContractEvent {
+                ext: xdr::ExtensionPoint::V0,
+                contract_id: None,
+                type_: xdr::ContractEventType::Diagnostic,
+                body: xdr::ContractEventBody::V0(xdr::ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data: xdr::ScVal::Void,
+                }),
+            },
+        };
+        let symbol = |s: &str| xdr::ScVal::Symbol(xdr::StringM::from_str(s).unwrap().into());
+
+        let events = [
+            event(vec![symbol("fn_call")]),
+            event(vec![
+                symbol("error"),
+                xdr::ScVal::Error(xdr::ScError::Contract(7)),
+            ]),
+            event(vec![
+                symbol("error"),
+                xdr::ScVal::Error(xdr::ScError::WasmVm(xdr::ScErrorCode::InvalidAction)),
+            ]),
+        ];
+        assert_eq!(
+            decode_host_error_from_events(&events),
+            Some(HostErrorKind::Contract(7))
+        );
+        assert_eq!(decode_host_error_from_events(&events[..1]), None);
+    }
+
+    #[test]
+    fn test_decode_diagnostic_events() {
+        let contract = |n: u8| xdr::ContractId(xdr::Hash([n; 32]));
+        let event =
+            |id: Option<u8>, topics: Vec<xdr::ScVal>, data: xdr::ScVal| xdr::DiagnosticEvent {
+                in_successful_contract_call: false,
+                event: xdr::ContractEvent {
+                    ext: xdr::ExtensionPoint::V0,
+                    contract_id: id.map(contract),
+                    type_: xdr::ContractEventType::Diagnostic,
+                    body: xdr::ContractEventBody::V0(xdr::ContractEventV0 {
+                        topics: topics.try_into().unwrap(),
+                        data,
+                    }),
+                },
+            };
+        let symbol = |s: &str| xdr::ScVal::Symbol(s.try_into().unwrap());
+        let id_bytes = |n: u8| xdr::ScVal::Bytes(vec![n; 32].try_into().unwrap());
+        let string = |s: &str| xdr::ScVal::String(xdr::ScString(s.try_into().unwrap()));
+
+        let events = [
+            event(
+                None,
+                vec![symbol("fn_call"), id_bytes(1), symbol("swap")],
+                xdr::ScVal::Vec(Some(vec![xdr::ScVal::U32(5)].try_into().unwrap())),
+            ),
+            event(
+                Some(1),
+                vec![symbol("fn_call"), id_bytes(2), symbol("balance")],
+                xdr::ScVal::Void,
+            ),
+            event(
+                Some(2),
+                vec![symbol("fn_return"), symbol("balance")],
+                xdr::ScVal::I32(0),
+            ),
+            event(
+                Some(1),
+                vec![symbol("fn_call"), id_bytes(3), symbol("transfer")],
+                xdr::ScVal::I32(9),
+            ),
+            event(
+                Some(3),
+                vec![
+                    symbol("error"),
+                    xdr::ScVal::Error(xdr::ScError::Contract(10)),
+                ],
+                xdr::ScVal::Vec(Some(
+                    vec![string("balance is not sufficient"), xdr::ScVal::I32(9)]
+                        .try_into()
+                        .unwrap(),
+                )),
+            ),
+            event(
+                Some(1),
+                vec![
+                    symbol("error"),
+                    xdr::ScVal::Error(xdr::ScError::Contract(1)),
+                ],
+                string("escalating error"),
+            ),
+        ];
+
+        let error = decode_diagnostic_events(&events).unwrap();
+        let contract_id = |n: u8| stellar_strkey::Contract([n; 32]).to_string();
+        assert_eq!(error.kind, HostErrorKind::Contract(10));
+        assert_eq!(error.contract_id, Some(contract_id(3)));
+        assert_eq!(error.message.as_deref(), Some("balance is not sufficient"));
+        assert_eq!(error.data, vec![xdr::ScVal::I32(9)]);
+        assert_eq!(
+            error.call_stack,
+            vec![
+                FunctionCall {
+                    contract_id: contract_id(1),
+                    function: "swap".into(),
+                    args: vec![xdr::ScVal::U32(5)],
+                },
+                FunctionCall {
+                    contract_id: contract_id(3),
+                    function: "transfer".into(),
+                    args: vec![xdr::ScVal::I32(9)],
+                },
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "contract error #10 in transfer of {}: balance is not sufficient",
+                contract_id(3)
+            )
+        );
+        assert_eq!(decode_diagnostic_events(&events[..4]), None);
+    }
+
+    #[test]
+    fn test_return_value_from_meta() {
+        let v3 = xdr::TransactionMeta::V3(xdr::TransactionMetaV3 {
+            soroban_meta: Some(xdr::SorobanTransactionMeta {
+                return_value: xdr::ScVal::U32(7),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        assert_eq!(return_value_from_meta(&v3), Some(xdr::ScVal::U32(7)));
+
+        let v4 = xdr::TransactionMeta::V4(xdr::TransactionMetaV4 {
+            soroban_meta: Some(xdr::SorobanTransactionMetaV2 {
+                ext: xdr::SorobanTransactionMetaExt::V0,
+                return_value: Some(xdr::ScVal::Bool(true)),
+            }),
+            ..Default::default()
+        });
+        assert_eq!(return_value_from_meta(&v4), Some(xdr::ScVal::Bool(true)));
+
+        let classic = xdr::TransactionMeta::V3(Default::default());
+        assert_eq!(return_value_from_meta(&classic), None);
+        assert_eq!(
+            return_value_from_meta(&xdr::TransactionMeta::V0(Default::default())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_events_from_meta() {
+        let event = |n: u32| xdr::ContractEvent {
+            ext: xdr::ExtensionPoint::V0,
+            contract_id: Some(xdr::ContractId(xdr::Hash([0; 32]))),
+            type_: xdr::ContractEventType::Contract,
+            body: xdr::ContractEventBody::V0(xdr::ContractEventV0 {
+                topics: vec![xdr::ScVal::Symbol("transfer".try_into().unwrap())]
+                    .try_into()
+                    .unwrap(),
+                data: xdr::ScVal::U32(n),
+            }),
+        };
+        let expected = |n: u32| ContractEvent {
+            contract_id: Some("CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4".into()),
+            topics: vec![xdr::ScVal::Symbol("transfer".try_into().unwrap())],
+            data: xdr::ScVal::U32(n),
+        };
+
+        let v3 = xdr::TransactionMeta::V3(xdr::TransactionMetaV3 {
+            soroban_meta: Some(xdr::SorobanTransactionMeta {
+                events: vec![event(1), event(2)].try_into().unwrap(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        assert_eq!(events_from_meta(&v3), vec![expected(1), expected(2)]);
+
+        let operation = |events: Vec<xdr::ContractEvent>| xdr::OperationMetaV2 {
+            ext: xdr::ExtensionPoint::V0,
+            changes: Default::default(),
+            events: events.try_into().unwrap(),
+        };
+        let v4 = xdr::TransactionMeta::V4(xdr::TransactionMetaV4 {
+            operations: vec![operation(vec![event(3)]), operation(vec![event(4)])]
+                .try_into()
+                .unwrap(),
+            events: vec![xdr::TransactionEvent {
+                stage: xdr::TransactionEventStage::BeforeAllTxs,
+                event: event(5),
+            }]
+            .try_into()
+            .unwrap(),
+            ..Default::default()
+        });
+        assert_eq!(events_from_meta(&v4), vec![expected(3), expected(4)]);
+        assert!(events_from_meta(&xdr::TransactionMeta::V3(Default::default())).is_empty());
+    }
+
+    #[test]
+    fn test_needs_restore() {
+        let key = |n: u8| {
+            xdr::LedgerKey::ContractCode(xdr::LedgerKeyContractCode {
+                hash: xdr::Hash([n; 32]),
+            })
+        };
+        let entries = [(key(1), 99), (key(2), 100), (key(3), 50), (key(1), 99)];
+
+        let archived = needs_restore(&entries, 100);
+        assert_eq!(archived, vec![key(1), key(3)]);
+        assert!(needs_restore(&entries, 50).is_empty());
+
+        let data = restore_footprint_data(archived.clone());
+        assert_eq!(data.resources.footprint.read_write.to_vec(), archived);
+        assert!(data.resources.footprint.read_only.is_empty());
+    }
+
+    #[test]
+    fn test_estimate_rent_fee() {
+        let config = NetworkConfig {
+            fee_per_rent_1kb: 1000,
+            persistent_rent_rate_denominator: 1000,
+            temporary_rent_rate_denominator: 2000,
+        };
+        let persistent = xdr::ContractDataDurability::Persistent;
+        let temporary = xdr::ContractDataDurability::Temporary;
+
+        assert_eq!(estimate_rent_fee(1024, 1000, persistent, &config), 1000);
+        assert_eq!(estimate_rent_fee(1024, 1000, temporary, &config), 500);
+        // Rounded up.
+        assert_eq!(estimate_rent_fee(1, 1, persistent, &config), 1);
+        assert_eq!(estimate_rent_fee(1024, 0, persistent, &config), 0);
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["fee_per_rent_1kb"], 1000);
+        assert_eq!(
+            serde_json::from_value::<NetworkConfig>(json).unwrap(),
+            config
+        );
+    }
 }
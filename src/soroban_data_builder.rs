@@ -14,6 +14,8 @@ pub enum Either<L, R> {
 }
 // Define a trait for SorobanDataBuilder behavior
 pub trait SorobanDataBuilderBehavior {
+    /// Adds keys to the footprint, skipping those it already has, like
+    /// [set_footprint](Self::set_footprint).
     fn append_footprint(
         &mut self,
         read_only: Vec<xdr::LedgerKey>,
@@ -22,12 +24,28 @@ pub trait SorobanDataBuilderBehavior {
     fn set_resources(&mut self, instructions: u32, read_bytes: u32, write_bytes: u32) -> &mut Self;
     fn new(soroban_data: Option<Either<String, xdr::SorobanTransactionData>>) -> Self;
     fn from_xdr(data: Either<String, Vec<u8>>) -> xdr::SorobanTransactionData;
+    /// Sets the footprint, keeping the current keys of a set passed as `None`.
+    ///
+    /// Duplicate keys are dropped and a key is never in both sets: a read-write key is removed
+    /// from the read-only set.
     fn set_footprint(
         &mut self,
         read_only: Option<Vec<xdr::LedgerKey>>,
         read_write: Option<Vec<xdr::LedgerKey>>,
     ) -> &mut Self;
+    #[deprecated(note = "the fee covers all resources, use `set_resource_fee`")]
     fn set_refundable_fee(&mut self, fee: i64) -> &mut Self;
+    /// Sets the resource fee, in stroops, paid on top of the inclusion fee.
+    fn set_resource_fee(&mut self, fee: i64) -> &mut Self;
+    /// Marks the read-write footprint entries at `indices` as archived, so protocol 23 and
+    /// later restore them before the invocation. An empty list removes the extension.
+    ///
+    /// Fails with [xdr::Error::Invalid] if an index is not one of the read-write footprint,
+    /// which must be set first.
+    fn set_archived_entries(&mut self, indices: Vec<u32>) -> Result<&mut Self, xdr::Error>;
+    /// Returns the indices of the archived read-write footprint entries.
+    fn get_archived_entries(&self) -> Vec<u32>;
+    fn get_ext(&self) -> &xdr::SorobanTransactionDataExt;
     fn set_read_only(&mut self, read_only: Vec<xdr::LedgerKey>) -> &mut Self;
     fn set_read_write(&mut self, read_write: Vec<xdr::LedgerKey>) -> &mut Self;
     fn get_read_only(&self) -> &Vec<xdr::LedgerKey>;
@@ -97,15 +115,12 @@ impl SorobanDataBuilderBehavior for SorobanDataBuilder {
         read_only: Vec<xdr::LedgerKey>,
         read_write: Vec<xdr::LedgerKey>,
     ) -> &mut Self {
-        // Get current footprints
         let mut current_read_only = self.get_read_only().clone();
         let mut current_read_write = self.get_read_write();
 
-        // Append new keys
         current_read_only.extend(read_only);
         current_read_write.extend(read_write);
 
-        // Set the combined footprints
         self.set_footprint(Some(current_read_only), Some(current_read_write))
     }
 
@@ -114,20 +129,63 @@ impl SorobanDataBuilderBehavior for SorobanDataBuilder {
         read_only: Option<Vec<xdr::LedgerKey>>,
         read_write: Option<Vec<xdr::LedgerKey>>,
     ) -> &mut Self {
-        if let Some(ros) = read_only {
-            self.set_read_only(ros);
+        if read_only.is_none() && read_write.is_none() {
+            return self;
         }
-        if let Some(rws) = read_write {
-            self.set_read_write(rws);
+        let mut rws: Vec<xdr::LedgerKey> = Vec::new();
+        for key in read_write.unwrap_or_else(|| self.get_read_write()) {
+            if !rws.contains(&key) {
+                rws.push(key);
+            }
         }
-        self
+        let mut ros: Vec<xdr::LedgerKey> = Vec::new();
+        for key in read_only.unwrap_or_else(|| self.get_read_only().clone()) {
+            if !ros.contains(&key) && !rws.contains(&key) {
+                ros.push(key);
+            }
+        }
+        self.set_read_only(ros);
+        self.set_read_write(rws)
     }
 
     fn set_refundable_fee(&mut self, fee: i64) -> &mut Self {
+        self.set_resource_fee(fee)
+    }
+
+    fn set_resource_fee(&mut self, fee: i64) -> &mut Self {
         self.data.resource_fee = fee;
         self
     }
 
+    fn set_archived_entries(&mut self, indices: Vec<u32>) -> Result<&mut Self, xdr::Error> {
+        let read_write = self.data.resources.footprint.read_write.len();
+        if indices
+            .iter()
+            .any(|&index| usize::try_from(index).map_or(true, |index| index >= read_write))
+        {
+            return Err(xdr::Error::Invalid);
+        }
+        self.data.ext = if indices.is_empty() {
+            xdr::SorobanTransactionDataExt::V0
+        } else {
+            xdr::SorobanTransactionDataExt::V1(xdr::SorobanResourcesExtV0 {
+                archived_soroban_entries: indices.try_into()?,
+            })
+        };
+        Ok(self)
+    }
+
+    fn get_archived_entries(&self) -> Vec<u32> {
+        match &self.data.ext {
+            xdr::SorobanTransactionDataExt::V0 => Vec::new(),
+            xdr::SorobanTransactionDataExt::V1(ext) => ext.archived_soroban_entries.to_vec(),
+        }
+    }
+
+    fn get_ext(&self) -> &xdr::SorobanTransactionDataExt {
+        &self.data.ext
+    }
+
     fn set_read_only(&mut self, read_only: Vec<xdr::LedgerKey>) -> &mut Self {
         self.data.resources.footprint.read_only = read_only.try_into().unwrap();
         self
@@ -232,18 +290,19 @@ mod tests {
 
         // Test setting resources and resource fee
         let mut binding = SorobanDataBuilder::new(None);
-        let builder = binding.set_resources(1, 2, 3).set_refundable_fee(5);
+        let builder = binding.set_resources(1, 2, 3).set_resource_fee(5);
         assert_eq!(builder.build(), sentinel);
 
         let contract_id = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
         let c = Contracts::new(contract_id).unwrap();
         let key = c.get_footprint();
+        let rw_key = c.persistent_key(xdr::ScVal::Symbol("balance".try_into().unwrap()));
 
         let with_footprint = SorobanDataBuilder::new(None)
-            .set_footprint(Some(vec![key.clone()]), Some(vec![key.clone()]))
+            .set_footprint(Some(vec![key.clone()]), Some(vec![rw_key.clone()]))
             .build();
         assert_eq!(with_footprint.resources.footprint.read_only[0], key);
-        assert_eq!(with_footprint.resources.footprint.read_write[0], key);
+        assert_eq!(with_footprint.resources.footprint.read_write[0], rw_key);
     }
 
     #[test]
@@ -254,11 +313,12 @@ mod tests {
         let contract_id = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
         let c = Contracts::new(contract_id).unwrap();
         let key = c.get_footprint();
+        let rw_key = c.persistent_key(xdr::ScVal::Symbol("balance".try_into().unwrap()));
 
         // First builder - set both read_only and read_write footprints
         let mut builder = SorobanDataBuilder::new(None);
         let data = builder
-            .set_footprint(Some(vec![key.clone()]), Some(vec![key.clone()]))
+            .set_footprint(Some(vec![key.clone()]), Some(vec![rw_key.clone()]))
             .build();
 
         // Second builder - constructed from first data, only modify read_write
@@ -270,7 +330,7 @@ mod tests {
         assert_eq!(data.resources.footprint.read_only.len(), 1);
         assert_eq!(data.resources.footprint.read_write.len(), 1);
         assert_eq!(data.resources.footprint.read_only[0], key);
-        assert_eq!(data.resources.footprint.read_write[0], key);
+        assert_eq!(data.resources.footprint.read_write[0], rw_key);
 
         // Verify second data preserved read_only but cleared read_write
         assert_eq!(data2.resources.footprint.read_only.len(), 1);
@@ -293,13 +353,11 @@ mod tests {
             .append_footprint(vec![key.clone(), key.clone()], vec![]);
 
         // Test the builder's current state
-        assert_eq!(builder.get_read_only().len(), 3);
+        assert_eq!(builder.get_read_only().len(), 0);
         assert_eq!(builder.get_read_write().len(), 1);
 
-        // Verify read_only contains three copies of the key
-        assert_eq!(builder.get_read_only()[0], key);
-        assert_eq!(builder.get_read_only()[1], key);
-        assert_eq!(builder.get_read_only()[2], key);
+        // Verify read_only does not repeat the read-write key
+        assert!(!builder.get_read_only().contains(&key));
 
         // Verify read_write contains one copy of the key
         assert_eq!(builder.get_read_write()[0], key);
@@ -308,15 +366,52 @@ mod tests {
         let built = builder.build();
 
         // Verify the built data has the same footprint structure
-        assert_eq!(built.resources.footprint.read_only.len(), 3);
+        assert_eq!(built.resources.footprint.read_only.len(), 0);
         assert_eq!(built.resources.footprint.read_write.len(), 1);
 
-        assert_eq!(built.resources.footprint.read_only[0], key);
-        assert_eq!(built.resources.footprint.read_only[1], key);
-        assert_eq!(built.resources.footprint.read_only[2], key);
         assert_eq!(built.resources.footprint.read_write[0], key);
     }
 
+    #[test]
+    fn test_deduplicates_footprints() {
+        let contract_id = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+        let c = Contracts::new(contract_id).unwrap();
+        let key = c.get_footprint();
+        let balance = c.persistent_key(xdr::ScVal::Symbol("balance".try_into().unwrap()));
+        let allowance = c.persistent_key(xdr::ScVal::Symbol("allowance".try_into().unwrap()));
+
+        let mut builder = SorobanDataBuilder::new(None);
+        builder.set_footprint(
+            Some(vec![key.clone(), key.clone(), balance.clone()]),
+            Some(vec![balance.clone(), balance.clone()]),
+        );
+        assert_eq!(builder.get_read_only(), &vec![key.clone()]);
+        assert_eq!(builder.get_read_write(), vec![balance.clone()]);
+
+        builder.append_footprint(vec![key.clone(), key.clone(), allowance.clone()], vec![]);
+        assert_eq!(
+            builder.get_read_only(),
+            &vec![key.clone(), allowance.clone()]
+        );
+        assert_eq!(builder.get_read_write(), vec![balance.clone()]);
+
+        // Read-write keys are not duplicated as read-only, and take over read-only ones.
+        builder.append_footprint(
+            vec![balance.clone()],
+            vec![allowance.clone(), balance.clone()],
+        );
+        assert_eq!(builder.get_read_only(), &vec![key.clone()]);
+        assert_eq!(
+            builder.get_read_write(),
+            vec![balance.clone(), allowance.clone()]
+        );
+
+        // A set passed as `None` is kept, but still loses keys that become read-write.
+        builder.set_footprint(None, Some(vec![key.clone()]));
+        assert!(builder.get_read_only().is_empty());
+        assert_eq!(builder.get_read_write(), vec![key]);
+    }
+
     #[test]
     fn test_makes_copies_on_build() {
         // Create a builder
@@ -326,7 +421,7 @@ mod tests {
         let first = builder.build();
 
         // Modify builder and get second build
-        let second = builder.set_refundable_fee(100).build();
+        let second = builder.set_resource_fee(100).build();
 
         // Verify that the first build wasn't affected by later modifications
         assert_ne!(first.resource_fee, second.resource_fee);
@@ -335,4 +430,45 @@ mod tests {
         assert_eq!(first.resource_fee, 0); // Default value
         assert_eq!(second.resource_fee, 100); // Modified value
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_refundable_fee_sets_resource_fee() {
+        let data = SorobanDataBuilder::new(None).set_refundable_fee(7).build();
+        assert_eq!(data.resource_fee, 7);
+    }
+
+    #[test]
+    fn test_archived_entries() {
+        let c = Contracts::new("CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE").unwrap();
+        let mut builder = SorobanDataBuilder::new(None);
+        assert_eq!(
+            builder.set_archived_entries(vec![0]).err(),
+            Some(xdr::Error::Invalid)
+        );
+        builder
+            .set_footprint(None, Some(vec![c.get_footprint()]))
+            .set_archived_entries(vec![0])
+            .unwrap();
+        assert_eq!(builder.get_archived_entries(), vec![0]);
+        assert_eq!(
+            builder.set_archived_entries(vec![0, 1]).err(),
+            Some(xdr::Error::Invalid)
+        );
+        assert_eq!(builder.get_archived_entries(), vec![0]);
+
+        let data = builder.build();
+        assert_eq!(
+            data.ext,
+            xdr::SorobanTransactionDataExt::V1(xdr::SorobanResourcesExtV0 {
+                archived_soroban_entries: vec![0].try_into().unwrap(),
+            })
+        );
+        let rebuilt = SorobanDataBuilder::new(Some(Either::Right(data)));
+        assert_eq!(rebuilt.get_archived_entries(), vec![0]);
+
+        builder.set_archived_entries(vec![]).unwrap();
+        assert_eq!(builder.get_ext(), &xdr::SorobanTransactionDataExt::V0);
+        assert!(builder.get_archived_entries().is_empty());
+    }
 }
@@ -1,8 +1,12 @@
+use crate::decoded::Decoded;
+use crate::decoded_operation::DecodedOperation;
 use crate::hashing::HashingBehavior;
 use crate::utils::decode_encode_muxed_account::encode_muxed_account_to_address;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::ValuesMut;
 use std::error::Error;
 use std::fmt;
+use std::future::Future;
 use std::str::FromStr;
 use stellar_strkey::ed25519::PublicKey;
 use stellar_xdr::curr::LedgerKey;
@@ -11,14 +15,25 @@ use xdr::Limits;
 use xdr::SorobanTransactionData;
 
 use crate::account::Account;
+use crate::address::{Address, AddressTrait};
 use crate::hashing::Sha256Hasher;
 use crate::keypair::Keypair;
 use crate::keypair::KeypairBehavior;
+use crate::signer_key::SignerKey;
+use crate::signing::verify;
+use crate::transaction_signer;
 use crate::xdr;
 use crate::xdr::ReadXdr;
 use crate::xdr::WriteXdr;
 
-#[derive(Debug, Clone)]
+/// A transaction with its signatures, as built by
+/// [TransactionBuilder](crate::transaction_builder::TransactionBuilder) or decoded from an
+/// envelope.
+///
+/// It serializes with the field names below and XDR values in the JSON shape of `stellar-xdr`,
+/// so drafts can be stored and restored before signing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Transaction {
     //pub tx: Option<xdr::Transaction>,
     //pub tx_v0: Option<xdr::TransactionV0>,
@@ -32,26 +47,323 @@ pub struct Transaction {
     pub time_bounds: Option<xdr::TimeBounds>,
     pub ledger_bounds: Option<xdr::LedgerBounds>,
     pub min_account_sequence: Option<String>,
-    pub min_account_sequence_age: Option<u32>,
+    pub min_account_sequence_age: Option<u64>,
     pub min_account_sequence_ledger_gap: Option<u32>,
-    pub extra_signers: Option<Vec<xdr::AccountId>>,
+    pub extra_signers: Option<Vec<xdr::SignerKey>>,
     pub operations: Option<Vec<xdr::Operation>>,
-    pub hash: Option<[u8; 32]>,
     pub soroban_data: Option<SorobanTransactionData>,
+    /// The fee bump wrapping the transaction, when it was decoded from a fee-bump envelope.
+    pub fee_bump: Option<FeeBump>,
+}
+
+/// The outer part of a fee-bump envelope: the account paying the fee, the fee it pays and its
+/// signatures. The transaction it wraps keeps its own fee and signatures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FeeBump {
+    pub fee_source: String,
+    pub fee: i64,
+    pub signatures: Vec<DecoratedSignature>,
+}
+
+/// Hash of a transaction, i.e. the SHA-256 of its signature base.
+///
+/// Keeping it as a dedicated type avoids mixing transaction hashes with other 32-byte digests
+/// such as wasm hashes or liquidity pool ids. It is displayed and parsed as lowercase hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct TxHash(pub [u8; 32]);
+
+impl fmt::Display for TxHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for TxHash {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut hash = [0; 32];
+        hex::decode_to_slice(s, &mut hash)?;
+        Ok(Self(hash))
+    }
+}
+
+impl From<TxHash> for String {
+    fn from(hash: TxHash) -> Self {
+        hash.to_string()
+    }
+}
+
+impl TryFrom<String> for TxHash {
+    type Error = hex::FromHexError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for TxHash {
+    fn schema_name() -> String {
+        "TxHash".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+impl AsRef<[u8]> for TxHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for TxHash {
+    fn from(hash: [u8; 32]) -> Self {
+        Self(hash)
+    }
+}
+
+impl From<TxHash> for [u8; 32] {
+    fn from(hash: TxHash) -> Self {
+        hash.0
+    }
+}
+
+/// Fees charged to a single operation, in stroops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OperationFee {
+    /// Share of the inclusion fee bid.
+    pub inclusion_fee: i64,
+    /// Soroban resource fee, only charged to the host function operation.
+    pub resource_fee: i64,
+}
+
+/// Id of a claimable balance, both as hex including the id type, as used by Horizon, and as
+/// a `B...` strkey.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimableBalanceIds {
+    pub hex: String,
+    pub strkey: String,
+}
+
+/// Result of matching the signatures of a transaction against a set of signers, see
+/// [verify_signatures](TransactionBehavior::verify_signatures).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The signature at `index` is valid for `signer`.
+    Valid { index: usize, signer: String },
+    /// The signature at `index` is not valid for any of the signers.
+    Unmatched { index: usize },
+    /// `signer` is a pre-authorized transaction signer for this very transaction, which needs
+    /// no signature.
+    PreAuthorized { signer: String },
+    /// No signature is valid for `signer`.
+    Missing { signer: String },
+    /// `signer` is not a valid `G...`, `T...`, `X...` or `P...` strkey.
+    InvalidSigner { signer: String },
+}
+
+/// Attribution of a transaction's maximum fee to its operations, in stroops.
+///
+/// The inclusion fee is split evenly across operations, with any remainder going to the
+/// first ones, so that the per-operation amounts always add up to the total.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub total_fee: i64,
+    pub inclusion_fee: i64,
+    pub resource_fee: i64,
+    /// Resources declared by the Soroban transaction data, if any.
+    pub resources: Option<xdr::SorobanResources>,
+    pub operations: Vec<OperationFee>,
 }
 
 // Define a trait for Transaction behavior
 pub trait TransactionBehavior {
     fn signature_base(&self) -> Vec<u8>;
-    fn hash(&self) -> [u8; 32];
-    fn sign(&mut self, keypairs: &[Keypair]);
+    fn hash(&self) -> TxHash;
+    fn upgrade_to_v1(&mut self);
+    fn sign(&mut self, keypairs: &[Keypair]) -> Result<(), Box<dyn Error>>;
+    fn sign_with(
+        &mut self,
+        signers: &[&dyn transaction_signer::TransactionSigner],
+    ) -> Result<(), Box<dyn Error>>;
+    fn sign_with_async(
+        &mut self,
+        signers: &[&dyn transaction_signer::AsyncTransactionSigner],
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send;
+    fn add_signature(&mut self, public_key: &str, signature: &str) -> Result<(), Box<dyn Error>>;
+    fn remove_signature(&mut self, hint_or_public_key: &str) -> Result<usize, Box<dyn Error>>;
+    fn clear_signatures(&mut self);
     fn to_envelope(&self) -> Result<xdr::TransactionEnvelope, Box<dyn Error>>;
+    fn to_envelope_base64(&self) -> Result<String, Box<dyn Error>>;
+    #[deprecated(note = "panics on invalid input, use try_from_xdr_envelope or decode_envelope")]
     fn from_xdr_envelope(xdr: &str, network: &str) -> Self;
+    fn try_from_xdr_envelope(xdr: &str, network: &str) -> Result<Self, Box<dyn Error>>
+    where
+        Self: Sized;
+    fn try_from_xdr_envelope_with_limits(
+        xdr: &str,
+        network: &str,
+        limits: Limits,
+    ) -> Result<Self, Box<dyn Error>>
+    where
+        Self: Sized;
+    /// Returns `true` if the base64 `envelope` decodes and re-encodes to exactly the same
+    /// bytes, so that its hash can safely identify the transaction that was received.
+    fn is_canonical(envelope: &str) -> bool
+    where
+        Self: Sized;
+    fn effective_source(&self, op_index: usize) -> Option<String>;
+    fn source_address(&self) -> Result<Address, Box<dyn Error>>;
+    fn is_soroban_transaction(&self) -> bool;
+    fn decoded_operations(&self) -> Result<Vec<DecodedOperation>, Box<dyn Error>>;
+    fn describe(&self) -> Result<Vec<String>, Box<dyn Error>>;
+    fn verify_signatures(&self, signers: &[&str]) -> Vec<SignatureStatus>;
+    fn get_claimable_balance_id(
+        &self,
+        op_index: usize,
+    ) -> Result<ClaimableBalanceIds, Box<dyn Error>>;
+    fn fee_breakdown(&self) -> FeeBreakdown;
+    fn signed_payload_signature(
+        &self,
+        keypair: &Keypair,
+        payload: &[u8],
+    ) -> Result<DecoratedSignature, Box<dyn Error>>;
     //TODO: XDR Conversion, Proper From and To
 }
 
 impl Transaction {
-    fn to_tx(&self) -> xdr::Transaction {
+    /// Returns the preconditions of the transaction. Any V2-only field that is set, even to
+    /// its default, selects V2 preconditions, so decoded envelopes encode back identically.
+    fn preconditions(&self) -> xdr::Preconditions {
+        if self.ledger_bounds.is_none()
+            && self.min_account_sequence.is_none()
+            && self.min_account_sequence_age.is_none()
+            && self.min_account_sequence_ledger_gap.is_none()
+            && self.extra_signers.is_none()
+        {
+            return match &self.time_bounds {
+                None => xdr::Preconditions::None,
+                Some(time_bounds) => xdr::Preconditions::Time(time_bounds.clone()),
+            };
+        }
+
+        xdr::Preconditions::V2(xdr::PreconditionsV2 {
+            time_bounds: self.time_bounds.clone(),
+            ledger_bounds: self.ledger_bounds.clone(),
+            min_seq_num: self.min_account_sequence.as_ref().map(|seq| {
+                xdr::SequenceNumber(seq.parse::<i64>().expect("Invalid min account sequence"))
+            }),
+            min_seq_age: xdr::Duration(self.min_account_sequence_age.unwrap_or(0)),
+            min_seq_ledger_gap: self.min_account_sequence_ledger_gap.unwrap_or(0),
+            extra_signers: self
+                .extra_signers
+                .clone()
+                .unwrap_or_default()
+                .try_into()
+                .expect("Too many extra signers"),
+        })
+    }
+
+    fn from_envelope(tx_env: xdr::TransactionEnvelope, network: &str) -> Self {
+        let (tx_env, fee_bump) = match tx_env {
+            xdr::TransactionEnvelope::TxFeeBump(fee_bump_env) => {
+                let xdr::FeeBumpTransactionInnerTx::Tx(inner) = fee_bump_env.tx.inner_tx;
+                let fee_bump = FeeBump {
+                    fee_source: encode_muxed_account_to_address(&fee_bump_env.tx.fee_source),
+                    fee: fee_bump_env.tx.fee,
+                    signatures: fee_bump_env.signatures.to_vec(),
+                };
+                (xdr::TransactionEnvelope::Tx(inner), Some(fee_bump))
+            }
+            tx_env => (tx_env, None),
+        };
+        let envelope_type = tx_env.discriminant();
+
+        match tx_env {
+            xdr::TransactionEnvelope::TxV0(tx_v0_env) => Self {
+                //tx: None,
+                //tx_v0: Some(tx_v0_env.tx.clone()),
+                network_passphrase: network.to_owned(),
+                signatures: tx_v0_env.signatures.to_vec(),
+                fee: tx_v0_env.tx.fee,
+                envelope_type,
+                memo: Some(tx_v0_env.tx.memo),
+                sequence: Some(tx_v0_env.tx.seq_num.0.to_string()),
+                source: Some(
+                    stellar_strkey::Strkey::PublicKeyEd25519(PublicKey(
+                        tx_v0_env.tx.source_account_ed25519.0,
+                    ))
+                    .to_string(),
+                ),
+                time_bounds: tx_v0_env.tx.time_bounds,
+                ledger_bounds: None,
+                min_account_sequence: None,
+                min_account_sequence_age: None,
+                min_account_sequence_ledger_gap: None,
+                extra_signers: None,
+                operations: Some(tx_v0_env.tx.operations.to_vec()),
+                fee_bump: None,
+                soroban_data: None,
+            },
+            xdr::TransactionEnvelope::Tx(tx_env) => {
+                let mut time_bounds = None;
+                let mut ledger_bounds = None;
+                let mut min_account_sequence = None;
+                let mut min_account_sequence_age = None;
+                let mut min_account_sequence_ledger_gap = None;
+                let mut extra_signers = None;
+
+                match tx_env.tx.cond.clone() {
+                    xdr::Preconditions::Time(tb) => {
+                        time_bounds = Some(tb);
+                    }
+                    xdr::Preconditions::V2(v2) => {
+                        time_bounds = v2.time_bounds;
+                        ledger_bounds = v2.ledger_bounds;
+                        min_account_sequence = v2.min_seq_num.map(|seq| seq.0.to_string());
+                        min_account_sequence_age = Some(v2.min_seq_age.0);
+                        min_account_sequence_ledger_gap = Some(v2.min_seq_ledger_gap);
+                        extra_signers = Some(v2.extra_signers.to_vec());
+                    }
+                    xdr::Preconditions::None => {}
+                }
+
+                Self {
+                    //tx: Some(tx_env.clone().tx),
+                    //tx_v0: None,
+                    network_passphrase: network.to_owned(),
+                    signatures: tx_env.signatures.to_vec(),
+                    fee: tx_env.tx.fee,
+                    envelope_type,
+                    memo: Some(tx_env.tx.memo),
+                    sequence: Some(tx_env.tx.seq_num.0.to_string()),
+                    source: Some(encode_muxed_account_to_address(&tx_env.tx.source_account)),
+                    time_bounds,
+                    ledger_bounds,
+                    min_account_sequence,
+                    min_account_sequence_age,
+                    min_account_sequence_ledger_gap,
+                    extra_signers,
+                    operations: Some(tx_env.tx.operations.to_vec()),
+                    fee_bump,
+                    soroban_data: match tx_env.tx.ext {
+                        xdr::TransactionExt::V1(data) => Some(data),
+                        xdr::TransactionExt::V0 => None,
+                    },
+                }
+            }
+            xdr::TransactionEnvelope::TxFeeBump(_) => {
+                unreachable!("fee-bump envelopes are unwrapped above")
+            }
+        }
+    }
+
+    pub(crate) fn to_tx(&self) -> xdr::Transaction {
         match self.envelope_type {
             xdr::EnvelopeType::TxV0 => xdr::Transaction {
                 source_account: xdr::MuxedAccount::from_str(
@@ -66,10 +378,7 @@ impl Transaction {
                         .parse::<i64>()
                         .expect("Invalid sequence number"),
                 ),
-                cond: match &self.time_bounds {
-                    None => xdr::Preconditions::None,
-                    Some(time_bounds) => xdr::Preconditions::Time(time_bounds.clone()),
-                },
+                cond: self.preconditions(),
                 memo: self.memo.clone().unwrap_or(xdr::Memo::None),
                 operations: self
                     .operations
@@ -92,10 +401,7 @@ impl Transaction {
                         .parse()
                         .expect("Invalid sequence number"),
                 ),
-                cond: match &self.time_bounds {
-                    None => xdr::Preconditions::None,
-                    Some(time_bounds) => xdr::Preconditions::Time(time_bounds.clone()),
-                },
+                cond: self.preconditions(),
                 memo: self.memo.clone().unwrap_or(xdr::Memo::None),
                 operations: self
                     .operations
@@ -125,20 +431,111 @@ impl TransactionBehavior for Transaction {
         tx_sig.to_xdr(Limits::none()).unwrap()
     }
 
-    fn hash(&self) -> [u8; 32] {
-        Sha256Hasher::hash(self.signature_base())
+    /// Computes the hash of the signed fields.
+    ///
+    /// This is always the hash of the transaction itself, which its own signatures are made
+    /// over, even when it carries a [fee_bump](Transaction::fee_bump). The hash of the fee-bump
+    /// envelope is the one of the matching
+    /// [FeeBumpTransaction](crate::fee_bump_transaction::FeeBumpTransaction).
+    fn hash(&self) -> TxHash {
+        TxHash(Sha256Hasher::hash(self.signature_base()))
+    }
+
+    /// Converts a transaction decoded from a legacy V0 envelope to the V1 representation, so
+    /// it can be fee-bumped. Does nothing for V1 transactions.
+    ///
+    /// V0 transactions are signed as their V1 equivalent, so the hash and the existing
+    /// signatures stay valid.
+    fn upgrade_to_v1(&mut self) {
+        if self.envelope_type == xdr::EnvelopeType::TxV0 {
+            self.envelope_type = xdr::EnvelopeType::Tx;
+        }
+    }
+
+    /// Signs the transaction with `keypairs`. Nothing is added if one of them has no secret
+    /// key.
+    fn sign(&mut self, keypairs: &[Keypair]) -> Result<(), Box<dyn Error>> {
+        let tx_hash = self.hash();
+        let signatures = keypairs
+            .iter()
+            .map(|kp| kp.sign_decorated(&tx_hash.0))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.signatures.extend(signatures);
+        Ok(())
+    }
+
+    /// Signs the transaction with external `signers`, e.g. hardware wallets. Nothing is added
+    /// if one of them fails.
+    fn sign_with(
+        &mut self,
+        signers: &[&dyn transaction_signer::TransactionSigner],
+    ) -> Result<(), Box<dyn Error>> {
+        let tx_hash = self.hash();
+        let signatures = signers
+            .iter()
+            .map(|signer| signer.decorated_signature(&tx_hash.0))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e as Box<dyn Error>)?;
+        self.signatures.extend(signatures);
+        Ok(())
+    }
+
+    /// Signs the transaction with `signers` that sign asynchronously, e.g. a remote KMS,
+    /// one after the other. Nothing is added if one of them fails.
+    async fn sign_with_async(
+        &mut self,
+        signers: &[&dyn transaction_signer::AsyncTransactionSigner],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let tx_hash = self.hash();
+        let signatures =
+            transaction_signer::decorated_signatures_async(&tx_hash.0, signers).await?;
+        self.signatures.extend(signatures);
+        Ok(())
     }
 
-    fn sign(&mut self, keypairs: &[Keypair]) {
-        let tx_hash: [u8; 32] = self.hash();
-        for kp in keypairs {
-            let sig = kp.sign_decorated(&tx_hash);
-            self.signatures.push(sig);
+    /// Adds the base64 `signature` made by `public_key` over the transaction hash, e.g. by a
+    /// remote co-signer who never shares their secret key.
+    ///
+    /// Fails if the signature is not valid for this transaction.
+    fn add_signature(&mut self, public_key: &str, signature: &str) -> Result<(), Box<dyn Error>> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let keypair = Keypair::from_public_key(public_key)?;
+        let signature = STANDARD.decode(signature)?;
+        let tx_hash = self.hash();
+        if !keypair.verify(&tx_hash.0, &signature) {
+            return Err("signature is not valid for this transaction".into());
+        }
+        let hint = keypair.signature_hint().ok_or("invalid public key")?;
+        self.signatures.push(DecoratedSignature {
+            hint: xdr::SignatureHint(hint.as_slice().try_into()?),
+            signature: xdr::Signature(signature.try_into()?),
+        });
+        Ok(())
+    }
+
+    /// Removes the signatures whose hint matches `hint_or_public_key`, either a `G...` public
+    /// key or a hint as 8 hex characters, and returns how many were removed.
+    fn remove_signature(&mut self, hint_or_public_key: &str) -> Result<usize, Box<dyn Error>> {
+        let mut hint = [0; 4];
+        if hint_or_public_key.len() == 8 {
+            hex::decode_to_slice(hint_or_public_key, &mut hint)?;
+        } else {
+            let keypair = Keypair::from_public_key(hint_or_public_key)?;
+            hint.copy_from_slice(&keypair.raw_pubkey()[28..]);
         }
 
-        self.hash = Some(tx_hash);
+        let count = self.signatures.len();
+        self.signatures.retain(|signature| signature.hint.0 != hint);
+        Ok(count - self.signatures.len())
     }
 
+    fn clear_signatures(&mut self) {
+        self.signatures.clear();
+    }
+
+    /// Returns the envelope of the transaction with its signatures, wrapped in a fee-bump
+    /// envelope when it carries a [fee_bump](Transaction::fee_bump).
     fn to_envelope(&self) -> Result<xdr::TransactionEnvelope, Box<dyn Error>> {
         let raw_tx = self.to_tx().to_xdr_base64(xdr::Limits::none()).unwrap();
 
@@ -170,128 +567,488 @@ impl TransactionBehavior for Transaction {
             }
         };
 
-        Ok(envelope)
+        let Some(fee_bump) = &self.fee_bump else {
+            return Ok(envelope);
+        };
+        let xdr::TransactionEnvelope::Tx(inner) = envelope else {
+            return Err("a fee bump can only wrap an envelopeTypeTx transaction".into());
+        };
+        Ok(xdr::TransactionEnvelope::TxFeeBump(
+            xdr::FeeBumpTransactionEnvelope {
+                tx: xdr::FeeBumpTransaction {
+                    fee_source: xdr::MuxedAccount::from_str(&fee_bump.fee_source)?,
+                    fee: fee_bump.fee,
+                    inner_tx: xdr::FeeBumpTransactionInnerTx::Tx(inner),
+                    ext: xdr::FeeBumpTransactionExt::V0,
+                },
+                signatures: fee_bump.signatures.clone().try_into()?,
+            },
+        ))
+    }
+
+    /// Returns the envelope of the transaction, with its signatures, encoded in base64 as
+    /// expected by Horizon and Soroban RPC for submission.
+    fn to_envelope_base64(&self) -> Result<String, Box<dyn Error>> {
+        Ok(self.to_envelope()?.to_xdr_base64(xdr::Limits::none())?)
     }
 
+    fn is_canonical(envelope: &str) -> bool {
+        xdr::TransactionEnvelope::from_xdr_base64(envelope, Limits::none())
+            .and_then(|env| env.to_xdr_base64(Limits::none()))
+            .is_ok_and(|encoded| encoded == envelope)
+    }
+
+    /// Decodes the base64 envelope `xdr` like
+    /// [try_from_xdr_envelope](Self::try_from_xdr_envelope), panicking if it is not valid.
     fn from_xdr_envelope(xdr: &str, network: &str) -> Self {
-        let tx_env = xdr::TransactionEnvelope::from_xdr_base64(xdr, Limits::none()).unwrap();
-        let envelope_type = tx_env.discriminant();
+        Self::try_from_xdr_envelope(xdr, network).unwrap()
+    }
 
-        match tx_env {
-            xdr::TransactionEnvelope::TxV0(tx_v0_env) => Self {
-                //tx: None,
-                //tx_v0: Some(tx_v0_env.tx.clone()),
-                network_passphrase: network.to_owned(),
-                signatures: tx_v0_env.signatures.to_vec(),
-                fee: tx_v0_env.tx.fee,
-                envelope_type,
-                memo: Some(tx_v0_env.tx.memo),
-                sequence: Some(tx_v0_env.tx.seq_num.0.to_string()),
-                source: Some(
-                    stellar_strkey::Strkey::PublicKeyEd25519(PublicKey(
-                        tx_v0_env.tx.source_account_ed25519.0,
-                    ))
-                    .to_string(),
-                ),
-                time_bounds: tx_v0_env.tx.time_bounds,
-                ledger_bounds: None,
-                min_account_sequence: None,
-                min_account_sequence_age: None,
-                min_account_sequence_ledger_gap: None,
-                extra_signers: None,
-                operations: Some(tx_v0_env.tx.operations.to_vec()),
-                hash: None,
-                soroban_data: None,
-            },
-            xdr::TransactionEnvelope::Tx(tx_env) => {
-                let mut time_bounds = None;
-                let mut ledger_bounds = None;
-                let mut min_account_sequence = None;
-                let mut min_account_sequence_age = None;
-                let mut min_account_sequence_ledger_gap = None;
-                let mut extra_signers = None;
+    /// Decodes the base64 envelope `xdr`. A fee-bump envelope decodes to the transaction it
+    /// wraps, with the fee source, fee and signatures of the bump in
+    /// [fee_bump](Transaction::fee_bump).
+    ///
+    /// Fails if `xdr` is not valid base64 or XDR.
+    fn try_from_xdr_envelope(xdr: &str, network: &str) -> Result<Self, Box<dyn Error>> {
+        Self::try_from_xdr_envelope_with_limits(xdr, network, Limits::none())
+    }
 
-                match tx_env.tx.cond.clone() {
-                    xdr::Preconditions::Time(tb) => {
-                        time_bounds = Some(tb);
-                    }
-                    xdr::Preconditions::V2(v2) => {
-                        time_bounds = v2.time_bounds;
-                        ledger_bounds = v2.ledger_bounds;
-                        min_account_sequence = v2
-                            .min_seq_num
-                            .map(|seq| seq.to_xdr_base64(Limits::none()).unwrap());
-                        min_account_sequence_age = Some(v2.min_seq_age);
-                        min_account_sequence_ledger_gap = Some(v2.min_seq_ledger_gap);
-                        extra_signers = Some(v2.extra_signers.to_vec());
-                    }
-                    xdr::Preconditions::None => {}
-                }
+    /// Decodes the base64 envelope `xdr` like
+    /// [try_from_xdr_envelope](Self::try_from_xdr_envelope), failing if it exceeds `limits`.
+    fn try_from_xdr_envelope_with_limits(
+        xdr: &str,
+        network: &str,
+        limits: Limits,
+    ) -> Result<Self, Box<dyn Error>> {
+        let tx_env = xdr::TransactionEnvelope::from_xdr_base64(xdr, limits)?;
+        Ok(Self::from_envelope(tx_env, network))
+    }
 
-                Self {
-                    //tx: Some(tx_env.clone().tx),
-                    //tx_v0: None,
-                    network_passphrase: network.to_owned(),
-                    signatures: tx_env.signatures.to_vec(),
-                    fee: tx_env.tx.fee,
-                    envelope_type,
-                    memo: Some(tx_env.tx.memo),
-                    sequence: Some(tx_env.tx.seq_num.0.to_string()),
-                    source: Some(encode_muxed_account_to_address(&tx_env.tx.source_account)),
-                    time_bounds,
-                    ledger_bounds,
-                    min_account_sequence,
-                    min_account_sequence_age: None,
-                    min_account_sequence_ledger_gap,
-                    extra_signers: None,
-                    operations: Some(tx_env.tx.operations.to_vec()),
-                    hash: None,
-                    soroban_data: None,
-                }
-            }
-            _ => panic!("Invalid envelope type"),
+    /// Splits the transaction fee into the inclusion fee charged to each operation and the
+    /// Soroban resource fee declared in `soroban_data`.
+    fn fee_breakdown(&self) -> FeeBreakdown {
+        let total_fee = i64::from(self.fee);
+        let resource_fee = self
+            .soroban_data
+            .as_ref()
+            .map_or(0, |data| data.resource_fee)
+            .clamp(0, total_fee);
+        let inclusion_fee = total_fee - resource_fee;
+
+        let op_count = self.operations.as_ref().map_or(0, Vec::len) as i64;
+        let operations = (0..op_count)
+            .map(|i| OperationFee {
+                inclusion_fee: inclusion_fee / op_count + i64::from(i < inclusion_fee % op_count),
+                resource_fee: if i == 0 { resource_fee } else { 0 },
+            })
+            .collect();
+
+        FeeBreakdown {
+            total_fee,
+            inclusion_fee,
+            resource_fee,
+            resources: self
+                .soroban_data
+                .as_ref()
+                .map(|data| data.resources.clone()),
+            operations,
         }
     }
-}
 
-impl fmt::Display for Transaction {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Transaction {{")?;
+    /// Signs `payload` for the signed payload extra signer of `keypair` (CAP-40), returning a
+    /// signature whose hint is the key hint XORed with the last 4 bytes of the payload.
+    ///
+    /// Fails if the transaction has no extra signer for this key and payload, as the signature
+    /// would not satisfy any of its preconditions.
+    fn signed_payload_signature(
+        &self,
+        keypair: &Keypair,
+        payload: &[u8],
+    ) -> Result<DecoratedSignature, Box<dyn Error>> {
+        let expected = xdr::SignerKey::Ed25519SignedPayload(xdr::SignerKeyEd25519SignedPayload {
+            ed25519: xdr::Uint256(keypair.raw_pubkey()),
+            payload: payload.to_vec().try_into()?,
+        });
+        if !self
+            .extra_signers
+            .as_ref()
+            .is_some_and(|signers| signers.contains(&expected))
+        {
+            return Err("transaction has no extra signer for this key and payload".into());
+        }
 
-        // Network information
-        writeln!(f, "  Network: {}", self.network_passphrase)?;
+        keypair.sign_payload_decorated(payload)
+    }
 
-        // Source account
-        if let Some(source) = &self.source {
-            writeln!(f, "  Source Account: {}", source)?;
+    /// Returns the account the operation at `op_index` acts on behalf of: the operation's own
+    /// source if set, otherwise the transaction source. Muxed accounts are returned as
+    /// `M...` addresses.
+    ///
+    /// Returns `None` if there is no operation at `op_index`.
+    fn effective_source(&self, op_index: usize) -> Option<String> {
+        let op = self.operations.as_ref()?.get(op_index)?;
+        match &op.source_account {
+            Some(source) => Some(encode_muxed_account_to_address(source)),
+            None => self.source.clone(),
         }
+    }
 
-        // Fee
-        writeln!(f, "  Fee: {}", self.fee)?;
+    /// Returns the transaction source as an [Address]: an account for `G...` sources,
+    /// including those of V0 transactions, and a muxed account with its id for `M...` ones.
+    fn source_address(&self) -> Result<Address, Box<dyn Error>> {
+        let source = self.source.as_deref().ok_or("No account")?;
+        Ok(Address::new(source)?)
+    }
 
-        // Sequence number
-        if let Some(sequence) = &self.sequence {
-            writeln!(f, "  Sequence Number: {}", sequence)?;
-        }
+    /// Returns `true` if the transaction has a single Soroban operation, which requires
+    /// Soroban transaction data, usually obtained by simulating the transaction.
+    fn is_soroban_transaction(&self) -> bool {
+        matches!(
+            self.operations.as_deref(),
+            Some([xdr::Operation {
+                body: xdr::OperationBody::InvokeHostFunction(_)
+                    | xdr::OperationBody::ExtendFootprintTtl(_)
+                    | xdr::OperationBody::RestoreFootprint(_),
+                ..
+            }])
+        )
+    }
 
-        // Memo
-        if let Some(memo) = &self.memo {
-            write!(f, "  Memo: ")?;
-            match memo {
-                xdr::Memo::Text(text) => writeln!(f, "TEXT: {:?}", text)?,
-                xdr::Memo::Id(id) => writeln!(f, "ID: {}", id)?,
-                xdr::Memo::Hash(hash) => writeln!(f, "HASH: {:?}", hash)?,
-                xdr::Memo::Return(ret) => writeln!(f, "RETURN: {:?}", ret)?,
-                xdr::Memo::None => writeln!(f, "NONE")?,
+    /// Returns the operations of the transaction with decimal amounts and strkey addresses.
+    fn decoded_operations(&self) -> Result<Vec<DecodedOperation>, Box<dyn Error>> {
+        self.operations
+            .iter()
+            .flatten()
+            .map(DecodedOperation::from_xdr)
+            .collect()
+    }
+
+    /// Returns an English sentence describing each operation, e.g. for signing prompts, see
+    /// [DecodedOperation].
+    fn describe(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(self
+            .decoded_operations()?
+            .iter()
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    /// Matches the signatures of the transaction against `signers`, given as strkeys.
+    ///
+    /// Returns the status of every signature, in order, followed by the status of every
+    /// signer without a valid signature.
+    fn verify_signatures(&self, signers: &[&str]) -> Vec<SignatureStatus> {
+        let hash = self.hash();
+        let keys: Vec<Option<SignerKey>> = signers.iter().map(|s| s.parse().ok()).collect();
+        let mut signed = vec![false; signers.len()];
+
+        let mut statuses: Vec<SignatureStatus> = self
+            .signatures
+            .iter()
+            .enumerate()
+            .map(|(index, signature)| {
+                let matching = keys.iter().position(|key| {
+                    key.as_ref()
+                        .is_some_and(|key| signature_matches(key, &hash, signature))
+                });
+                match matching {
+                    Some(i) => {
+                        signed[i] = true;
+                        SignatureStatus::Valid {
+                            index,
+                            signer: signers[i].to_string(),
+                        }
+                    }
+                    None => SignatureStatus::Unmatched { index },
+                }
+            })
+            .collect();
+
+        for ((signer, key), signed) in signers.iter().zip(&keys).zip(signed) {
+            if signed {
+                continue;
             }
+            let signer = signer.to_string();
+            statuses.push(match key {
+                None => SignatureStatus::InvalidSigner { signer },
+                Some(SignerKey::PreAuthTx(tx_hash)) if *tx_hash == hash.0 => {
+                    SignatureStatus::PreAuthorized { signer }
+                }
+                Some(_) => SignatureStatus::Missing { signer },
+            });
         }
+        statuses
+    }
 
-        // Time bounds
-        if let Some(time_bounds) = &self.time_bounds {
-            writeln!(f, "  Time Bounds: {{")?;
-            writeln!(f, "    Min Time: {:?}", time_bounds.min_time)?;
-            writeln!(f, "    Max Time: {:?}", time_bounds.max_time)?;
-            writeln!(f, "  }}")?;
+    /// Returns the id of the claimable balance created by the operation at `op_index`.
+    ///
+    /// Following CAP-23, it hashes the transaction source account and sequence number with
+    /// the operation index, so it is known before the transaction is submitted.
+    fn get_claimable_balance_id(
+        &self,
+        op_index: usize,
+    ) -> Result<ClaimableBalanceIds, Box<dyn Error>> {
+        let op = self
+            .operations
+            .as_ref()
+            .and_then(|ops| ops.get(op_index))
+            .ok_or("operation index is out of range")?;
+        if !matches!(op.body, xdr::OperationBody::CreateClaimableBalance(_)) {
+            return Err("operation does not create a claimable balance".into());
+        }
+
+        let source = self.source.as_deref().ok_or("No account")?;
+        let source_account = match xdr::MuxedAccount::from_str(source)? {
+            xdr::MuxedAccount::Ed25519(key) => key,
+            xdr::MuxedAccount::MuxedEd25519(muxed) => muxed.ed25519,
+        };
+        let preimage = xdr::HashIdPreimage::OpId(xdr::HashIdPreimageOperationId {
+            source_account: xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(source_account)),
+            seq_num: xdr::SequenceNumber(
+                self.sequence
+                    .as_deref()
+                    .ok_or("No sequence number")?
+                    .parse()?,
+            ),
+            op_num: op_index.try_into()?,
+        });
+        let hash = Sha256Hasher::hash(preimage.to_xdr(Limits::none())?);
+
+        let id = xdr::ClaimableBalanceId::ClaimableBalanceIdTypeV0(xdr::Hash(hash));
+        Ok(ClaimableBalanceIds {
+            hex: hex::encode(id.to_xdr(Limits::none())?),
+            strkey: stellar_strkey::ClaimableBalance::V0(hash).to_string(),
+        })
+    }
+}
+
+/// Verifies that the base64 `envelope` carries a valid signature from `public_key`.
+///
+/// The signature base is computed according to the envelope type (V0, V1 or fee bump) for the
+/// given `network` passphrase, then every decorated signature whose hint matches the key is
+/// checked.
+pub fn verify_envelope_signature(
+    envelope: &str,
+    network: &str,
+    public_key: &str,
+) -> Result<bool, Box<dyn Error>> {
+    let tx_env = xdr::TransactionEnvelope::from_xdr_base64(envelope, Limits::none())?;
+    let keypair = Keypair::from_public_key(public_key)?;
+
+    let hash = TxHash(Sha256Hasher::hash(envelope_signature_base(
+        &tx_env, network,
+    )?));
+    let signatures = match &tx_env {
+        xdr::TransactionEnvelope::TxV0(env) => &env.signatures,
+        xdr::TransactionEnvelope::Tx(env) => &env.signatures,
+        xdr::TransactionEnvelope::TxFeeBump(env) => &env.signatures,
+    };
+
+    Ok(verify_hash_signature(&hash, signatures, &keypair))
+}
+
+/// Returns the signature base of the base64 `envelope` on `network`, i.e. the bytes whose
+/// SHA-256 hash must be signed.
+///
+/// Together with [attach_signature], this lets an offline host sign an envelope without
+/// building a [Transaction].
+pub fn signature_base_from_envelope(
+    envelope: &str,
+    network: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let tx_env = xdr::TransactionEnvelope::from_xdr_base64(envelope, Limits::none())?;
+    envelope_signature_base(&tx_env, network)
+}
+
+/// Decodes the base64 `envelope` like
+/// [try_from_xdr_envelope](TransactionBehavior::try_from_xdr_envelope), but keeps its raw bytes
+/// as [Decoded::Unknown] when they use envelope or operation types of a newer protocol.
+///
+/// Fails if `envelope` is not valid base64, or is truncated or otherwise malformed XDR.
+pub fn decode_envelope(
+    envelope: &str,
+    network: &str,
+) -> Result<Decoded<Transaction>, Box<dyn Error>> {
+    decode_envelope_with_limits(envelope, network, Limits::none())
+}
+
+/// Decodes the base64 `envelope` like [decode_envelope], failing if it exceeds `limits`.
+pub fn decode_envelope_with_limits(
+    envelope: &str,
+    network: &str,
+    limits: Limits,
+) -> Result<Decoded<Transaction>, Box<dyn Error>> {
+    Ok(
+        Decoded::<xdr::TransactionEnvelope>::from_xdr_base64_with_limits(envelope, limits)?
+            .map(|tx_env| Transaction::from_envelope(tx_env, network)),
+    )
+}
+
+/// Adds `signature`, made by `public_key` over the hash of the envelope signature base, to the
+/// base64 `envelope` and returns the new envelope in base64.
+///
+/// Fails if the signature is not valid for this envelope on `network`.
+pub fn attach_signature(
+    envelope: &str,
+    network: &str,
+    public_key: &str,
+    signature: &[u8],
+) -> Result<String, Box<dyn Error>> {
+    let mut tx_env = xdr::TransactionEnvelope::from_xdr_base64(envelope, Limits::none())?;
+    let keypair = Keypair::from_public_key(public_key)?;
+
+    let hash = Sha256Hasher::hash(envelope_signature_base(&tx_env, network)?);
+    if !keypair.verify(&hash, signature) {
+        return Err("signature is not valid for this envelope".into());
+    }
+    let hint = keypair.signature_hint().ok_or("invalid public key")?;
+    let decorated = DecoratedSignature {
+        hint: xdr::SignatureHint(hint.as_slice().try_into()?),
+        signature: xdr::Signature(signature.to_vec().try_into()?),
+    };
+
+    let signatures = match &mut tx_env {
+        xdr::TransactionEnvelope::TxV0(env) => &mut env.signatures,
+        xdr::TransactionEnvelope::Tx(env) => &mut env.signatures,
+        xdr::TransactionEnvelope::TxFeeBump(env) => &mut env.signatures,
+    };
+    let mut updated = signatures.to_vec();
+    updated.push(decorated);
+    *signatures = updated.try_into()?;
+
+    Ok(tx_env.to_xdr_base64(Limits::none())?)
+}
+
+/// Computes the signature base according to the envelope type (V0, V1 or fee bump).
+fn envelope_signature_base(
+    tx_env: &xdr::TransactionEnvelope,
+    network: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let tagged_transaction = match tx_env {
+        xdr::TransactionEnvelope::TxV0(env) => {
+            xdr::TransactionSignaturePayloadTaggedTransaction::Tx(tx_v0_to_v1(env.tx.clone()))
+        }
+        xdr::TransactionEnvelope::Tx(env) => {
+            xdr::TransactionSignaturePayloadTaggedTransaction::Tx(env.tx.clone())
+        }
+        xdr::TransactionEnvelope::TxFeeBump(env) => {
+            xdr::TransactionSignaturePayloadTaggedTransaction::TxFeeBump(env.tx.clone())
+        }
+    };
+
+    let tx_sig = xdr::TransactionSignaturePayload {
+        network_id: xdr::Hash(Sha256Hasher::hash(network.as_bytes())),
+        tagged_transaction,
+    };
+    Ok(tx_sig.to_xdr(Limits::none())?)
+}
+
+/// V0 transactions are signed as their V1 equivalent with an ed25519 source account.
+fn tx_v0_to_v1(tx: xdr::TransactionV0) -> xdr::Transaction {
+    xdr::Transaction {
+        source_account: xdr::MuxedAccount::Ed25519(tx.source_account_ed25519),
+        fee: tx.fee,
+        seq_num: tx.seq_num,
+        cond: match tx.time_bounds {
+            None => xdr::Preconditions::None,
+            Some(time_bounds) => xdr::Preconditions::Time(time_bounds),
+        },
+        memo: tx.memo,
+        operations: tx.operations,
+        ext: xdr::TransactionExt::V0,
+    }
+}
+
+/// Returns `true` if one of the `signatures` is a valid signature of `hash` by `keypair`.
+pub fn verify_hash_signature(
+    hash: &TxHash,
+    signatures: &[DecoratedSignature],
+    keypair: &Keypair,
+) -> bool {
+    let hint = match keypair.signature_hint() {
+        Some(hint) => hint,
+        None => return false,
+    };
+
+    signatures
+        .iter()
+        .filter(|sig| sig.hint.0.as_slice() == hint.as_slice())
+        .any(|sig| keypair.verify(&hash.0, &sig.signature.0))
+}
+
+/// Returns `true` if `signature` is a valid signature of `hash` for `key`.
+fn signature_matches(key: &SignerKey, hash: &TxHash, signature: &DecoratedSignature) -> bool {
+    let hint = signature.hint.0;
+    let signature = signature.signature.0.as_slice();
+    match key {
+        SignerKey::Ed25519(public_key) => {
+            hint == public_key[28..] && verify(&hash.0, signature, public_key)
+        }
+        SignerKey::HashX(preimage_hash) => {
+            hint == preimage_hash[28..] && Sha256Hasher::hash(signature) == *preimage_hash
+        }
+        SignerKey::Ed25519SignedPayload { ed25519, payload } => {
+            let mut payload_hint = [0; 4];
+            let tail = &payload[payload.len().saturating_sub(4)..];
+            payload_hint[..tail.len()].copy_from_slice(tail);
+            let expected: Vec<u8> = payload_hint
+                .iter()
+                .zip(&ed25519[28..])
+                .map(|(p, k)| p ^ k)
+                .collect();
+            hint[..] == expected[..] && verify(payload, signature, ed25519)
+        }
+        SignerKey::PreAuthTx(_) => false,
+    }
+}
+
+/// Returns the `T...` PreAuthTx signer for `tx`, to be added with
+/// [set_signer](crate::operation::Operation::set_signer) before submitting `tx`.
+///
+/// The hash depends on the network passphrase of `tx`, so the signer is only valid on that
+/// network.
+pub fn preauth_signer_for(tx: &Transaction) -> String {
+    stellar_strkey::Strkey::PreAuthTx(stellar_strkey::PreAuthTx(tx.hash().0)).to_string()
+}
+
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Transaction {{")?;
+
+        // Network information
+        writeln!(f, "  Network: {}", self.network_passphrase)?;
+
+        // Source account
+        if let Some(source) = &self.source {
+            writeln!(f, "  Source Account: {}", source)?;
+        }
+
+        // Fee
+        writeln!(f, "  Fee: {}", self.fee)?;
+
+        // Sequence number
+        if let Some(sequence) = &self.sequence {
+            writeln!(f, "  Sequence Number: {}", sequence)?;
+        }
+
+        // Memo
+        if let Some(memo) = &self.memo {
+            write!(f, "  Memo: ")?;
+            match memo {
+                xdr::Memo::Text(text) => writeln!(f, "TEXT: {:?}", text)?,
+                xdr::Memo::Id(id) => writeln!(f, "ID: {}", id)?,
+                xdr::Memo::Hash(hash) => writeln!(f, "HASH: {:?}", hash)?,
+                xdr::Memo::Return(ret) => writeln!(f, "RETURN: {:?}", ret)?,
+                xdr::Memo::None => writeln!(f, "NONE")?,
+            }
+        }
+
+        // Time bounds
+        if let Some(time_bounds) = &self.time_bounds {
+            writeln!(f, "  Time Bounds: {{")?;
+            writeln!(f, "    Min Time: {:?}", time_bounds.min_time)?;
+            writeln!(f, "    Max Time: {:?}", time_bounds.max_time)?;
+            writeln!(f, "  }}")?;
         }
 
         // Ledger bounds
@@ -340,9 +1097,7 @@ impl fmt::Display for Transaction {
         writeln!(f, "  ]")?;
 
         // Transaction hash
-        if let Some(hash) = &self.hash {
-            writeln!(f, "  Hash: {:?}", hash)?;
-        }
+        writeln!(f, "  Hash: {}", self.hash())?;
 
         // Soroban data
         if let Some(soroban_data) = &self.soroban_data {
@@ -367,6 +1122,7 @@ mod tests {
     use crate::{
         account::{Account, AccountBehavior},
         asset::{Asset, AssetBehavior},
+        fee_bump_transaction::{FeeBumpTransaction, FeeBumpTransactionBehavior},
         keypair::{self, Keypair},
         network::{NetworkPassphrase, Networks},
         operation::{self, Operation},
@@ -396,7 +1152,8 @@ mod tests {
             .add_memo("Happy birthday!")
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap()
-            .build();
+            .try_build()
+            .unwrap();
 
         //TODO: Tests still coming in for Envelope
 
@@ -409,11 +1166,12 @@ mod tests {
                     .create_account(destination, 10 * operation::ONE)
                     .unwrap(),
             )
-            .build();
+            .try_build()
+            .unwrap();
 
-        tx.sign(&[signer.clone()]);
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
         let sig = &tx.signatures[0].signature.0;
-        let verified = signer.verify(&tx.hash(), sig);
+        let verified = signer.verify(tx.hash().as_ref(), sig);
         assert!(verified);
     }
 
@@ -437,7 +1195,7 @@ mod tests {
     #[test]
     fn calculates_correct_hash_with_non_utf8_strings() {
         let xdr = "AAAAAAtjwtJadppTmm0NtAU99BFxXXfzPO1N/SqR43Z8aXqXAAAAZAAIj6YAAAACAAAAAAAAAAEAAAAB0QAAAAAAAAEAAAAAAAAAAQAAAADLa6390PDAqg3qDLpshQxS+uVw3ytSgKRirQcInPWt1QAAAAAAAAAAA1Z+AAAAAAAAAAABfGl6lwAAAEBC655+8Izq54MIZrXTVF/E1ycHgQWpVcBD+LFkuOjjJd995u/7wM8sFqQqambL0/ME2FTOtxMO65B9i3eAIu4P";
-        let tx = Transaction::from_xdr_envelope(xdr, Networks::public());
+        let tx = Transaction::try_from_xdr_envelope(xdr, Networks::public()).unwrap();
 
         println!("Transaction {}", tx);
         assert_eq!(
@@ -445,4 +1203,997 @@ mod tests {
             "a84d534b3742ad89413bdbf259e02fa4c5d039123769e9bcc63616f723a2bcd5"
         );
     }
+
+    #[test]
+    fn resolves_effective_operation_source() {
+        let mut source = Account::new(
+            "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB",
+            "20",
+        )
+        .unwrap();
+        let op_source = "GAAOFCNYV2OQUMVONXH2DOOQNNLJO7WRQ7E4INEZ7VH7JNG7IKBQAK5D";
+        let muxed_source = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVAAAAAAAAAAAAAJLK";
+
+        let tx = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(10).unwrap())
+            .add_operation(
+                Operation::with_source(op_source)
+                    .unwrap()
+                    .bump_sequence(10)
+                    .unwrap(),
+            )
+            .add_operation(
+                Operation::with_source(muxed_source)
+                    .unwrap()
+                    .bump_sequence(10)
+                    .unwrap(),
+            )
+            .try_build()
+            .unwrap();
+
+        assert_eq!(tx.effective_source(0), tx.source);
+        assert_eq!(tx.effective_source(1).unwrap(), op_source);
+        assert_eq!(tx.effective_source(2).unwrap(), muxed_source);
+        assert_eq!(tx.effective_source(3), None);
+    }
+
+    #[test]
+    fn resolves_source_address() {
+        let signer = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(
+            tx.source_address().unwrap(),
+            Address::account(&signer.raw_pubkey()).unwrap()
+        );
+
+        let mut raw = tx.to_tx();
+        raw.source_account = xdr::MuxedAccount::MuxedEd25519(xdr::MuxedAccountMed25519 {
+            id: 7,
+            ed25519: xdr::Uint256(signer.raw_pubkey()),
+        });
+        let muxed = encode_muxed_account_to_address(&raw.source_account);
+        let envelope = xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope {
+            tx: raw.clone(),
+            signatures: Default::default(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+        let muxed_tx = Transaction::try_from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        let address = muxed_tx.source_address().unwrap();
+        assert_eq!(address.to_string(), muxed);
+        assert!(matches!(
+            address.to_sc_address().unwrap(),
+            xdr::ScAddress::MuxedAccount(xdr::MuxedEd25519Account { id: 7, .. })
+        ));
+
+        let v0_envelope = xdr::TransactionEnvelope::TxV0(xdr::TransactionV0Envelope {
+            tx: xdr::TransactionV0 {
+                source_account_ed25519: xdr::Uint256(signer.raw_pubkey()),
+                fee: raw.fee,
+                seq_num: raw.seq_num,
+                time_bounds: None,
+                memo: raw.memo,
+                operations: raw.operations,
+                ext: xdr::TransactionV0Ext::V0,
+            },
+            signatures: Default::default(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+        let v0 = Transaction::try_from_xdr_envelope(&v0_envelope, Networks::testnet()).unwrap();
+        assert_eq!(v0.source_address().unwrap(), tx.source_address().unwrap());
+
+        tx.source = None;
+        assert!(tx.source_address().is_err());
+    }
+
+    #[test]
+    fn tx_hash_hex_roundtrip() {
+        let hex_hash = "a84d534b3742ad89413bdbf259e02fa4c5d039123769e9bcc63616f723a2bcd5";
+        let hash = TxHash::from_str(hex_hash).unwrap();
+
+        assert_eq!(hash.to_string(), hex_hash);
+        assert_eq!(hash.as_ref(), hex::decode(hex_hash).unwrap().as_slice());
+        assert!(TxHash::from_str("a84d").is_err());
+        assert!(TxHash::from_str(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn verifies_signature_against_tx_hash() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "1").unwrap();
+        let mut tx = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(10).unwrap())
+            .try_build()
+            .unwrap();
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
+
+        assert!(verify_hash_signature(&tx.hash(), &tx.signatures, &signer));
+        assert!(!verify_hash_signature(
+            &TxHash([0; 32]),
+            &tx.signatures,
+            &signer
+        ));
+    }
+
+    #[test]
+    fn computes_preauth_signer() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "1").unwrap();
+        let tx = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(10).unwrap())
+            .try_build()
+            .unwrap();
+
+        let preauth = preauth_signer_for(&tx);
+        assert!(preauth.starts_with('T'));
+        assert_eq!(
+            stellar_strkey::PreAuthTx::from_string(&preauth).unwrap().0,
+            tx.hash().0
+        );
+
+        let op = Operation::new().set_signer(&preauth, 1).unwrap();
+        let xdr::OperationBody::SetOptions(xdr::SetOptionsOp {
+            signer: Some(xdr::Signer { key, .. }),
+            ..
+        }) = op.body
+        else {
+            panic!("unexpected operation");
+        };
+        assert_eq!(key, xdr::SignerKey::PreAuthTx(xdr::Uint256(tx.hash().0)));
+
+        let mut other = tx.clone();
+        other.network_passphrase = Networks::public().to_string();
+        assert_ne!(preauth_signer_for(&other), preauth);
+    }
+
+    #[test]
+    fn detects_non_canonical_envelopes() {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "1").unwrap();
+        let mut tx = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_memo("abc")
+            .add_operation(Operation::new().bump_sequence(10).unwrap())
+            .try_build()
+            .unwrap();
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
+        let envelope = tx
+            .to_envelope()
+            .unwrap()
+            .to_xdr_base64(Limits::none())
+            .unwrap();
+        assert!(Transaction::is_canonical(&envelope));
+
+        let bytes = STANDARD.decode(&envelope).unwrap();
+
+        let mut trailing = bytes.clone();
+        trailing.extend_from_slice(&[0; 4]);
+        assert!(!Transaction::is_canonical(&STANDARD.encode(trailing)));
+
+        let mut padding = bytes.clone();
+        let memo = padding.windows(4).position(|w| w == b"abc\0").unwrap();
+        padding[memo + 3] = 1;
+        assert!(!Transaction::is_canonical(&STANDARD.encode(padding)));
+
+        assert!(!Transaction::is_canonical(&format!("{}\n", envelope)));
+        assert!(!Transaction::is_canonical("not an envelope"));
+    }
+
+    fn signed_payment_envelope(signer: &Keypair) -> String {
+        let mut source = Account::new(&signer.public_key(), "20").unwrap();
+        let mut tx = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(
+                Operation::new()
+                    .payment(
+                        "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2",
+                        &Asset::native(),
+                        10 * operation::ONE,
+                    )
+                    .unwrap(),
+            )
+            .try_build()
+            .unwrap();
+        tx.sign(std::slice::from_ref(signer)).unwrap();
+        tx.to_envelope_base64().unwrap()
+    }
+
+    #[test]
+    fn decodes_fee_bump_envelope() {
+        let signer = Keypair::random().unwrap();
+        let fee_source = Keypair::random().unwrap();
+        let envelope = signed_payment_envelope(&signer);
+        let xdr::TransactionEnvelope::Tx(inner) =
+            xdr::TransactionEnvelope::from_xdr_base64(&envelope, Limits::none()).unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        let fee_bump_signature = xdr::DecoratedSignature {
+            hint: xdr::SignatureHint([1; 4]),
+            signature: xdr::Signature([2; 64].try_into().unwrap()),
+        };
+        let fee_bump_envelope =
+            xdr::TransactionEnvelope::TxFeeBump(xdr::FeeBumpTransactionEnvelope {
+                tx: xdr::FeeBumpTransaction {
+                    fee_source: xdr::MuxedAccount::Ed25519(xdr::Uint256(fee_source.raw_pubkey())),
+                    fee: 400,
+                    inner_tx: xdr::FeeBumpTransactionInnerTx::Tx(inner),
+                    ext: xdr::FeeBumpTransactionExt::V0,
+                },
+                signatures: vec![fee_bump_signature.clone()].try_into().unwrap(),
+            })
+            .to_xdr_base64(Limits::none())
+            .unwrap();
+
+        let inner = Transaction::try_from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        let tx =
+            Transaction::try_from_xdr_envelope(&fee_bump_envelope, Networks::testnet()).unwrap();
+        assert_eq!(tx.hash(), inner.hash());
+        assert_eq!(tx.signatures, inner.signatures);
+        assert_eq!(tx.envelope_type, xdr::EnvelopeType::Tx);
+        assert_eq!(inner.fee_bump, None);
+        assert_eq!(
+            tx.fee_bump,
+            Some(FeeBump {
+                fee_source: fee_source.public_key(),
+                fee: 400,
+                signatures: vec![fee_bump_signature],
+            })
+        );
+        assert_eq!(tx.to_envelope_base64().unwrap(), fee_bump_envelope);
+        assert_eq!(inner.to_envelope_base64().unwrap(), envelope);
+    }
+
+    #[test]
+    fn encodes_envelope_base64() {
+        let signer = Keypair::random().unwrap();
+        let envelope = signed_payment_envelope(&signer);
+        assert!(Transaction::is_canonical(&envelope));
+
+        let tx = Transaction::try_from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        assert_eq!(tx.signatures.len(), 1);
+        assert_eq!(tx.to_envelope_base64().unwrap(), envelope);
+        assert_eq!(
+            tx.to_envelope_base64().unwrap(),
+            tx.to_envelope()
+                .unwrap()
+                .to_xdr_base64(Limits::none())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn adds_remote_signature() {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let signer = Keypair::random().unwrap();
+        let cosigner = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+
+        let signature = STANDARD.encode(cosigner.sign(&tx.hash().0).unwrap());
+        assert!(tx.add_signature(&signer.public_key(), &signature).is_err());
+        assert!(tx
+            .add_signature(&cosigner.public_key(), "not base64!")
+            .is_err());
+        assert_eq!(tx.signatures.len(), 1);
+
+        tx.add_signature(&cosigner.public_key(), &signature)
+            .unwrap();
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(verify_envelope_signature(
+            &tx.to_envelope_base64().unwrap(),
+            Networks::testnet(),
+            &cosigner.public_key()
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn signs_with_external_signers() {
+        use crate::transaction_signer::AsyncTransactionSigner;
+        use std::future::Future;
+        use std::task::{Context, Poll, Waker};
+
+        struct FailingSigner;
+
+        impl transaction_signer::TransactionSigner for FailingSigner {
+            fn signer_public_key(&self) -> String {
+                "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB".into()
+            }
+
+            fn sign_payload(&self, _: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+                Err("device disconnected".into())
+            }
+        }
+
+        let signer = Keypair::random().unwrap();
+        let envelope = signed_payment_envelope(&signer);
+        let mut signed =
+            Transaction::try_from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        signed.clear_signatures();
+        let mut expected = signed.clone();
+        expected.sign(std::slice::from_ref(&signer)).unwrap();
+
+        let mut tx = signed.clone();
+        tx.sign_with(&[&signer]).unwrap();
+        assert_eq!(tx.signatures, expected.signatures);
+        let error = tx.sign_with(&[&signer, &FailingSigner]).unwrap_err();
+        assert_eq!(error.to_string(), "device disconnected");
+        assert_eq!(tx.signatures.len(), 1);
+        let public_only = Keypair::from_public_key(&signer.public_key()).unwrap();
+        assert!(tx.sign(&[signer.clone(), public_only]).is_err());
+        assert_eq!(tx.signatures.len(), 1);
+
+        let mut tx = signed;
+        let signers: [&dyn AsyncTransactionSigner; 1] = [&signer];
+        let result = {
+            let mut future = std::pin::pin!(tx.sign_with_async(&signers));
+            match future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()))
+            {
+                Poll::Ready(result) => result,
+                Poll::Pending => panic!("signing with a keypair is ready at once"),
+            }
+        };
+        result.unwrap();
+        assert_eq!(tx.signatures, expected.signatures);
+    }
+
+    #[test]
+    fn computes_claimable_balance_id() {
+        use crate::claimant::{Claimant, ClaimantBehavior};
+
+        let mut source = Account::new(
+            "GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB",
+            "1234",
+        )
+        .unwrap();
+        let claimant = Claimant::new(
+            Some("GBBM6BKZPEHWYO3E3YKREDPQXMS4VK35YLNU7NFBRI26RAN7GI5POFBB"),
+            None,
+        )
+        .unwrap();
+        let tx = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(
+                Operation::new()
+                    .create_claimable_balance(&Asset::native(), operation::ONE, vec![claimant])
+                    .unwrap(),
+            )
+            .add_operation(Operation::new().bump_sequence(5).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let ids = tx.get_claimable_balance_id(0).unwrap();
+        assert_eq!(
+            ids.hex,
+            "000000006ec80556a0fdb66768568c3a603d64d14c5e6949ae1635cf76ab7035a2f2ed2b"
+        );
+        assert_eq!(
+            xdr::ClaimableBalanceId::from_str(&ids.strkey).unwrap(),
+            xdr::ClaimableBalanceId::from_xdr(hex::decode(&ids.hex).unwrap(), Limits::none())
+                .unwrap()
+        );
+        assert!(tx.get_claimable_balance_id(1).is_err());
+        assert!(tx.get_claimable_balance_id(2).is_err());
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let signer = Keypair::random().unwrap();
+        let tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json["fee"], 100);
+        assert_eq!(json["source"], signer.public_key());
+
+        let restored: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.hash(), tx.hash());
+        assert_eq!(
+            restored.to_envelope_base64().unwrap(),
+            tx.to_envelope_base64().unwrap()
+        );
+        assert!(serde_json::from_str::<TxHash>("\"abc\"").is_err());
+    }
+
+    #[test]
+    fn verifies_signatures_against_signers() {
+        let signer = Keypair::random().unwrap();
+        let missing = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+
+        let preimage = b"secret preimage";
+        let hash_x =
+            stellar_strkey::Strkey::HashX(stellar_strkey::HashX(Sha256Hasher::hash(preimage)))
+                .to_string();
+        let mut hint = [0; 4];
+        hint.copy_from_slice(&Sha256Hasher::hash(preimage)[28..]);
+        tx.signatures.push(DecoratedSignature {
+            hint: xdr::SignatureHint(hint),
+            signature: xdr::Signature(preimage.to_vec().try_into().unwrap()),
+        });
+        tx.signatures.push(
+            Keypair::random()
+                .unwrap()
+                .sign_decorated(&tx.hash().0)
+                .unwrap(),
+        );
+        let preauth = preauth_signer_for(&tx);
+
+        let statuses = tx.verify_signatures(&[
+            &signer.public_key(),
+            &hash_x,
+            &missing.public_key(),
+            &preauth,
+            "GABC",
+        ]);
+        assert_eq!(
+            statuses,
+            vec![
+                SignatureStatus::Valid {
+                    index: 0,
+                    signer: signer.public_key()
+                },
+                SignatureStatus::Valid {
+                    index: 1,
+                    signer: hash_x.clone()
+                },
+                SignatureStatus::Unmatched { index: 2 },
+                SignatureStatus::Missing {
+                    signer: missing.public_key()
+                },
+                SignatureStatus::PreAuthorized { signer: preauth },
+                SignatureStatus::InvalidSigner {
+                    signer: "GABC".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn verifies_signed_payload_signatures() {
+        let signer = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        let payload = tx.hash().0.to_vec();
+        tx.signatures = vec![signer.sign_payload_decorated(&payload).unwrap()];
+
+        let signed_payload = SignerKey::Ed25519SignedPayload {
+            ed25519: signer.raw_pubkey(),
+            payload,
+        }
+        .to_string();
+        assert_eq!(
+            tx.verify_signatures(&[&signer.public_key(), &signed_payload]),
+            vec![
+                SignatureStatus::Valid {
+                    index: 0,
+                    signer: signed_payload
+                },
+                SignatureStatus::Missing {
+                    signer: signer.public_key()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_signatures() {
+        let signer = Keypair::random().unwrap();
+        let cosigner = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        tx.sign(&[cosigner.clone(), cosigner.clone()]).unwrap();
+        assert_eq!(tx.signatures.len(), 3);
+
+        assert_eq!(tx.remove_signature(&cosigner.public_key()).unwrap(), 2);
+        assert_eq!(tx.remove_signature(&cosigner.public_key()).unwrap(), 0);
+        assert!(tx.remove_signature("GABC").is_err());
+        assert!(tx.remove_signature("zzzzzzzz").is_err());
+
+        let hint = hex::encode(tx.signatures[0].hint.0);
+        assert_eq!(tx.remove_signature(&hint).unwrap(), 1);
+        assert!(tx.signatures.is_empty());
+
+        tx.sign(&[signer]).unwrap();
+        tx.clear_signatures();
+        assert!(tx.signatures.is_empty());
+    }
+
+    #[test]
+    fn detects_soroban_transactions() {
+        let signer = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert!(!tx.is_soroban_transaction());
+
+        let restore = Operation::new().restore_footprint().unwrap();
+        tx.operations = Some(vec![restore.clone()]);
+        assert!(tx.is_soroban_transaction());
+
+        tx.operations = Some(vec![restore.clone(), restore]);
+        assert!(!tx.is_soroban_transaction());
+    }
+
+    #[test]
+    fn decodes_operations() {
+        use crate::decoded_operation::DecodedOperationBody;
+
+        let signer = Keypair::random().unwrap();
+        let tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        let operations = tx.decoded_operations().unwrap();
+        assert_eq!(operations.len(), 1);
+        assert!(matches!(
+            operations[0].body,
+            DecodedOperationBody::Payment { .. }
+        ));
+        assert_eq!(tx.describe().unwrap(), vec![operations[0].to_string()]);
+    }
+
+    #[test]
+    fn rejects_invalid_envelopes() {
+        let signer = Keypair::random().unwrap();
+        let envelope = signed_payment_envelope(&signer);
+        let tx = Transaction::try_from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        assert_eq!(tx.to_envelope_base64().unwrap(), envelope);
+
+        assert!(Transaction::try_from_xdr_envelope("not base64!", Networks::testnet()).is_err());
+        assert!(Transaction::try_from_xdr_envelope("AAAAYwAAAAA=", Networks::testnet()).is_err());
+        assert!(Transaction::try_from_xdr_envelope(
+            &envelope[..envelope.len() / 2],
+            Networks::testnet()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn preserves_default_v2_preconditions() {
+        let signer = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        tx.min_account_sequence_age = Some(0);
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
+        let envelope = tx.to_envelope_base64().unwrap();
+
+        let decoded = Transaction::try_from_xdr_envelope(&envelope, Networks::testnet()).unwrap();
+        assert_eq!(decoded.min_account_sequence_age, Some(0));
+        assert_eq!(decoded.min_account_sequence_ledger_gap, Some(0));
+        assert_eq!(decoded.extra_signers, Some(vec![]));
+        assert!(matches!(decoded.to_tx().cond, xdr::Preconditions::V2(_)));
+        assert_eq!(decoded.to_envelope_base64().unwrap(), envelope);
+        assert_eq!(decoded.hash(), tx.hash());
+    }
+
+    #[test]
+    fn recomputes_hash_after_changes() {
+        let signer = Keypair::random().unwrap();
+        let mut tx = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
+        let hash = tx.hash();
+
+        tx.fee += 100;
+        assert_ne!(tx.hash(), hash);
+        assert_eq!(tx.hash(), TxHash(Sha256Hasher::hash(tx.signature_base())));
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
+        assert!(verify_hash_signature(
+            &tx.hash(),
+            &tx.signatures[1..],
+            &signer
+        ));
+    }
+
+    #[test]
+    fn upgrades_v0_transactions() {
+        let signer = Keypair::random().unwrap();
+        let v1 = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        let tx = v1.to_tx();
+        let v0_envelope = xdr::TransactionEnvelope::TxV0(xdr::TransactionV0Envelope {
+            tx: xdr::TransactionV0 {
+                source_account_ed25519: xdr::Uint256(signer.raw_pubkey()),
+                fee: tx.fee,
+                seq_num: tx.seq_num,
+                time_bounds: v1.time_bounds.clone(),
+                memo: tx.memo,
+                operations: tx.operations,
+                ext: xdr::TransactionV0Ext::V0,
+            },
+            signatures: v1.signatures.clone().try_into().unwrap(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+
+        let mut upgraded =
+            Transaction::try_from_xdr_envelope(&v0_envelope, Networks::testnet()).unwrap();
+        assert_eq!(upgraded.envelope_type, xdr::EnvelopeType::TxV0);
+        upgraded.upgrade_to_v1();
+        assert_eq!(upgraded.envelope_type, xdr::EnvelopeType::Tx);
+        assert_eq!(upgraded.hash(), v1.hash());
+
+        let envelope = upgraded.to_envelope_base64().unwrap();
+        assert!(matches!(
+            upgraded.to_envelope().unwrap(),
+            xdr::TransactionEnvelope::Tx(_)
+        ));
+        assert!(
+            verify_envelope_signature(&envelope, Networks::testnet(), &signer.public_key())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn decodes_unknown_envelope() {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let signer = Keypair::random().unwrap();
+        let envelope = signed_payment_envelope(&signer);
+        let decoded = decode_envelope(&envelope, Networks::testnet()).unwrap();
+        assert_eq!(
+            decoded.known().unwrap().to_envelope_base64().unwrap(),
+            envelope
+        );
+
+        // An envelope type no protocol defines yet.
+        let unknown = STANDARD.encode([0, 0, 0, 99, 0, 0, 0, 0]);
+        let decoded = decode_envelope(&unknown, Networks::testnet()).unwrap();
+        assert!(decoded.is_unknown());
+        assert_eq!(
+            decoded.map(|tx| tx.to_tx()).to_xdr_base64().unwrap(),
+            unknown
+        );
+
+        let truncated = STANDARD.encode([0, 0, 0, 2, 0, 0]);
+        assert!(decode_envelope(&truncated, Networks::testnet()).is_err());
+    }
+
+    #[test]
+    fn decodes_fee_bump_envelope_round_trip() {
+        let signer = Keypair::random().unwrap();
+        let inner = Transaction::try_from_xdr_envelope(
+            &signed_payment_envelope(&signer),
+            Networks::testnet(),
+        )
+        .unwrap();
+        let fee_source = Keypair::random().unwrap();
+        let mut fee_bump = FeeBumpTransaction::new(inner, &fee_source.public_key(), 400).unwrap();
+        fee_bump.sign(&[fee_source]).unwrap();
+        let envelope = fee_bump
+            .to_envelope()
+            .unwrap()
+            .to_xdr_base64(Limits::none())
+            .unwrap();
+
+        let decoded = decode_envelope(&envelope, Networks::testnet()).unwrap();
+        assert!(decoded.known().unwrap().fee_bump.is_some());
+        assert_eq!(
+            decoded
+                .map(|tx| tx.to_envelope().unwrap())
+                .to_xdr_base64()
+                .unwrap(),
+            envelope
+        );
+    }
+
+    #[test]
+    fn verifies_envelope_signature() {
+        let signer = Keypair::random().unwrap();
+        let envelope = signed_payment_envelope(&signer);
+
+        assert!(
+            verify_envelope_signature(&envelope, Networks::testnet(), &signer.public_key())
+                .unwrap()
+        );
+        assert!(
+            !verify_envelope_signature(&envelope, Networks::public(), &signer.public_key())
+                .unwrap()
+        );
+
+        let other = Keypair::random().unwrap();
+        assert!(
+            !verify_envelope_signature(&envelope, Networks::testnet(), &other.public_key())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn verifies_v0_envelope_signature() {
+        let xdr = "AAAAAAtjwtJadppTmm0NtAU99BFxXXfzPO1N/SqR43Z8aXqXAAAAZAAIj6YAAAACAAAAAAAAAAEAAAAB0QAAAAAAAAEAAAAAAAAAAQAAAADLa6390PDAqg3qDLpshQxS+uVw3ytSgKRirQcInPWt1QAAAAAAAAAAA1Z+AAAAAAAAAAABfGl6lwAAAEBC655+8Izq54MIZrXTVF/E1ycHgQWpVcBD+LFkuOjjJd995u/7wM8sFqQqambL0/ME2FTOtxMO65B9i3eAIu4P";
+        let tx = Transaction::try_from_xdr_envelope(xdr, Networks::public()).unwrap();
+        let source = tx.source.unwrap();
+
+        assert!(verify_envelope_signature(xdr, Networks::public(), &source).unwrap());
+        assert!(!verify_envelope_signature(xdr, Networks::testnet(), &source).unwrap());
+    }
+
+    #[test]
+    fn verifies_fee_bump_envelope_signature() {
+        let inner_signer = Keypair::random().unwrap();
+        let fee_source = Keypair::random().unwrap();
+        let inner = xdr::TransactionEnvelope::from_xdr_base64(
+            signed_payment_envelope(&inner_signer),
+            Limits::none(),
+        )
+        .unwrap();
+        let inner = match inner {
+            xdr::TransactionEnvelope::Tx(env) => env,
+            _ => panic!("Expected a V1 envelope"),
+        };
+
+        let fee_bump = xdr::FeeBumpTransaction {
+            fee_source: xdr::MuxedAccount::Ed25519(xdr::Uint256(fee_source.raw_pubkey())),
+            fee: 400,
+            inner_tx: xdr::FeeBumpTransactionInnerTx::Tx(inner),
+            ext: xdr::FeeBumpTransactionExt::V0,
+        };
+        let payload = xdr::TransactionSignaturePayload {
+            network_id: xdr::Hash(Sha256Hasher::hash(Networks::testnet())),
+            tagged_transaction: xdr::TransactionSignaturePayloadTaggedTransaction::TxFeeBump(
+                fee_bump.clone(),
+            ),
+        };
+        let hash = Sha256Hasher::hash(payload.to_xdr(Limits::none()).unwrap());
+        let envelope = xdr::TransactionEnvelope::TxFeeBump(xdr::FeeBumpTransactionEnvelope {
+            tx: fee_bump,
+            signatures: vec![fee_source.sign_decorated(&hash).unwrap()]
+                .try_into()
+                .unwrap(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+
+        assert!(verify_envelope_signature(
+            &envelope,
+            Networks::testnet(),
+            &fee_source.public_key()
+        )
+        .unwrap());
+        assert!(!verify_envelope_signature(
+            &envelope,
+            Networks::testnet(),
+            &inner_signer.public_key()
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn verify_envelope_signature_rejects_bad_input() {
+        let signer = Keypair::random().unwrap();
+        assert!(verify_envelope_signature(
+            "not an envelope",
+            Networks::testnet(),
+            &signer.public_key()
+        )
+        .is_err());
+
+        let envelope = signed_payment_envelope(&signer);
+        assert!(verify_envelope_signature(&envelope, Networks::testnet(), "GBBB").is_err());
+    }
+
+    #[test]
+    fn computes_fee_breakdown() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let mut transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_operation(Operation::new().bump_sequence(2).unwrap())
+            .add_operation(Operation::new().bump_sequence(3).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        transaction.fee = 302;
+
+        let breakdown = transaction.fee_breakdown();
+        assert_eq!(breakdown.total_fee, 302);
+        assert_eq!(breakdown.inclusion_fee, 302);
+        assert_eq!(breakdown.resource_fee, 0);
+        assert_eq!(breakdown.resources, None);
+        assert_eq!(
+            breakdown
+                .operations
+                .iter()
+                .map(|op| op.inclusion_fee)
+                .collect::<Vec<_>>(),
+            vec![101, 101, 100]
+        );
+    }
+
+    #[test]
+    fn computes_soroban_fee_breakdown() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let resources = xdr::SorobanResources {
+            footprint: xdr::LedgerFootprint {
+                read_only: Default::default(),
+                read_write: Default::default(),
+            },
+            instructions: 1_000,
+            disk_read_bytes: 200,
+            write_bytes: 100,
+        };
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_soroban_data(xdr::SorobanTransactionData {
+                ext: xdr::SorobanTransactionDataExt::V0,
+                resources: resources.clone(),
+                resource_fee: 50,
+            })
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let parsed = Transaction::try_from_xdr_envelope(
+            &transaction
+                .to_envelope()
+                .unwrap()
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(parsed.soroban_data, transaction.soroban_data);
+
+        let breakdown = parsed.fee_breakdown();
+        assert_eq!(breakdown.total_fee, i64::from(transaction.fee));
+        assert_eq!(breakdown.resource_fee, 50);
+        assert_eq!(breakdown.inclusion_fee, breakdown.total_fee - 50);
+        assert_eq!(breakdown.resources, Some(resources));
+        assert_eq!(
+            breakdown.operations,
+            vec![OperationFee {
+                inclusion_fee: breakdown.inclusion_fee,
+                resource_fee: 50,
+            }]
+        );
+    }
+
+    #[test]
+    fn signs_extra_signer_payload() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let payload = [7_u8; 32];
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_extra_signer(xdr::SignerKey::Ed25519SignedPayload(
+                xdr::SignerKeyEd25519SignedPayload {
+                    ed25519: xdr::Uint256(signer.raw_pubkey()),
+                    payload: payload.to_vec().try_into().unwrap(),
+                },
+            ))
+            .unwrap()
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let signature = transaction
+            .signed_payload_signature(&signer, &payload)
+            .unwrap();
+        assert!(signer.verify(&payload, &signature.signature));
+        let key_hint = signer.signature_hint().unwrap();
+        let expected_hint: Vec<u8> = key_hint.iter().map(|b| b ^ 7).collect();
+        assert_eq!(signature.hint.0.to_vec(), expected_hint);
+
+        assert!(transaction
+            .signed_payload_signature(&signer, &[8; 32])
+            .is_err());
+        assert!(transaction
+            .signed_payload_signature(&Keypair::random().unwrap(), &payload)
+            .is_err());
+    }
+
+    #[test]
+    fn signs_envelope_offline() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "0").unwrap();
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        let envelope = transaction
+            .to_envelope()
+            .unwrap()
+            .to_xdr_base64(Limits::none())
+            .unwrap();
+
+        let base = signature_base_from_envelope(&envelope, Networks::testnet()).unwrap();
+        assert_eq!(base, transaction.signature_base());
+
+        let signature = signer.sign(&Sha256Hasher::hash(&base)).unwrap();
+        let signed = attach_signature(
+            &envelope,
+            Networks::testnet(),
+            &signer.public_key(),
+            &signature,
+        )
+        .unwrap();
+        assert!(
+            verify_envelope_signature(&signed, Networks::testnet(), &signer.public_key()).unwrap()
+        );
+
+        let mut expected = transaction.clone();
+        expected.sign(std::slice::from_ref(&signer)).unwrap();
+        assert_eq!(
+            signed,
+            expected
+                .to_envelope()
+                .unwrap()
+                .to_xdr_base64(Limits::none())
+                .unwrap()
+        );
+
+        assert!(attach_signature(
+            &envelope,
+            Networks::public(),
+            &signer.public_key(),
+            &signature
+        )
+        .is_err());
+        assert!(signature_base_from_envelope("not an envelope", Networks::testnet()).is_err());
+    }
 }
@@ -1,16 +1,22 @@
 use std::collections::hash_map::ValuesMut;
 use std::error::Error;
 use std::str::FromStr;
-use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
+use std::time::{Duration, SystemTime};
 
 use serde_json::from_str;
 
 use crate::account::Account;
 use crate::account::AccountBehavior;
+use crate::asset::Asset;
+use crate::config::{Config, BASE_FEE};
 use crate::hashing::Sha256Hasher;
 use crate::keypair::Keypair;
-use crate::transaction::Transaction;
+use crate::memo::{Memo, MemoBehavior};
+use crate::operation;
+use crate::operation::Operation;
+use crate::signer_key::SignerKey;
+use crate::transaction::{Transaction, TransactionBehavior};
 use crate::utils::decode_encode_muxed_account::decode_address_fully_to_muxed_account;
 use crate::utils::decode_encode_muxed_account::decode_address_to_muxed_account;
 use crate::utils::decode_encode_muxed_account::decode_address_to_muxed_account_fix_for_g_address;
@@ -29,37 +35,348 @@ pub struct TransactionBuilder<'a> {
     envelope_type: Option<xdr::EnvelopeType>,
     memo: Option<xdr::Memo>,
     sequence: Option<String>,
-    source: Option<&'a mut Account>,
+    source: Option<SourceAccount<'a>>,
+    muxed_source: Option<String>,
     time_bounds: Option<xdr::TimeBounds>,
     ledger_bounds: Option<xdr::LedgerBounds>,
     min_account_sequence: Option<String>,
-    min_account_sequence_age: Option<u32>,
+    min_account_sequence_age: Option<u64>,
     min_account_sequence_ledger_gap: Option<u32>,
-    extra_signers: Option<Vec<xdr::AccountId>>,
+    extra_signers: Option<Vec<xdr::SignerKey>>,
     operations: Option<Vec<xdr::Operation>>,
     soroban_data: Option<xdr::SorobanTransactionData>,
+    xdr_limits: Option<xdr::Limits>,
+    strict: bool,
+    no_increment: bool,
+    include_resource_fee: bool,
+}
+
+/// The source account of a builder, either borrowed from the caller or owned by the builder
+/// when created with [with_source](TransactionBuilderBehavior::with_source).
+enum SourceAccount<'a> {
+    Borrowed(&'a mut Account),
+    Owned(Account),
+}
+
+impl std::ops::Deref for SourceAccount<'_> {
+    type Target = Account;
+
+    fn deref(&self) -> &Account {
+        match self {
+            SourceAccount::Borrowed(account) => account,
+            SourceAccount::Owned(account) => account,
+        }
+    }
+}
+
+impl std::ops::DerefMut for SourceAccount<'_> {
+    fn deref_mut(&mut self) -> &mut Account {
+        match self {
+            SourceAccount::Borrowed(account) => account,
+            SourceAccount::Owned(account) => account,
+        }
+    }
 }
 
 // Define a trait for TransactionBuilder behavior
 pub trait TransactionBuilderBehavior<'a> {
     fn build_for_simulation(&self) -> Transaction;
     fn set_soroban_data_from_xdr_base64(&mut self, soroban_data: &str) -> &mut Self;
+    fn try_set_soroban_data_from_xdr_base64(
+        &mut self,
+        soroban_data: &str,
+    ) -> Result<&mut Self, xdr::Error>;
     fn new(
         source_account: &'a mut Account,
         network: &str,
         time_bounds: Option<xdr::TimeBounds>,
     ) -> Self;
+    fn with_config(source_account: &'a mut Account, config: &Config) -> Self;
+    fn with_source(account_id: &str, sequence: i64, network: &str) -> Result<Self, String>
+    where
+        Self: Sized;
+    fn from_transaction(
+        source_account: &'a mut Account,
+        transaction: &Transaction,
+    ) -> Result<Self, String>
+    where
+        Self: Sized;
     fn fee(&mut self, fee: impl Into<u32>) -> &mut Self;
     fn add_operation(&mut self, operation: xdr::Operation) -> &mut Self;
+    fn add_operations(
+        &mut self,
+        operations: impl IntoIterator<Item = xdr::Operation>,
+    ) -> Result<&mut Self, BuildError>;
+    fn insert_operation(
+        &mut self,
+        index: usize,
+        operation: xdr::Operation,
+    ) -> Result<&mut Self, String>;
+    fn replace_operation(
+        &mut self,
+        index: usize,
+        operation: xdr::Operation,
+    ) -> Result<&mut Self, String>;
+    fn strict(&mut self, strict: bool) -> &mut Self;
+    fn include_resource_fee(&mut self, include: bool) -> &mut Self;
+    #[deprecated(note = "panics if the transaction cannot be built, use try_build")]
     fn build(&mut self) -> Transaction;
+    fn try_build(&mut self) -> Result<Transaction, BuildError>;
+    fn build_with_sequence(&mut self, sequence: i64) -> Result<Transaction, BuildError>;
+    fn no_increment(&mut self) -> &mut Self;
+    fn build_and_sign(
+        &mut self,
+        signers: &[Keypair],
+        timeout_seconds: i64,
+    ) -> Result<(Transaction, String, String), Box<dyn Error>>;
+    fn set_muxed_source(&mut self, muxed_address: &str) -> Result<&mut Self, String>;
     fn add_memo(&mut self, memo_text: &str) -> &mut Self;
+    fn add_memo_text(&mut self, text: &str) -> Result<&mut Self, String>;
+    fn add_memo_id(&mut self, id: u64) -> &mut Self;
+    fn add_memo_hash(&mut self, hash: [u8; 32]) -> &mut Self;
+    fn add_memo_return(&mut self, hash: [u8; 32]) -> &mut Self;
+    fn set_memo(&mut self, memo: Memo) -> Result<&mut Self, String>;
     fn set_timeout(&mut self, timeout_seconds: i64) -> Result<&mut Self, String>;
     fn set_time_bounds(&mut self, time_bounds: xdr::TimeBounds) -> &mut Self;
+    fn set_valid_for(&mut self, duration: Duration) -> Result<&mut Self, String>;
+    fn set_valid_until(&mut self, deadline: impl Into<SystemTime>) -> Result<&mut Self, String>;
+    fn set_ledger_bounds(&mut self, min_ledger: u32, max_ledger: u32) -> Result<&mut Self, String>;
+    fn set_min_account_sequence(&mut self, min_sequence: &str) -> Result<&mut Self, String>;
+    fn set_min_account_sequence_age(&mut self, seconds: u64) -> &mut Self;
+    fn set_min_account_sequence_ledger_gap(&mut self, gap: u32) -> &mut Self;
+    fn add_extra_signer<S>(&mut self, signer: S) -> Result<&mut Self, String>
+    where
+        S: TryInto<SignerKey>,
+        S::Error: std::fmt::Display;
     fn set_soroban_data(&mut self, soroban_data: xdr::SorobanTransactionData) -> &mut Self;
     fn clear_operations(&mut self) -> &mut Self;
 }
 
 pub const TIMEOUT_INFINITE: i64 = 0;
+/// Protocol limit on the number of extra signers a transaction can require.
+pub const MAX_EXTRA_SIGNERS: usize = 2;
+
+/// Maximum number of operations allowed in a single transaction.
+pub const MAX_OPERATIONS: usize = 100;
+
+/// Reasons a [TransactionBuilder] cannot build a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// The transaction has more than [MAX_OPERATIONS] operations.
+    TooManyOperations(usize),
+    /// The base fee times the number of operations does not fit in a `u32`.
+    FeeOverflow { base_fee: u32, operations: usize },
+    /// The soroban data declares a negative resource fee.
+    InvalidResourceFee(i64),
+    /// In [strict](TransactionBuilderBehavior::strict) mode, every protocol constraint the
+    /// transaction violates.
+    ProtocolViolations(Vec<String>),
+    /// No base fee was set with [fee](TransactionBuilderBehavior::fee).
+    MissingFee,
+    /// The builder has no source account.
+    MissingSource,
+    /// The builder has no network passphrase.
+    MissingNetwork,
+    /// The next sequence number of the source account does not fit in an `i64`.
+    InvalidSequence(String),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::TooManyOperations(count) => write!(
+                f,
+                "a transaction can have at most {} operations, got {}",
+                MAX_OPERATIONS, count
+            ),
+            BuildError::FeeOverflow {
+                base_fee,
+                operations,
+            } => write!(
+                f,
+                "fee overflow: base fee {} times {} operations exceeds u32::MAX",
+                base_fee, operations
+            ),
+            BuildError::InvalidResourceFee(resource_fee) => {
+                write!(
+                    f,
+                    "invalid resource fee {}, it cannot be negative",
+                    resource_fee
+                )
+            }
+            BuildError::ProtocolViolations(violations) => {
+                write!(f, "invalid transaction: {}", violations.join("; "))
+            }
+            BuildError::MissingFee => write!(f, "fee not set"),
+            BuildError::MissingSource => write!(f, "source account not set"),
+            BuildError::MissingNetwork => write!(f, "network passphrase not set"),
+            BuildError::InvalidSequence(sequence) => {
+                write!(f, "invalid sequence number {}", sequence)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// Builds a transaction made only of `payments`, each given as `(destination, asset, amount)`.
+///
+/// This is a fast path for payout workloads: the operations are allocated once and moved into
+/// the resulting [Transaction] instead of being cloned at every build step. The total fee is
+/// `base_fee` times the number of payments, and like
+/// [build()](TransactionBuilderBehavior::build) the `source` sequence number is incremented.
+pub fn build_payments_tx(
+    source: &mut Account,
+    network: &str,
+    payments: &[(&str, &Asset, i64)],
+    base_fee: u32,
+) -> Result<Transaction, operation::Error> {
+    if payments.is_empty() || payments.len() > MAX_OPERATIONS {
+        return Err(operation::Error::InvalidField("payments".into()));
+    }
+    let fee = base_fee
+        .checked_mul(payments.len() as u32)
+        .ok_or_else(|| operation::Error::InvalidField("base_fee".into()))?;
+
+    let op = Operation::new();
+    let mut operations = Vec::with_capacity(payments.len());
+    for (destination, asset, amount) in payments {
+        operations.push(op.payment(destination, asset, *amount)?);
+    }
+
+    source.increment_sequence_number();
+
+    Ok(Transaction {
+        network_passphrase: network.to_string(),
+        signatures: Vec::new(),
+        fee,
+        envelope_type: xdr::EnvelopeType::Tx,
+        memo: None,
+        sequence: Some(source.sequence_number()),
+        source: Some(source.account_id()),
+        time_bounds: None,
+        ledger_bounds: None,
+        min_account_sequence: None,
+        min_account_sequence_age: None,
+        min_account_sequence_ledger_gap: None,
+        extra_signers: None,
+        operations: Some(operations),
+        fee_bump: None,
+        soroban_data: None,
+    })
+}
+
+impl TransactionBuilder<'_> {
+    /// Returns the total fee, i.e. the base fee times the number of operations, plus the
+    /// resource fee of the soroban data unless it is managed manually.
+    fn total_fee(&self) -> Result<u32, BuildError> {
+        let base_fee = self.fee.ok_or(BuildError::MissingFee)?;
+        let operations = self.operations.as_ref().map_or(0, Vec::len);
+        if operations > MAX_OPERATIONS {
+            return Err(BuildError::TooManyOperations(operations));
+        }
+        let overflow = BuildError::FeeOverflow {
+            base_fee,
+            operations,
+        };
+        let resource_fee = match &self.soroban_data {
+            Some(data) if self.include_resource_fee && data.resource_fee < 0 => {
+                return Err(BuildError::InvalidResourceFee(data.resource_fee));
+            }
+            Some(data) if self.include_resource_fee => {
+                u32::try_from(data.resource_fee).map_err(|_| overflow.clone())?
+            }
+            _ => 0,
+        };
+        base_fee
+            .checked_mul(operations as u32)
+            .and_then(|fee| fee.checked_add(resource_fee))
+            .ok_or(overflow)
+    }
+
+    /// Builds the transaction with `sequence`, or the next sequence number of the source
+    /// account, which is incremented unless
+    /// [no_increment](TransactionBuilderBehavior::no_increment) is set.
+    fn build_at(&mut self, sequence: Option<i64>) -> Result<Transaction, BuildError> {
+        if self.strict {
+            let violations = self.protocol_violations();
+            if !violations.is_empty() {
+                return Err(BuildError::ProtocolViolations(violations));
+            }
+        }
+        let fee = self.total_fee()?;
+        let network_passphrase = self
+            .network_passphrase
+            .clone()
+            .ok_or(BuildError::MissingNetwork)?;
+        let source = self.source.as_mut().ok_or(BuildError::MissingSource)?;
+
+        let sequence_number = match sequence {
+            Some(sequence) => sequence.to_string(),
+            None => {
+                let current = source.sequence_number();
+                let next = current
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|current| current.checked_add(1))
+                    .ok_or(BuildError::InvalidSequence(current))?;
+                if !self.no_increment {
+                    // Increment the sequence number directly on the mutable reference
+                    source.increment_sequence_number();
+                }
+                next.to_string()
+            }
+        };
+        let account_id = self
+            .muxed_source
+            .clone()
+            .unwrap_or_else(|| source.account_id());
+
+        Ok(Transaction {
+            network_passphrase,
+            signatures: Vec::new(),
+            fee,
+            envelope_type: xdr::EnvelopeType::Tx,
+            memo: self.memo.clone(),
+            sequence: Some(sequence_number),
+            source: Some(account_id.to_string()),
+            time_bounds: self.time_bounds.clone(),
+            ledger_bounds: self.ledger_bounds.clone(),
+            min_account_sequence: self.min_account_sequence.clone(),
+            min_account_sequence_age: self.min_account_sequence_age,
+            min_account_sequence_ledger_gap: self.min_account_sequence_ledger_gap,
+            extra_signers: self.extra_signers.clone(),
+            operations: self.operations.clone(),
+            fee_bump: None,
+            soroban_data: self.soroban_data.clone(),
+            //tx_v0: None,
+        })
+    }
+
+    /// Returns every protocol constraint the transaction would violate once built.
+    fn protocol_violations(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let operations = self.operations.as_deref().unwrap_or_default();
+        if operations.is_empty() {
+            violations.push("transaction has no operations".to_string());
+        }
+        if operations.len() > MAX_OPERATIONS {
+            violations.push(BuildError::TooManyOperations(operations.len()).to_string());
+        }
+        if let Some(fee) = self.fee.filter(|fee| *fee < BASE_FEE) {
+            violations.push(format!(
+                "base fee must be at least {}, got {}",
+                BASE_FEE, fee
+            ));
+        }
+        for (i, op) in operations.iter().enumerate() {
+            for violation in operation::protocol_violations(op) {
+                violations.push(format!("operation {}: {}", i, violation));
+            }
+        }
+        violations
+    }
+}
 
 impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
     fn new(
@@ -76,7 +393,8 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
             envelope_type: None,
             memo: None,
             sequence: None,
-            source: Some(source_account),
+            source: Some(SourceAccount::Borrowed(source_account)),
+            muxed_source: None,
             time_bounds,
             ledger_bounds: None,
             min_account_sequence: None,
@@ -85,7 +403,104 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
             extra_signers: None,
             operations: Some(Vec::new()),
             soroban_data: None,
+            xdr_limits: None,
+            strict: false,
+            no_increment: false,
+            include_resource_fee: true,
+        }
+    }
+
+    /// Creates a builder using the network, base fee, timeout and XDR limits of `config`.
+    fn with_config(source_account: &'a mut Account, config: &Config) -> Self {
+        let mut builder = Self::new(source_account, &config.network_passphrase, None);
+        builder.fee(config.base_fee);
+        builder.xdr_limits = Some(config.xdr_limits.clone());
+        if let Some(timeout) = config.timeout {
+            builder
+                .set_timeout(timeout.into())
+                .expect("a new builder has no time bounds and the timeout is positive");
+        }
+        builder
+    }
+
+    /// Creates a builder owning its source account, for one-shot transactions where the caller
+    /// does not need to keep track of the account itself.
+    ///
+    /// `account_id` can be a `G...` or `M...` address and `sequence` is the current sequence
+    /// number of the account; built transactions use the following ones.
+    fn with_source(account_id: &str, sequence: i64, network: &str) -> Result<Self, String> {
+        let (account_id, muxed_source) = match xdr::MuxedAccount::from_str(account_id) {
+            Ok(xdr::MuxedAccount::MuxedEd25519(muxed)) => (
+                xdr::MuxedAccount::Ed25519(muxed.ed25519.clone()).to_string(),
+                Some(xdr::MuxedAccount::MuxedEd25519(muxed).to_string()),
+            ),
+            _ => (account_id.to_string(), None),
+        };
+        let account = Account::new(&account_id, &sequence.to_string())?;
+        Ok(Self {
+            network_passphrase: Some(network.to_string()),
+            source: Some(SourceAccount::Owned(account)),
+            muxed_source,
+            operations: Some(Vec::new()),
+            include_resource_fee: true,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a builder producing the same transaction as `transaction`, with its operations,
+    /// memo, preconditions and soroban data, so that it can be rebuilt with the current
+    /// sequence number of `source_account` or a different fee.
+    ///
+    /// The base fee is the transaction fee, minus the resource fee of its soroban data, divided
+    /// by the number of operations and rounded up.
+    /// Fails if `source_account` is not the source of `transaction`, or if its resource fee
+    /// is negative or does not fit in a `u32`.
+    fn from_transaction(
+        source_account: &'a mut Account,
+        transaction: &Transaction,
+    ) -> Result<Self, String> {
+        let source = transaction
+            .source
+            .as_deref()
+            .and_then(|source| xdr::MuxedAccount::from_str(source).ok())
+            .ok_or("transaction has no valid source account")?;
+        let source_key = match &source {
+            xdr::MuxedAccount::Ed25519(key) => key.clone(),
+            xdr::MuxedAccount::MuxedEd25519(muxed) => muxed.ed25519.clone(),
+        };
+        if xdr::MuxedAccount::Ed25519(source_key).to_string() != source_account.account_id() {
+            return Err("source_account is not the transaction source".to_string());
         }
+
+        let operations = transaction.operations.clone().unwrap_or_default();
+        let mut builder = Self::new(
+            source_account,
+            &transaction.network_passphrase,
+            transaction.time_bounds.clone(),
+        );
+        if let xdr::MuxedAccount::MuxedEd25519(_) = source {
+            builder.muxed_source = Some(source.to_string());
+        }
+        let resource_fee = match &transaction.soroban_data {
+            Some(data) => u32::try_from(data.resource_fee)
+                .map_err(|_| format!("invalid resource fee {}", data.resource_fee))?,
+            None => 0,
+        };
+        builder.fee(
+            transaction
+                .fee
+                .saturating_sub(resource_fee)
+                .div_ceil(operations.len().max(1) as u32),
+        );
+        builder.memo = transaction.memo.clone();
+        builder.ledger_bounds = transaction.ledger_bounds.clone();
+        builder.min_account_sequence = transaction.min_account_sequence.clone();
+        builder.min_account_sequence_age = transaction.min_account_sequence_age;
+        builder.min_account_sequence_ledger_gap = transaction.min_account_sequence_ledger_gap;
+        builder.extra_signers = transaction.extra_signers.clone();
+        builder.soroban_data = transaction.soroban_data.clone();
+        builder.operations = Some(operations);
+        Ok(builder)
     }
 
     fn fee(&mut self, fee: impl Into<u32>) -> &mut Self {
@@ -100,6 +515,80 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
         self
     }
 
+    /// Inserts `operation` at `index`, shifting the following operations. Fails if `index` is
+    /// past the end of the operation list.
+    fn insert_operation(
+        &mut self,
+        index: usize,
+        operation: xdr::Operation,
+    ) -> Result<&mut Self, String> {
+        let operations = self.operations.get_or_insert_with(Vec::new);
+        if index > operations.len() {
+            return Err(format!(
+                "operation index {} out of range, the transaction has {} operations",
+                index,
+                operations.len()
+            ));
+        }
+
+        operations.insert(index, operation);
+        Ok(self)
+    }
+
+    /// Replaces the operation at `index` with `operation`. Fails if there is no operation at
+    /// `index`.
+    fn replace_operation(
+        &mut self,
+        index: usize,
+        operation: xdr::Operation,
+    ) -> Result<&mut Self, String> {
+        let operations = self.operations.get_or_insert_with(Vec::new);
+        let len = operations.len();
+        let slot = operations.get_mut(index).ok_or_else(|| {
+            format!(
+                "operation index {} out of range, the transaction has {} operations",
+                index, len
+            )
+        })?;
+
+        *slot = operation;
+        Ok(self)
+    }
+
+    /// Appends all of `operations`. If the transaction would end up with more than
+    /// [MAX_OPERATIONS] operations, none of them are added.
+    fn add_operations(
+        &mut self,
+        operations: impl IntoIterator<Item = xdr::Operation>,
+    ) -> Result<&mut Self, BuildError> {
+        let operations = operations.into_iter().collect::<Vec<_>>();
+        let vec = self.operations.get_or_insert_with(Vec::new);
+        let count = vec.len() + operations.len();
+        if count > MAX_OPERATIONS {
+            return Err(BuildError::TooManyOperations(count));
+        }
+
+        vec.extend(operations);
+        Ok(self)
+    }
+
+    /// Uses the `M...` address `muxed_address` as the transaction source. Its base account must
+    /// be the source account of the builder, whose sequence number is still used.
+    fn set_muxed_source(&mut self, muxed_address: &str) -> Result<&mut Self, String> {
+        let source = self.source.as_ref().ok_or("Source account not set")?;
+        let muxed = operation::parse_muxed_account(muxed_address, "muxed_source")
+            .map_err(|_| "muxed_source is not a valid address".to_string())?;
+        let xdr::MuxedAccount::MuxedEd25519(muxed) = muxed else {
+            return Err("muxed_source must be an M... address".to_string());
+        };
+        if xdr::MuxedAccount::Ed25519(muxed.ed25519.clone()).to_string() != source.account_id() {
+            return Err("muxed_source is not based on the source account".to_string());
+        }
+
+        self.muxed_source = Some(xdr::MuxedAccount::MuxedEd25519(muxed).to_string());
+        Ok(self)
+    }
+
     fn add_memo(&mut self, memo_text: &str) -> &mut Self {
         self.memo = Some(xdr::Memo::Text(
             xdr::StringM::<28>::from_str(memo_text).unwrap(),
@@ -107,6 +596,37 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
         self
     }
 
+    /// Sets a text memo of at most 28 bytes.
+    fn add_memo_text(&mut self, text: &str) -> Result<&mut Self, String> {
+        let text = xdr::StringM::<28>::from_str(text)
+            .map_err(|_| "memo text cannot be longer than 28 bytes".to_string())?;
+        self.memo = Some(xdr::Memo::Text(text));
+        Ok(self)
+    }
+
+    fn add_memo_id(&mut self, id: u64) -> &mut Self {
+        self.memo = Some(xdr::Memo::Id(id));
+        self
+    }
+
+    fn add_memo_hash(&mut self, hash: [u8; 32]) -> &mut Self {
+        self.memo = Some(xdr::Memo::Hash(xdr::Hash(hash)));
+        self
+    }
+
+    /// Sets a return memo, holding the hash of the transaction being refunded.
+    fn add_memo_return(&mut self, hash: [u8; 32]) -> &mut Self {
+        self.memo = Some(xdr::Memo::Return(xdr::Hash(hash)));
+        self
+    }
+
+    /// Sets a memo of any kind built with [MemoBehavior], replacing any previous memo.
+    fn set_memo(&mut self, memo: Memo) -> Result<&mut Self, String> {
+        let memo = memo.to_xdr_object().ok_or("memo type is invalid")?;
+        self.memo = Some(memo);
+        Ok(self)
+    }
+
     fn set_timeout(&mut self, timeout_seconds: i64) -> Result<&mut Self, String> {
         if let Some(timebounds) = &self.time_bounds {
             if timebounds.max_time > xdr::TimePoint(0) {
@@ -147,16 +667,142 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
         self
     }
 
+    /// Makes the transaction valid for `duration` from now, rounded down to whole seconds.
+    ///
+    /// Fails if the duration is shorter than a second or the time bounds already have a
+    /// `max_time`.
+    fn set_valid_for(&mut self, duration: Duration) -> Result<&mut Self, String> {
+        if duration.as_secs() == 0 {
+            return Err("duration must be at least one second".to_string());
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Error getting current time: {}", e))?;
+        let deadline = now.checked_add(duration).ok_or("duration is too large")?;
+        self.set_valid_until(UNIX_EPOCH + deadline)
+    }
+
+    /// Makes the transaction valid until `deadline`, rounded down to whole seconds.
+    ///
+    /// Anything converting into a [SystemTime] is accepted, such as a `chrono::DateTime`.
+    /// Fails if the deadline is in the past, before the `min_time` of the time bounds, or if
+    /// they already have a `max_time`.
+    fn set_valid_until(&mut self, deadline: impl Into<SystemTime>) -> Result<&mut Self, String> {
+        let min_time = match &self.time_bounds {
+            Some(time_bounds) if time_bounds.max_time > xdr::TimePoint(0) => {
+                return Err("TimeBounds.max_time has been already set".to_string());
+            }
+            Some(time_bounds) => time_bounds.min_time.0,
+            None => 0,
+        };
+        let deadline = deadline.into();
+        if deadline < SystemTime::now() {
+            return Err("deadline is in the past".to_string());
+        }
+        let max_time = deadline
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| "deadline is before the Unix epoch".to_string())?
+            .as_secs();
+        if max_time < min_time {
+            return Err("deadline is before TimeBounds.min_time".to_string());
+        }
+
+        self.time_bounds = Some(xdr::TimeBounds {
+            min_time: xdr::TimePoint(min_time),
+            max_time: xdr::TimePoint(max_time),
+        });
+        Ok(self)
+    }
+
+    /// Sets the ledger range in which the transaction is valid. A `max_ledger` of 0 means no
+    /// upper bound.
+    fn set_ledger_bounds(&mut self, min_ledger: u32, max_ledger: u32) -> Result<&mut Self, String> {
+        if max_ledger != 0 && max_ledger < min_ledger {
+            return Err("min_ledger cannot be greater than max_ledger".to_string());
+        }
+
+        self.ledger_bounds = Some(xdr::LedgerBounds {
+            min_ledger,
+            max_ledger,
+        });
+        Ok(self)
+    }
+
+    /// Sets the minimum source account sequence number for the transaction to be valid. When
+    /// set, the transaction's sequence may be any value greater than the account's current
+    /// sequence, as long as the account sequence is at least `min_sequence`.
+    fn set_min_account_sequence(&mut self, min_sequence: &str) -> Result<&mut Self, String> {
+        let min_sequence = min_sequence
+            .parse::<i64>()
+            .map_err(|_| "min_account_sequence must be a valid sequence number".to_string())?;
+        if min_sequence < 0 {
+            return Err("min_account_sequence cannot be negative".to_string());
+        }
+
+        self.min_account_sequence = Some(min_sequence.to_string());
+        Ok(self)
+    }
+
+    /// Requires the source account sequence number to be at least `seconds` old before the
+    /// transaction is valid. 0 means no minimum.
+    fn set_min_account_sequence_age(&mut self, seconds: u64) -> &mut Self {
+        self.min_account_sequence_age = Some(seconds);
+        self
+    }
+
+    /// Requires at least `gap` ledgers to have closed since the source account sequence number
+    /// last changed before the transaction is valid. 0 means no minimum.
+    fn set_min_account_sequence_ledger_gap(&mut self, gap: u32) -> &mut Self {
+        self.min_account_sequence_ledger_gap = Some(gap);
+        self
+    }
+
+    /// Requires an additional signature from `signer`, given as a strkey (`G...`, `T...`, `X...`
+    /// or `P...`) or a signer key. At most [`MAX_EXTRA_SIGNERS`] can be added.
+    fn add_extra_signer<S>(&mut self, signer: S) -> Result<&mut Self, String>
+    where
+        S: TryInto<SignerKey>,
+        S::Error: std::fmt::Display,
+    {
+        let signer = signer
+            .try_into()
+            .map_err(|e| format!("invalid extra signer: {}", e))?;
+        let signer =
+            xdr::SignerKey::try_from(signer).map_err(|e| format!("invalid extra signer: {}", e))?;
+
+        let extra_signers = self.extra_signers.get_or_insert_with(Vec::new);
+        if extra_signers.len() >= MAX_EXTRA_SIGNERS {
+            return Err(format!(
+                "a transaction can have at most {} extra signers",
+                MAX_EXTRA_SIGNERS
+            ));
+        }
+        extra_signers.push(signer);
+        Ok(self)
+    }
+
     fn set_soroban_data(&mut self, soroban_data: xdr::SorobanTransactionData) -> &mut Self {
         self.soroban_data = Some(soroban_data);
         self
     }
 
+    /// Sets the Soroban data from base64 XDR, panicking if it is not valid, see
+    /// [try_set_soroban_data_from_xdr_base64](Self::try_set_soroban_data_from_xdr_base64).
     fn set_soroban_data_from_xdr_base64(&mut self, soroban_data: &str) -> &mut Self {
-        let data = xdr::SorobanTransactionData::from_xdr_base64(soroban_data, xdr::Limits::none())
-            .unwrap();
+        self.try_set_soroban_data_from_xdr_base64(soroban_data)
+            .unwrap()
+    }
+
+    /// Sets the Soroban data from base64 XDR, decoded with the XDR limits of the builder
+    /// config.
+    fn try_set_soroban_data_from_xdr_base64(
+        &mut self,
+        soroban_data: &str,
+    ) -> Result<&mut Self, xdr::Error> {
+        let limits = self.xdr_limits.clone().unwrap_or(xdr::Limits::none());
+        let data = xdr::SorobanTransactionData::from_xdr_base64(soroban_data, limits)?;
         self.soroban_data = Some(data);
-        self
+        Ok(self)
     }
 
     fn clear_operations(&mut self) -> &mut Self {
@@ -164,69 +810,93 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
         self
     }
 
-    fn build(&mut self) -> Transaction {
-        let source = self.source.as_mut().expect("Source account not set");
+    /// Sets whether the resource fee declared in the soroban data is added to the inclusion
+    /// fee when building, which is the default. Disable it to set the total fee manually.
+    fn include_resource_fee(&mut self, include: bool) -> &mut Self {
+        self.include_resource_fee = include;
+        self
+    }
 
-        // Increment the sequence number directly on the mutable reference
-        source.increment_sequence_number();
+    /// Sets strict mode: every build checks the transaction against the constraints
+    /// stellar-core enforces, such as positive amounts, valid asset codes and weights, so that
+    /// malformed transactions are caught before submission. [try_build](Self::try_build)
+    /// reports all violations at once, and
+    /// [build_for_simulation](Self::build_for_simulation) panics with them.
+    fn strict(&mut self, strict: bool) -> &mut Self {
+        self.strict = strict;
+        self
+    }
 
-        let fee = self
-            .fee
-            .unwrap()
-            .checked_mul(self.operations.clone().unwrap().len().try_into().unwrap());
-        let account_id = source.account_id();
-        let sequence_number = source.sequence_number();
+    /// Builds transactions with the next sequence number of the source account without
+    /// incrementing it, so that the account is not mutated by building.
+    fn no_increment(&mut self) -> &mut Self {
+        self.no_increment = true;
+        self
+    }
 
-        let ext_on_the_fly = if self.soroban_data.is_some() {
-            xdr::TransactionExt::V1(self.soroban_data.clone().unwrap())
-        } else {
-            xdr::TransactionExt::V0
-        };
-        let vv = decode_address_to_muxed_account_fix_for_g_address(&account_id);
+    /// Builds the transaction like [try_build](Self::try_build).
+    ///
+    /// # Panics
+    ///
+    /// If the transaction cannot be built, with the [BuildError] reported by `try_build`.
+    fn build(&mut self) -> Transaction {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        let tx_cond = if let Some(tb) = self.time_bounds.clone() {
-            xdr::Preconditions::Time(tb)
-        } else {
-            xdr::Preconditions::None
-        };
-        let envelope_type = if self.soroban_data.is_some() {
-            xdr::EnvelopeType::Tx
-        } else {
-            xdr::EnvelopeType::TxV0
-        };
+    /// Builds the transaction and increments the source account sequence number, unless it has
+    /// more than [MAX_OPERATIONS] operations or its total fee overflows.
+    ///
+    /// In [strict](Self::strict) mode, the transaction is also checked against the protocol
+    /// constraints and all violations are reported at once.
+    /// With [no_increment](Self::no_increment), the source account is left untouched.
+    fn try_build(&mut self) -> Result<Transaction, BuildError> {
+        self.build_at(None)
+    }
 
-        let tx_obj = xdr::Transaction {
-            source_account: vv,
-            fee: fee.unwrap(),
-            seq_num: xdr::SequenceNumber(
-                sequence_number
-                    .parse()
-                    .unwrap_or_else(|_| panic!("Number too large for i64")),
-            ),
-            cond: tx_cond,
-            memo: self.memo.clone().unwrap_or(xdr::Memo::None),
-            operations: self.operations.clone().unwrap().try_into().unwrap(),
-            ext: ext_on_the_fly,
-        };
-        Transaction {
-            //tx: Some(tx_obj),
-            network_passphrase: self.network_passphrase.clone().unwrap(),
-            signatures: Vec::new(),
-            fee: fee.unwrap(),
-            envelope_type: xdr::EnvelopeType::Tx,
-            memo: self.memo.clone(),
-            sequence: Some(sequence_number),
-            source: Some(account_id.to_string()),
-            time_bounds: self.time_bounds.clone(),
-            ledger_bounds: None,
-            min_account_sequence: Some("0".to_string()),
-            min_account_sequence_age: Some(0),
-            min_account_sequence_ledger_gap: Some(0),
-            extra_signers: Some(Vec::new()),
-            operations: self.operations.clone(),
-            hash: None,
-            soroban_data: self.soroban_data.clone(),
-            //tx_v0: None,
+    /// Builds the transaction with the sequence number `sequence`, leaving the source account
+    /// untouched, e.g. to pre-sign transactions to be submitted later.
+    fn build_with_sequence(&mut self, sequence: i64) -> Result<Transaction, BuildError> {
+        self.build_at(Some(sequence))
+    }
+
+    /// Sets the timeout, builds the transaction and signs it with `signers`.
+    ///
+    /// Returns the signed transaction along with its envelope in base64 and its hash in hex,
+    /// ready for submission. The timeout and the sequence number of the source account are
+    /// only kept once signing succeeds, so a failed call can be retried.
+    fn build_and_sign(
+        &mut self,
+        signers: &[Keypair],
+        timeout_seconds: i64,
+    ) -> Result<(Transaction, String, String), Box<dyn Error>> {
+        let time_bounds = self.time_bounds.clone();
+        let no_increment = self.no_increment;
+
+        let result = (|| -> Result<(Transaction, String, String), Box<dyn Error>> {
+            self.set_timeout(timeout_seconds)?;
+            self.no_increment = true;
+            let mut transaction = self.try_build()?;
+            transaction.sign(signers)?;
+
+            let envelope = transaction
+                .to_envelope()?
+                .to_xdr_base64(self.xdr_limits.clone().unwrap_or(xdr::Limits::none()))?;
+            let hash = transaction.hash().to_string();
+            Ok((transaction, envelope, hash))
+        })();
+
+        self.no_increment = no_increment;
+        match result {
+            Ok(signed) => {
+                if let Some(source) = self.source.as_mut().filter(|_| !no_increment) {
+                    source.increment_sequence_number();
+                }
+                Ok(signed)
+            }
+            Err(e) => {
+                self.time_bounds = time_bounds;
+                Err(e)
+            }
         }
     }
 
@@ -236,7 +906,7 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
     /// It should be used when you only want to simulate a transaction and not actually submit it
     /// to the network.
     ///
-    /// Unlike [`build()`](TransactionBuilderBehavior::build), this method:
+    /// Unlike [`try_build()`](TransactionBuilderBehavior::try_build), this method:
     /// - Does NOT increment the source account's sequence number
     /// - Only requires an immutable reference to self (`&self` instead of `&mut self`)
     /// - Is safe to use for read-only operations like transaction simulation
@@ -249,7 +919,7 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
     /// - You need to preview transaction fees or resource requirements
     /// - You want to build multiple "what-if" scenarios without affecting account state
     ///
-    /// Use `build()` when:
+    /// Use `try_build()` when:
     /// - You're building a transaction to actually submit to the network
     /// - You want the account's sequence number to be incremented
     ///
@@ -274,12 +944,18 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
     /// // You can now simulate: rpc.simulate_transaction(&tx_for_simulation, None).await?;
     ///
     /// // For actual submission - increments the sequence number
-    /// let tx_for_submission = builder.build();
+    /// let tx_for_submission = builder.try_build()?;
     /// // source_account sequence number is now incremented
     /// # Ok(())
     /// # }
     /// ```
     fn build_for_simulation(&self) -> Transaction {
+        if self.strict {
+            let violations = self.protocol_violations();
+            if !violations.is_empty() {
+                panic!("{}", BuildError::ProtocolViolations(violations));
+            }
+        }
         let source = self.source.as_ref().expect("Source account not set");
 
         // Calculate the next sequence number (current + 1) without mutating the account
@@ -288,12 +964,12 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
             .parse()
             .expect("Invalid sequence number");
         let next_sequence_number = (current_seq + 1).to_string();
-        let account_id = source.account_id();
+        let account_id = self
+            .muxed_source
+            .clone()
+            .unwrap_or_else(|| source.account_id());
 
-        let fee = self
-            .fee
-            .unwrap()
-            .checked_mul(self.operations.clone().unwrap().len().try_into().unwrap());
+        let fee = self.total_fee().unwrap_or_else(|e| panic!("{}", e));
 
         let ext_on_the_fly = if self.soroban_data.is_some() {
             xdr::TransactionExt::V1(self.soroban_data.clone().unwrap())
@@ -315,7 +991,7 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
 
         let tx_obj = xdr::Transaction {
             source_account: vv,
-            fee: fee.unwrap(),
+            fee,
             seq_num: xdr::SequenceNumber(
                 next_sequence_number
                     .parse()
@@ -330,19 +1006,19 @@ impl<'a> TransactionBuilderBehavior<'a> for TransactionBuilder<'a> {
             //tx: Some(tx_obj),
             network_passphrase: self.network_passphrase.clone().unwrap(),
             signatures: Vec::new(),
-            fee: fee.unwrap(),
+            fee,
             envelope_type: xdr::EnvelopeType::Tx,
             memo: self.memo.clone(),
             sequence: Some(next_sequence_number),
             source: Some(account_id.to_string()),
             time_bounds: self.time_bounds.clone(),
-            ledger_bounds: None,
-            min_account_sequence: Some("0".to_string()),
-            min_account_sequence_age: Some(0),
-            min_account_sequence_ledger_gap: Some(0),
-            extra_signers: Some(Vec::new()),
+            ledger_bounds: self.ledger_bounds.clone(),
+            min_account_sequence: self.min_account_sequence.clone(),
+            min_account_sequence_age: self.min_account_sequence_age,
+            min_account_sequence_ledger_gap: self.min_account_sequence_ledger_gap,
+            extra_signers: self.extra_signers.clone(),
             operations: self.operations.clone(),
-            hash: None,
+            fee_bump: None,
             soroban_data: self.soroban_data.clone(),
             //tx_v0: None,
         }
@@ -394,11 +1070,12 @@ mod tests {
                     .create_account(destination, 10 * operation::ONE)
                     .unwrap(),
             )
-            .build();
+            .try_build()
+            .unwrap();
 
-        tx.sign(&[signer.clone()]);
+        tx.sign(std::slice::from_ref(&signer)).unwrap();
         let sig = &tx.signatures[0].signature.0;
-        let verified = signer.verify(&tx.hash(), sig);
+        let verified = signer.verify(tx.hash().as_ref(), sig);
         assert!(verified);
     }
 
@@ -427,7 +1104,7 @@ mod tests {
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap();
 
-        let transaction = builder.build();
+        let transaction = builder.try_build().unwrap();
 
         assert_eq!(transaction.source, Some(source.account_id().to_string()));
         assert_eq!(transaction.sequence.unwrap(), "1");
@@ -467,7 +1144,7 @@ mod tests {
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap();
 
-        let transaction = builder.build();
+        let transaction = builder.try_build().unwrap();
 
         assert_eq!(transaction.source, Some(source.account_id().to_string()));
         assert_eq!(transaction.sequence.unwrap(), "1");
@@ -507,7 +1184,8 @@ mod tests {
             )
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap()
-            .build();
+            .try_build()
+            .unwrap();
 
         // Assert that the total fee is 2000 stroops (1000 per operation, 2 operations)
         assert_eq!(transaction.fee, 2000);
@@ -541,7 +1219,7 @@ mod tests {
         // Set the timebounds
         builder.time_bounds = Some(timebounds.clone());
 
-        let transaction = builder.build();
+        let transaction = builder.try_build().unwrap();
 
         assert_eq!(
             transaction.time_bounds.as_ref().unwrap().min_time,
@@ -568,7 +1246,7 @@ mod tests {
         let mut soroban_data_builder = SorobanDataBuilder::new(None);
         soroban_data_builder
             .set_resources(0, 5, 0)
-            .set_refundable_fee(1);
+            .set_resource_fee(1);
         let soroban_transaction_data = soroban_data_builder.build();
 
         let contract_id = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
@@ -596,43 +1274,1007 @@ mod tests {
             .set_soroban_data(soroban_transaction_data.clone())
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap()
-            .build();
+            .try_build()
+            .unwrap();
 
         // Assert
+        assert_eq!(
+            transaction.fee as i64,
+            100 + soroban_transaction_data.resource_fee
+        );
         assert_eq!(transaction.soroban_data, Some(soroban_transaction_data));
         assert_eq!(transaction.operations.unwrap().len(), 1);
-        assert_eq!(transaction.fee, 100);
     }
 
     #[test]
-    fn test_set_soroban_data_from_xdr() {
-        // Arrange
+    fn test_ledger_bounds() {
         let mut source = Account::new(
             "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
             "0",
         )
         .unwrap();
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_ledger_bounds(100, 200)
+            .unwrap()
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
 
-        let contract_id = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
-        let binding = hex::encode(contract_id);
-        let hex_id = binding.as_bytes();
-        let mut array = [0u8; 32];
-        array.copy_from_slice(&hex_id[0..32]);
+        let bounds = xdr::LedgerBounds {
+            min_ledger: 100,
+            max_ledger: 200,
+        };
+        assert_eq!(transaction.ledger_bounds, Some(bounds.clone()));
 
-        let func = HostFunction::InvokeContract(InvokeContractArgs {
-            contract_address: Contract(xdr::ContractId(Hash::from(array))),
-            function_name: ScSymbol::from(xdr::StringM::from_str("hello").unwrap()),
-            args: vec![xdr::ScVal::String(ScString::from(
-                xdr::StringM::from_str("world").unwrap(),
-            ))]
-            .try_into()
-            .unwrap(),
-        });
+        let envelope = transaction.to_envelope().unwrap();
+        let xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope { tx, .. }) = &envelope else {
+            panic!("unexpected envelope type");
+        };
+        let xdr::Preconditions::V2(preconditions) = &tx.cond else {
+            panic!("expected V2 preconditions");
+        };
+        assert_eq!(preconditions.ledger_bounds, Some(bounds));
+        assert_eq!(preconditions.time_bounds, transaction.time_bounds);
 
-        let mut soroban_data_builder = SorobanDataBuilder::new(None);
+        let parsed = Transaction::try_from_xdr_envelope(
+            &envelope.to_xdr_base64(xdr::Limits::none()).unwrap(),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(parsed.hash(), transaction.hash());
+    }
+
+    #[test]
+    fn test_ledger_bounds_errors() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        assert!(builder.set_ledger_bounds(200, 100).is_err());
+        assert!(builder.set_ledger_bounds(200, 0).is_ok());
+    }
+
+    #[test]
+    fn test_min_account_sequence() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_min_account_sequence("5")
+            .unwrap()
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        assert_eq!(transaction.min_account_sequence, Some("5".to_string()));
+
+        let envelope = transaction.to_envelope().unwrap();
+        let xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope { tx, .. }) = &envelope else {
+            panic!("unexpected envelope type");
+        };
+        let xdr::Preconditions::V2(preconditions) = &tx.cond else {
+            panic!("expected V2 preconditions");
+        };
+        assert_eq!(preconditions.min_seq_num, Some(xdr::SequenceNumber(5)));
+        assert_eq!(preconditions.ledger_bounds, None);
+
+        let parsed = Transaction::try_from_xdr_envelope(
+            &envelope.to_xdr_base64(xdr::Limits::none()).unwrap(),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(parsed.min_account_sequence, Some("5".to_string()));
+        assert_eq!(parsed.hash(), transaction.hash());
+    }
+
+    #[test]
+    fn test_min_account_sequence_age_and_ledger_gap() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_min_account_sequence_age(3600)
+            .set_min_account_sequence_ledger_gap(5)
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let envelope = transaction.to_envelope().unwrap();
+        let xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope { tx, .. }) = &envelope else {
+            panic!("unexpected envelope type");
+        };
+        let xdr::Preconditions::V2(preconditions) = &tx.cond else {
+            panic!("expected V2 preconditions");
+        };
+        assert_eq!(preconditions.min_seq_age, xdr::Duration(3600));
+        assert_eq!(preconditions.min_seq_ledger_gap, 5);
+        assert_eq!(preconditions.min_seq_num, None);
+
+        let parsed = Transaction::try_from_xdr_envelope(
+            &envelope.to_xdr_base64(xdr::Limits::none()).unwrap(),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(parsed.min_account_sequence_age, Some(3600));
+        assert_eq!(parsed.min_account_sequence_ledger_gap, Some(5));
+        assert_eq!(parsed.hash(), transaction.hash());
+    }
+
+    #[test]
+    fn test_extra_signers() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let signed_payload =
+            stellar_strkey::Strkey::SignedPayloadEd25519(stellar_strkey::ed25519::SignedPayload {
+                ed25519: [1; 32],
+                payload: vec![2; 8],
+            })
+            .to_string();
+        let hash_x = xdr::SignerKey::HashX(xdr::Uint256([3; 32]));
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_extra_signer(signed_payload.as_str())
+            .unwrap()
+            .add_extra_signer(hash_x.clone())
+            .unwrap();
+        assert!(builder
+            .add_extra_signer("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ")
+            .is_err());
+        let transaction = builder
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let expected = vec![
+            xdr::SignerKey::Ed25519SignedPayload(xdr::SignerKeyEd25519SignedPayload {
+                ed25519: xdr::Uint256([1; 32]),
+                payload: vec![2; 8].try_into().unwrap(),
+            }),
+            hash_x,
+        ];
+        let envelope = transaction.to_envelope().unwrap();
+        let xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope { tx, .. }) = &envelope else {
+            panic!("unexpected envelope type");
+        };
+        let xdr::Preconditions::V2(preconditions) = &tx.cond else {
+            panic!("expected V2 preconditions");
+        };
+        assert_eq!(preconditions.extra_signers.to_vec(), expected);
+
+        let parsed = Transaction::try_from_xdr_envelope(
+            &envelope.to_xdr_base64(xdr::Limits::none()).unwrap(),
+            Networks::testnet(),
+        )
+        .unwrap();
+        assert_eq!(parsed.extra_signers, Some(expected));
+        assert_eq!(parsed.hash(), transaction.hash());
+    }
+
+    #[test]
+    fn test_extra_signer_errors() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        assert!(builder.add_extra_signer("GNOTAKEY").is_err());
+        assert!(builder
+            .add_extra_signer("CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE")
+            .is_err());
+    }
+
+    #[test]
+    fn test_typed_memos() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap();
+
+        let memos = [
+            (
+                builder.add_memo_text("hello").unwrap().try_build().unwrap(),
+                xdr::Memo::Text("hello".try_into().unwrap()),
+            ),
+            (
+                builder.add_memo_id(u64::MAX).try_build().unwrap(),
+                xdr::Memo::Id(u64::MAX),
+            ),
+            (
+                builder.add_memo_hash([1; 32]).try_build().unwrap(),
+                xdr::Memo::Hash(xdr::Hash([1; 32])),
+            ),
+            (
+                builder.add_memo_return([2; 32]).try_build().unwrap(),
+                xdr::Memo::Return(xdr::Hash([2; 32])),
+            ),
+        ];
+        for (transaction, memo) in memos {
+            assert_eq!(transaction.memo, Some(memo.clone()));
+            let xdr::TransactionEnvelope::Tx(envelope) = transaction.to_envelope().unwrap() else {
+                panic!("unexpected envelope type");
+            };
+            assert_eq!(envelope.tx.memo, memo);
+        }
+
+        assert!(builder
+            .add_memo_text("this memo is definitely too long")
+            .is_err());
+    }
+
+    #[test]
+    fn test_set_memo() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap();
+
+        let memos = [
+            (
+                Memo::text("hello"),
+                xdr::Memo::Text("hello".try_into().unwrap()),
+            ),
+            (Memo::id("42"), xdr::Memo::Id(42)),
+            (
+                Memo::hash_buffer(vec![10; 32]),
+                xdr::Memo::Hash(xdr::Hash([10; 32])),
+            ),
+            (
+                Memo::return_hash(hex::encode([11; 32]).into_bytes()),
+                xdr::Memo::Return(xdr::Hash([11; 32])),
+            ),
+            (Memo::none(), xdr::Memo::None),
+        ];
+        for (memo, expected) in memos {
+            let transaction = builder.set_memo(memo).unwrap().try_build().unwrap();
+            assert_eq!(transaction.memo, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_from_transaction() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let transaction = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(150_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_operation(Operation::new().bump_sequence(2).unwrap())
+            .add_memo_id(42)
+            .set_ledger_bounds(10, 20)
+            .unwrap()
+            .set_min_account_sequence_ledger_gap(3)
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let rebuilt = TransactionBuilder::from_transaction(&mut source, &transaction)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(rebuilt.sequence, Some("12".to_string()));
+        assert_eq!(rebuilt.fee, transaction.fee);
+        assert_eq!(rebuilt.memo, transaction.memo);
+        assert_eq!(rebuilt.time_bounds, transaction.time_bounds);
+        assert_eq!(rebuilt.ledger_bounds, transaction.ledger_bounds);
+        assert_eq!(rebuilt.min_account_sequence_ledger_gap, Some(3));
+        assert_eq!(rebuilt.operations, transaction.operations);
+
+        let bumped = TransactionBuilder::from_transaction(&mut source, &transaction)
+            .unwrap()
+            .fee(200_u32)
+            .try_build()
+            .unwrap();
+        assert_eq!(bumped.fee, 400);
+
+        let mut other = Account::new(
+            "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2",
+            "10",
+        )
+        .unwrap();
+        assert!(TransactionBuilder::from_transaction(&mut other, &transaction).is_err());
+    }
+
+    #[test]
+    fn test_muxed_source() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let muxed_address = xdr::MuxedAccount::MuxedEd25519(xdr::MuxedAccountMed25519 {
+            id: 420,
+            ed25519: xdr::Uint256(
+                stellar_strkey::ed25519::PublicKey::from_string(&source.account_id())
+                    .unwrap()
+                    .0,
+            ),
+        })
+        .to_string();
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_muxed_source(&muxed_address)
+            .unwrap()
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap();
+        assert_eq!(
+            builder.build_for_simulation().source,
+            Some(muxed_address.clone())
+        );
+        let transaction = builder.try_build().unwrap();
+        assert_eq!(transaction.source, Some(muxed_address.clone()));
+        assert_eq!(transaction.sequence, Some("11".to_string()));
+
+        let xdr::TransactionEnvelope::Tx(envelope) = transaction.to_envelope().unwrap() else {
+            panic!("unexpected envelope type");
+        };
+        let xdr::MuxedAccount::MuxedEd25519(muxed) = envelope.tx.source_account else {
+            panic!("expected a muxed source account");
+        };
+        assert_eq!(muxed.id, 420);
+
+        let rebuilt = TransactionBuilder::from_transaction(&mut source, &transaction)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(rebuilt.source, Some(muxed_address));
+    }
+
+    #[test]
+    fn test_muxed_source_errors() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let other = xdr::MuxedAccount::MuxedEd25519(xdr::MuxedAccountMed25519 {
+            id: 1,
+            ed25519: xdr::Uint256([1; 32]),
+        })
+        .to_string();
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        assert!(builder.set_muxed_source(&other).is_err());
+        assert!(builder
+            .set_muxed_source("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ")
+            .is_err());
+        assert!(builder.set_muxed_source("MNOTANADDRESS").is_err());
+    }
+
+    #[test]
+    fn test_try_build_errors() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder.fee(100_u32).set_timeout(TIMEOUT_INFINITE).unwrap();
+        for i in 0..=MAX_OPERATIONS {
+            builder.add_operation(Operation::new().bump_sequence(i as i64).unwrap());
+        }
+        assert_eq!(
+            builder.try_build().unwrap_err(),
+            BuildError::TooManyOperations(MAX_OPERATIONS + 1)
+        );
+
+        builder.clear_operations().fee(u32::MAX / 2 + 1);
+        builder
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_operation(Operation::new().bump_sequence(2).unwrap());
+        assert_eq!(
+            builder.try_build().unwrap_err(),
+            BuildError::FeeOverflow {
+                base_fee: u32::MAX / 2 + 1,
+                operations: 2
+            }
+        );
+
+        builder.fee(100_u32);
+        assert_eq!(builder.try_build().unwrap().fee, 200);
+        assert_eq!(source.sequence_number(), "11");
+    }
+
+    #[test]
+    fn test_try_build_missing_fee_and_invalid_sequence() {
+        let op = Operation::new().bump_sequence(1).unwrap();
+        let mut builder = TransactionBuilder::with_source(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            i64::MAX,
+            Networks::testnet(),
+        )
+        .unwrap();
+        builder.add_operation(op.clone());
+        assert_eq!(builder.try_build().unwrap_err(), BuildError::MissingFee);
+
+        builder.fee(100_u32);
+        assert_eq!(
+            builder.try_build().unwrap_err(),
+            BuildError::InvalidSequence(i64::MAX.to_string())
+        );
+        assert_eq!(
+            builder.no_increment().try_build().unwrap_err(),
+            BuildError::InvalidSequence(i64::MAX.to_string())
+        );
+        assert_eq!(
+            builder.build_with_sequence(7).unwrap().sequence.unwrap(),
+            "7"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 100 operations")]
+    #[allow(deprecated)]
+    fn test_build_panics_on_too_many_operations() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder.fee(100_u32);
+        for i in 0..=MAX_OPERATIONS {
+            builder.add_operation(Operation::new().bump_sequence(i as i64).unwrap());
+        }
+        builder.build();
+    }
+
+    #[test]
+    fn test_add_operations() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .add_operation(Operation::new().bump_sequence(0).unwrap());
+
+        let operations = (1..MAX_OPERATIONS as i64)
+            .map(|i| Operation::new().bump_sequence(i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            builder
+                .add_operations(operations.iter().cloned().chain(operations.clone()))
+                .err(),
+            Some(BuildError::TooManyOperations(1 + 2 * (MAX_OPERATIONS - 1)))
+        );
+
+        let transaction = builder
+            .add_operations(operations)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        let transaction_ops = transaction.operations.unwrap();
+        assert_eq!(transaction_ops.len(), MAX_OPERATIONS);
+        assert_eq!(
+            transaction_ops[MAX_OPERATIONS - 1],
+            Operation::new()
+                .bump_sequence(MAX_OPERATIONS as i64 - 1)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_insert_and_replace_operation() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let sponsored = "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2";
+        let begin = Operation::new()
+            .begin_sponsoring_future_reserves(sponsored)
+            .unwrap();
+        let end = Operation::with_source(sponsored)
+            .unwrap()
+            .end_sponsoring_future_reserves()
+            .unwrap();
+        let bump = Operation::new().bump_sequence(1).unwrap();
+        let payment = Operation::new()
+            .payment(sponsored, &Asset::native(), 10)
+            .unwrap();
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .add_operation(bump.clone());
+        builder
+            .insert_operation(0, begin.clone())
+            .unwrap()
+            .insert_operation(2, end.clone())
+            .unwrap()
+            .replace_operation(1, payment.clone())
+            .unwrap();
+        assert!(builder.insert_operation(4, bump.clone()).is_err());
+        assert!(builder.replace_operation(3, bump).is_err());
+
+        let transaction = builder.try_build().unwrap();
+        assert_eq!(transaction.operations, Some(vec![begin, payment, end]));
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let destination = "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2";
+        let mut bad_payment = Operation::new()
+            .payment(destination, &Asset::native(), 10)
+            .unwrap();
+        if let xdr::OperationBody::Payment(op) = &mut bad_payment.body {
+            op.amount = 0;
+            op.asset = xdr::Asset::CreditAlphanum4(xdr::AlphaNum4 {
+                asset_code: xdr::AssetCode4(*b"U\0SD"),
+                issuer: xdr::AccountId::from_str(destination).unwrap(),
+            });
+        }
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(10_u32)
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_operation(bad_payment);
+
+        // Without strict mode, the transaction is built as is.
+        assert!(builder.try_build().is_ok());
+
+        let Err(BuildError::ProtocolViolations(violations)) = builder.strict(true).try_build()
+        else {
+            panic!("expected protocol violations");
+        };
+        assert_eq!(
+            violations,
+            vec![
+                "base fee must be at least 100, got 10".to_string(),
+                "operation 1: asset has an invalid asset code \"U\\0SD\"".to_string(),
+                "operation 1: amount must be positive, got 0".to_string(),
+            ]
+        );
+
+        builder.clear_operations();
+        builder.fee(100_u32);
+        let Err(BuildError::ProtocolViolations(violations)) = builder.try_build() else {
+            panic!("expected protocol violations");
+        };
+        assert_eq!(
+            violations,
+            vec!["transaction has no operations".to_string()]
+        );
+
+        builder.add_operation(Operation::new().bump_sequence(1).unwrap());
+        assert!(builder.try_build().is_ok());
+        assert_eq!(source.sequence_number(), "12");
+    }
+
+    #[test]
+    fn test_strict_mode_checks_pool_shares() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let usd = Asset::new(
+            "USD",
+            Some("GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2"),
+        )
+        .unwrap();
+        let pool_share = |asset_a: &Asset, asset_b: &Asset, fee: i32| xdr::Operation {
+            source_account: None,
+            body: xdr::OperationBody::ChangeTrust(xdr::ChangeTrustOp {
+                line: xdr::ChangeTrustAsset::PoolShare(
+                    xdr::LiquidityPoolParameters::LiquidityPoolConstantProduct(
+                        xdr::LiquidityPoolConstantProductParameters {
+                            asset_a: asset_a.to_xdr_object(),
+                            asset_b: asset_b.to_xdr_object(),
+                            fee,
+                        },
+                    ),
+                ),
+                limit: 1000,
+            }),
+        };
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .strict(true)
+            .add_operation(pool_share(&usd, &Asset::native(), 31));
+        let Err(BuildError::ProtocolViolations(violations)) = builder.try_build() else {
+            panic!("expected protocol violations");
+        };
+        assert_eq!(
+            violations,
+            vec![
+                "operation 0: line.fee must be 30, got 31".to_string(),
+                "operation 0: line.asset_a must sort strictly before line.asset_b".to_string(),
+            ]
+        );
+
+        builder.clear_operations();
+        builder.add_operation(pool_share(&Asset::native(), &usd, 30));
+        assert!(builder.try_build().is_ok());
+        assert!(builder.strict(false).try_build().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "transaction has no operations")]
+    fn test_strict_build_for_simulation_panics() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder.fee(100_u32).strict(true);
+        builder.build_for_simulation();
+    }
+
+    #[test]
+    fn test_resource_fee() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let soroban_data = SorobanDataBuilder::new(None).set_resource_fee(5000).build();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().restore_footprint().unwrap())
+            .set_soroban_data(soroban_data)
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap();
+
+        assert_eq!(builder.build_for_simulation().fee, 5100);
+        let transaction = builder.try_build().unwrap();
+        assert_eq!(transaction.fee, 5100);
+        assert_eq!(
+            builder.include_resource_fee(false).try_build().unwrap().fee,
+            100
+        );
+
+        let rebuilt = TransactionBuilder::from_transaction(&mut source, &transaction)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(rebuilt.fee, 5100);
+
+        let mut negative = transaction.clone();
+        negative.soroban_data.as_mut().unwrap().resource_fee = -1;
+        assert!(TransactionBuilder::from_transaction(&mut source, &negative).is_err());
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().restore_footprint().unwrap())
+            .set_soroban_data(negative.soroban_data.unwrap());
+        assert_eq!(
+            builder.try_build().unwrap_err(),
+            BuildError::InvalidResourceFee(-1)
+        );
+    }
+
+    #[test]
+    fn test_build_and_sign() {
+        let signer = Keypair::random().unwrap();
+        let mut source = Account::new(&signer.public_key(), "10").unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap());
+
+        let (transaction, envelope, hash) = builder
+            .build_and_sign(std::slice::from_ref(&signer), 30)
+            .unwrap();
+        assert_eq!(transaction.signatures.len(), 1);
+        assert!(transaction.time_bounds.as_ref().unwrap().max_time.0 > 0);
+        assert_eq!(hash, transaction.hash().to_string());
+        assert!(crate::transaction::verify_envelope_signature(
+            &envelope,
+            Networks::testnet(),
+            &signer.public_key()
+        )
+        .unwrap());
+
+        // The timeout cannot be set again once the time bounds are set.
+        assert!(builder.build_and_sign(&[signer], 30).is_err());
+        assert_eq!(source.sequence_number(), "11");
+    }
+
+    #[test]
+    fn test_build_and_sign_failure_keeps_builder() {
+        let signer = Keypair::random().unwrap();
+        let public_only = Keypair::from_public_key(&signer.public_key()).unwrap();
+        let mut source = Account::new(&signer.public_key(), "10").unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder.fee(100_u32).strict(true);
+
+        // Neither a failed build nor a failed signature consume the timeout or the sequence.
+        assert!(builder
+            .build_and_sign(std::slice::from_ref(&signer), 30)
+            .is_err());
+        builder.add_operation(Operation::new().bump_sequence(1).unwrap());
+        assert!(builder.build_and_sign(&[public_only], 30).is_err());
+
+        let (transaction, _, _) = builder.build_and_sign(&[signer], 30).unwrap();
+        assert_eq!(transaction.sequence.as_deref(), Some("11"));
+        assert_eq!(source.sequence_number(), "11");
+    }
+
+    #[test]
+    fn test_with_source() {
+        let account_id = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+        let mut builder =
+            TransactionBuilder::with_source(account_id, 10, Networks::testnet()).unwrap();
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap();
+
+        let first = builder.try_build().unwrap();
+        assert_eq!(first.source.as_deref(), Some(account_id));
+        assert_eq!(first.sequence.as_deref(), Some("11"));
+        assert_eq!(first.network_passphrase, Networks::testnet());
+        let second = builder.try_build().unwrap();
+        assert_eq!(second.sequence.as_deref(), Some("12"));
+
+        let muxed = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVAAAAAAAAAAAAAJLK";
+        let transaction = TransactionBuilder::with_source(muxed, 0, Networks::testnet())
+            .unwrap()
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(transaction.source.as_deref(), Some(muxed));
+        assert_eq!(transaction.sequence.as_deref(), Some("1"));
+
+        assert!(TransactionBuilder::with_source("GABC", 0, Networks::testnet()).is_err());
+    }
+
+    #[test]
+    fn test_build_without_incrementing() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "10",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        builder
+            .fee(100_u32)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .set_timeout(TIMEOUT_INFINITE)
+            .unwrap();
+
+        let presigned = (12..15)
+            .map(|sequence| builder.build_with_sequence(sequence).unwrap())
+            .map(|transaction| transaction.sequence.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(presigned, ["12", "13", "14"]);
+
+        builder.no_increment();
+        assert_eq!(builder.try_build().unwrap().sequence.as_deref(), Some("11"));
+        assert_eq!(builder.try_build().unwrap().sequence.as_deref(), Some("11"));
+        assert_eq!(source.sequence_number(), "10");
+    }
+
+    #[test]
+    fn test_valid_for_and_until() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        assert!(builder.set_valid_for(Duration::from_millis(500)).is_err());
+        builder.set_valid_for(Duration::from_secs(60)).unwrap();
+        let max_time = builder.time_bounds.as_ref().unwrap().max_time.0;
+        assert!((now + 60..=now + 65).contains(&max_time));
+        assert!(builder.set_valid_for(Duration::from_secs(60)).is_err());
+
+        let deadline = UNIX_EPOCH + Duration::from_secs(now + 3600);
+        let mut builder = TransactionBuilder::new(
+            &mut source,
+            Networks::testnet(),
+            Some(xdr::TimeBounds {
+                min_time: xdr::TimePoint(now + 10),
+                max_time: xdr::TimePoint(0),
+            }),
+        );
+        builder.set_valid_until(deadline).unwrap();
+        assert_eq!(
+            builder.time_bounds,
+            Some(xdr::TimeBounds {
+                min_time: xdr::TimePoint(now + 10),
+                max_time: xdr::TimePoint(now + 3600),
+            })
+        );
+
+        let mut builder = TransactionBuilder::new(
+            &mut source,
+            Networks::testnet(),
+            Some(xdr::TimeBounds {
+                min_time: xdr::TimePoint(now + 7200),
+                max_time: xdr::TimePoint(0),
+            }),
+        );
+        assert!(builder.set_valid_until(deadline).is_err());
+        assert!(builder
+            .set_valid_until(UNIX_EPOCH + Duration::from_secs(now - 10))
+            .is_err());
+
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        let deadline = chrono::Utc::now() + chrono::TimeDelta::hours(1);
+        builder.set_valid_until(deadline).unwrap();
+        assert_eq!(
+            builder.time_bounds.unwrap().max_time.0,
+            deadline.timestamp() as u64
+        );
+    }
+
+    #[test]
+    fn test_min_account_sequence_errors() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let mut builder = TransactionBuilder::new(&mut source, Networks::testnet(), None);
+        assert!(builder.set_min_account_sequence("abc").is_err());
+        assert!(builder.set_min_account_sequence("-1").is_err());
+        assert!(builder.set_min_account_sequence("0").is_ok());
+    }
+
+    #[test]
+    fn test_with_config() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let config = Config::new(Networks::public()).base_fee(250).timeout(60);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let transaction = TransactionBuilder::with_config(&mut source, &config)
+            .add_operation(Operation::new().bump_sequence(1).unwrap())
+            .add_operation(Operation::new().bump_sequence(2).unwrap())
+            .try_build()
+            .unwrap();
+
+        assert_eq!(transaction.network_passphrase, Networks::public());
+        assert_eq!(transaction.fee, 500);
+        let max_time = transaction.time_bounds.unwrap().max_time.0;
+        assert!(max_time >= now + 60 && max_time <= now + 61);
+
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let transaction =
+            TransactionBuilder::with_config(&mut source, &Config::new(Networks::testnet()))
+                .add_operation(Operation::new().bump_sequence(1).unwrap())
+                .try_build()
+                .unwrap();
+        assert_eq!(transaction.fee, crate::config::BASE_FEE);
+        assert_eq!(transaction.time_bounds, None);
+    }
+
+    #[test]
+    fn test_with_config_applies_xdr_limits() {
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let soroban_data = SorobanDataBuilder::new(None)
+            .build()
+            .to_xdr_base64(xdr::Limits::none())
+            .unwrap();
+        let config = Config::new(Networks::testnet()).xdr_limits(xdr::Limits::len(8));
+
+        let mut builder = TransactionBuilder::with_config(&mut source, &config);
+        assert!(builder
+            .try_set_soroban_data_from_xdr_base64(&soroban_data)
+            .is_err());
+
+        let config = Config::new(Networks::testnet());
+        let mut builder = TransactionBuilder::with_config(&mut source, &config);
+        assert!(builder
+            .try_set_soroban_data_from_xdr_base64(&soroban_data)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_set_soroban_data_from_xdr() {
+        // Arrange
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+
+        let contract_id = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+        let binding = hex::encode(contract_id);
+        let hex_id = binding.as_bytes();
+        let mut array = [0u8; 32];
+        array.copy_from_slice(&hex_id[0..32]);
+
+        let func = HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: Contract(xdr::ContractId(Hash::from(array))),
+            function_name: ScSymbol::from(xdr::StringM::from_str("hello").unwrap()),
+            args: vec![xdr::ScVal::String(ScString::from(
+                xdr::StringM::from_str("world").unwrap(),
+            ))]
+            .try_into()
+            .unwrap(),
+        });
+
+        let mut soroban_data_builder = SorobanDataBuilder::new(None);
         soroban_data_builder
             .set_resources(0, 5, 0)
-            .set_refundable_fee(1);
+            .set_resource_fee(1);
         let soroban_transaction_data = soroban_data_builder.build();
 
         // Act
@@ -649,12 +2291,16 @@ mod tests {
             )
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap()
-            .build();
+            .try_build()
+            .unwrap();
 
         // Assert
+        assert_eq!(
+            transaction.fee as i64,
+            100 + soroban_transaction_data.resource_fee
+        );
         assert_eq!(transaction.soroban_data, Some(soroban_transaction_data));
         assert_eq!(transaction.operations.unwrap().len(), 1);
-        assert_eq!(transaction.fee, 100);
     }
 
     #[test]
@@ -685,7 +2331,7 @@ mod tests {
         let mut soroban_data_builder = SorobanDataBuilder::new(None);
         soroban_data_builder
             .set_resources(0, 5, 0)
-            .set_refundable_fee(1);
+            .set_resource_fee(1);
         let soroban_transaction_data = soroban_data_builder.build();
 
         // Act
@@ -702,7 +2348,8 @@ mod tests {
             )
             .set_timeout(TIMEOUT_INFINITE)
             .unwrap()
-            .build();
+            .try_build()
+            .unwrap();
 
         // Assert
 
@@ -717,6 +2364,94 @@ mod tests {
         assert_eq!(inner_val, xdr::TransactionExt::V1(soroban_transaction_data));
     }
 
+    #[test]
+    fn test_build_payments_tx_matches_builder() {
+        let destination1 = "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2";
+        let destination2 = "GC6ACGSA2NJGD6YWUNX2BYBL3VM4MZRSEU2RLIUZZL35NLV5IAHAX2E2";
+        let asset = Asset::native();
+
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let expected = TransactionBuilder::new(&mut source, Networks::testnet(), None)
+            .fee(100_u32)
+            .add_operation(
+                Operation::new()
+                    .payment(destination1, &asset, 1000 * operation::ONE)
+                    .unwrap(),
+            )
+            .add_operation(
+                Operation::new()
+                    .payment(destination2, &asset, 2000 * operation::ONE)
+                    .unwrap(),
+            )
+            .try_build()
+            .unwrap();
+
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+        let transaction = build_payments_tx(
+            &mut source,
+            Networks::testnet(),
+            &[
+                (destination1, &asset, 1000 * operation::ONE),
+                (destination2, &asset, 2000 * operation::ONE),
+            ],
+            100,
+        )
+        .unwrap();
+
+        assert_eq!(source.sequence_number(), "1");
+        assert_eq!(transaction.fee, 200);
+        assert_eq!(transaction.hash(), expected.hash());
+    }
+
+    #[test]
+    fn test_build_payments_tx_errors() {
+        let destination = "GDJJRRMBK4IWLEPJGIE6SXD2LP7REGZODU7WDC3I2D6MR37F4XSHBKX2";
+        let asset = Asset::native();
+        let mut source = Account::new(
+            "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ",
+            "0",
+        )
+        .unwrap();
+
+        let result = build_payments_tx(&mut source, Networks::testnet(), &[], 100);
+        assert_eq!(
+            result.err(),
+            Some(operation::Error::InvalidField("payments".into()))
+        );
+
+        let payments = vec![(destination, &asset, operation::ONE); MAX_OPERATIONS + 1];
+        let result = build_payments_tx(&mut source, Networks::testnet(), &payments, 100);
+        assert_eq!(
+            result.err(),
+            Some(operation::Error::InvalidField("payments".into()))
+        );
+
+        let payments = vec![(destination, &asset, operation::ONE); 2];
+        let result = build_payments_tx(&mut source, Networks::testnet(), &payments, u32::MAX);
+        assert_eq!(
+            result.err(),
+            Some(operation::Error::InvalidField("base_fee".into()))
+        );
+
+        let result = build_payments_tx(
+            &mut source,
+            Networks::testnet(),
+            &[(destination, &asset, -1)],
+            100,
+        );
+        assert_eq!(result.err(), Some(operation::Error::InvalidAmount(-1)));
+
+        assert_eq!(source.sequence_number(), "0");
+    }
+
     #[test]
     fn test_build_for_simulation_does_not_increment_sequence() {
         // Arrange
@@ -777,7 +2512,7 @@ mod tests {
             .unwrap();
 
         // Act
-        let transaction = builder.build();
+        let transaction = builder.try_build().unwrap();
 
         // Assert - sequence number should be incremented
         assert_eq!(source.sequence_number(), "51");
@@ -0,0 +1,155 @@
+//! Signers whose secret key is held elsewhere, e.g. by a hardware wallet, an HSM or a remote
+//! KMS, plugged into [Transaction::sign_with](crate::transaction::TransactionBehavior::sign_with).
+//!
+//! [Keypair] implements both traits, so local and external signers can be mixed. Signatures
+//! are checked against the public key of their signer before they are attached.
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+
+use stellar_strkey::ed25519::PublicKey;
+
+use crate::keypair::Keypair;
+use crate::signing;
+use crate::xdr;
+
+/// Future returned by [AsyncTransactionSigner::sign_payload_async].
+pub type SignFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<u8>, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// Signs payloads, usually transaction hashes, with an ed25519 key.
+pub trait TransactionSigner {
+    /// The `G...` public key of the signer.
+    fn signer_public_key(&self) -> String;
+
+    /// Returns the 64 byte ed25519 signature of `data`.
+    fn sign_payload(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+
+    /// Signs `data` and decorates the signature with the hint of the public key.
+    ///
+    /// Fails if the signature is not valid for `data` and the public key.
+    fn decorated_signature(
+        &self,
+        data: &[u8],
+    ) -> Result<xdr::DecoratedSignature, Box<dyn Error + Send + Sync>> {
+        let signature = self.sign_payload(data)?;
+        decorate(&self.signer_public_key(), data, signature)
+    }
+}
+
+/// A [TransactionSigner] that signs asynchronously, e.g. through a network call.
+///
+/// Every `Sync` [TransactionSigner] is one. Signers are `Sync` so that signing futures are
+/// `Send` and can run on a multi-threaded executor.
+///
+/// Its methods are named apart from those of [TransactionSigner], so both traits can be in
+/// scope together.
+pub trait AsyncTransactionSigner: Sync {
+    /// The `G...` public key of the signer.
+    fn async_signer_public_key(&self) -> String;
+
+    /// Resolves to the 64 byte ed25519 signature of `data`.
+    fn sign_payload_async<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a>;
+}
+
+impl<T: TransactionSigner + Sync> AsyncTransactionSigner for T {
+    fn async_signer_public_key(&self) -> String {
+        self.signer_public_key()
+    }
+
+    fn sign_payload_async<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(std::future::ready(self.sign_payload(data)))
+    }
+}
+
+/// Decorates a `signature` of `data` made by `public_key` with its hint.
+///
+/// Fails if the signature is not a valid ed25519 signature of `data` by `public_key`.
+pub(crate) fn decorate(
+    public_key: &str,
+    data: &[u8],
+    signature: Vec<u8>,
+) -> Result<xdr::DecoratedSignature, Box<dyn Error + Send + Sync>> {
+    if signature.len() != 64 {
+        return Err("ed25519 signatures are 64 bytes long".into());
+    }
+    let key = PublicKey::from_string(public_key)?;
+    if !signing::verify(data, &signature, &key.0) {
+        return Err(format!("signature is not valid for {public_key}").into());
+    }
+    let hint: [u8; 4] = key.0[28..].try_into()?;
+    Ok(xdr::DecoratedSignature {
+        hint: xdr::SignatureHint(hint),
+        signature: xdr::Signature(signature.try_into()?),
+    })
+}
+
+/// Signs `data` with each of `signers` one after the other, failing with the first error.
+pub(crate) async fn decorated_signatures_async(
+    data: &[u8],
+    signers: &[&dyn AsyncTransactionSigner],
+) -> Result<Vec<xdr::DecoratedSignature>, Box<dyn Error + Send + Sync>> {
+    let mut signatures = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let signature = signer.sign_payload_async(data).await?;
+        signatures.push(decorate(
+            &signer.async_signer_public_key(),
+            data,
+            signature,
+        )?);
+    }
+    Ok(signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::KeypairBehavior;
+
+    /// Signs through a keypair it does not expose, like a remote service would.
+    struct RemoteSigner(Keypair);
+
+    impl TransactionSigner for RemoteSigner {
+        fn signer_public_key(&self) -> String {
+            self.0.public_key()
+        }
+
+        fn sign_payload(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+            self.0.sign_payload(data)
+        }
+    }
+
+    /// Claims the key of a keypair but signs with another one.
+    struct WrongKeySigner(Keypair, Keypair);
+
+    impl TransactionSigner for WrongKeySigner {
+        fn signer_public_key(&self) -> String {
+            self.0.public_key()
+        }
+
+        fn sign_payload(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+            self.1.sign_payload(data)
+        }
+    }
+
+    #[test]
+    fn test_decorated_signature_matches_keypair() {
+        let keypair = Keypair::random().unwrap();
+        let remote = RemoteSigner(keypair.clone());
+        assert_eq!(
+            remote.decorated_signature(b"payload").unwrap(),
+            keypair.sign_decorated(b"payload").unwrap()
+        );
+        assert!(decorate(&keypair.public_key(), b"payload", vec![0; 3]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_signature_of_another_key() {
+        let keypair = Keypair::random().unwrap();
+        let signer = WrongKeySigner(keypair.clone(), Keypair::random().unwrap());
+        assert!(signer.decorated_signature(b"payload").is_err());
+
+        let public_only = Keypair::from_public_key(&keypair.public_key()).unwrap();
+        assert!(public_only.decorated_signature(b"payload").is_err());
+    }
+}